@@ -15,9 +15,15 @@ _lla() {
             add)
                 cmd+="__add"
                 ;;
+            cache)
+                cmd+="__cache"
+                ;;
             clean)
                 cmd+="__clean"
                 ;;
+            clear)
+                cmd+="__clear"
+                ;;
             completion)
                 cmd+="__completion"
                 ;;
@@ -57,9 +63,15 @@ _lla() {
             plugin)
                 cmd+="__plugin"
                 ;;
+            preset)
+                cmd+="__preset"
+                ;;
             preview)
                 cmd+="__preview"
                 ;;
+            prune)
+                cmd+="__prune"
+                ;;
             pull)
                 cmd+="__pull"
                 ;;
@@ -69,9 +81,18 @@ _lla() {
             shortcut)
                 cmd+="__shortcut"
                 ;;
+            show)
+                cmd+="__show"
+                ;;
             show-effective)
                 cmd+="__show__effective"
                 ;;
+            stats)
+                cmd+="__stats"
+                ;;
+            test)
+                cmd+="__test"
+                ;;
             theme)
                 cmd+="__theme"
                 ;;
@@ -91,12 +112,20 @@ _lla() {
 
     case "${cmd}" in
         lla)
-            opts="-h -V -d -l -t -T -g -S -G -F -s -r -f -c -R -X -a -A -i -H -@ -Z -M --help --version --json --ndjson --csv --pretty --search --search-context --depth --long --tree --table --grid --grid-ignore --sizemap --timeline --git --fuzzy --icons --no-icons --hyperlink --no-color --sort --sort-reverse --sort-dirs-first --sort-case-sensitive --sort-natural --filter --preset --size --modified --created --case-sensitive --refine --enable-plugin --search-pipe --disable-plugin --plugins-dir --recursive --include-dirs --dirs-only --files-only --symlinks-only --show-symlinks --no-dirs --no-files --no-symlinks --dereference --no-symlink-target --no-dotfiles --all --almost-all --dotfiles-only --respect-gitignore --no-gitignore --permission-format --hide-group --relative-dates --date-format --inode --links --allocated-size --extended --context --mounts <directory> diff jump install plugin list-plugins use init config update upgrade clean shortcut completion theme help"
+            opts="-h -V -d -l -t -T -g -S -G -F -0 -s -r -f -c -R -X -a -A -x -i -H -@ -Z -M --help --version --json --ndjson --csv --pretty --stdin --from-file --watch --interval --search --search-context --depth --long --tree --table --grid --grid-ignore --sizemap --timeline --git --fuzzy --icons --no-icons --hyperlink --no-color --print0 --quote --sort --sort-reverse --sort-dirs-first --sort-case-sensitive --sort-natural --sort-collation --head --limit --tail --filter --fuzzy-query --fuzzy-min-score --preset --size --modified --created --accessed --newer --older --owner --group --perm --setuid --world-writable --where --field-filter --type --git-status --case-sensitive --refine --enable-plugin --search-pipe --disable-plugin --plugins-dir --recursive --format-template --format --include-dirs --dirs-only --files-only --symlinks-only --show-symlinks --no-dirs --no-files --no-symlinks --dereference --no-symlink-target --no-dotfiles --all --almost-all --dotfiles-only --respect-gitignore --no-gitignore --one-file-system --cross-file-systems --no-llaignore --explain-ignore --permission-format --hide-group --relative-dates --date-format --inode --links --allocated-size --extended --context --mounts --mime --classify <directory>... diff jump install plugin list-plugins use init config update upgrade clean cache preset shortcut completion theme help"
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 1 ]] ; then
                 COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
                 return 0
             fi
             case "${prev}" in
+                --from-file)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --interval)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
                 --search)
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
@@ -117,12 +146,32 @@ _lla() {
                     COMPREPLY=($(compgen -W "always auto automatic never" -- "${cur}"))
                     return 0
                     ;;
+                --quote)
+                    COMPREPLY=($(compgen -W "literal shell c" -- "${cur}"))
+                    return 0
+                    ;;
                 --sort)
-                    COMPREPLY=($(compgen -W "name size date" -- "${cur}"))
+                    COMPREPLY=($(compgen -W "name size date modified created accessed extension kind owner group inode links version score" -- "${cur}"))
                     return 0
                     ;;
                 -s)
-                    COMPREPLY=($(compgen -W "name size date" -- "${cur}"))
+                    COMPREPLY=($(compgen -W "name size date modified created accessed extension kind owner group inode links version score" -- "${cur}"))
+                    return 0
+                    ;;
+                --sort-collation)
+                    COMPREPLY=($(compgen -W "unicode bytes" -- "${cur}"))
+                    return 0
+                    ;;
+                --limit)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --head)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --tail)
+                    COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
                 --filter)
@@ -133,6 +182,14 @@ _lla() {
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
+                --fuzzy-query)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --fuzzy-min-score)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
                 --preset)
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
@@ -149,6 +206,46 @@ _lla() {
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
+                --accessed)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --newer)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --older)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --owner)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --group)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --perm)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --where)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --field-filter)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --type)
+                    COMPREPLY=($(compgen -W "image text binary executable archive" -- "${cur}"))
+                    return 0
+                    ;;
+                --git-status)
+                    COMPREPLY=($(compgen -W "modified untracked staged conflicted ignored" -- "${cur}"))
+                    return 0
+                    ;;
                 --refine)
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
@@ -169,6 +266,14 @@ _lla() {
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
+                --format-template)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --format)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
                 --permission-format)
                     COMPREPLY=($(compgen -W "symbolic octal binary verbose compact" -- "${cur}"))
                     return 0
@@ -184,6 +289,84 @@ _lla() {
             COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
             return 0
             ;;
+        lla__cache)
+            opts="-h --help stats clear prune help"
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 2 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
+        lla__cache__clear)
+            opts="-h --help"
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 3 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
+        lla__cache__help)
+            opts="<SUBCOMMAND>..."
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 3 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
+        lla__cache__prune)
+            opts="-h --max-age --max-size --help"
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 3 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                --max-age)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --max-size)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
+        lla__cache__stats)
+            opts="-h --help"
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 3 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
         lla__clean)
             opts="-h --help"
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 2 ]] ; then
@@ -434,6 +617,76 @@ _lla() {
             COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
             return 0
             ;;
+        lla__preset)
+            opts="-h --help list show test help"
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 2 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
+        lla__preset__help)
+            opts="<SUBCOMMAND>..."
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 3 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
+        lla__preset__list)
+            opts="-h --help"
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 3 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
+        lla__preset__show)
+            opts="-h --help <preset>"
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 3 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
+        lla__preset__test)
+            opts="-h --help <preset> <directory>"
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 3 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+            return 0
+            ;;
         lla__shortcut)
             opts="-h --help add create remove export import list help"
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 2 ]] ; then
@@ -1,18 +1,38 @@
+complete -c lla -n "__fish_use_subcommand" -l from-file -d 'Read the paths to list from a file (newline or NUL separated) instead of walking a directory' -r
+complete -c lla -n "__fish_use_subcommand" -l interval -d 'With --watch, wait this long for changes to settle before updating (default: 0.3)' -r
 complete -c lla -n "__fish_use_subcommand" -l search -d 'Search file contents with ripgrep for the given pattern' -r
 complete -c lla -n "__fish_use_subcommand" -l search-context -d 'Number of context lines to show before and after matches (default: 2)' -r
 complete -c lla -n "__fish_use_subcommand" -s d -l depth -d 'Set the depth for tree listing (default from config)' -r
 complete -c lla -n "__fish_use_subcommand" -l hyperlink -d 'Emit OSC 8 file hyperlinks (always, auto, never)' -r -f -a "{always	,auto	,automatic	,never	}"
-complete -c lla -n "__fish_use_subcommand" -s s -l sort -d 'Sort files by name, size, or date' -r -f -a "{name	,size	,date	}"
+complete -c lla -n "__fish_use_subcommand" -l quote -d 'Quote file names for scripts: literal, shell, or c (implies plain output)' -r -f -a "{literal	,shell	,c	}"
+complete -c lla -n "__fish_use_subcommand" -s s -l sort -d 'Sort files by one or more keys or sortable plugin fields (field:<name>); list keys like size,-date,name to break ties, with - flipping a key\'s direction' -r -f -a "{name	,size	,date	,modified	,created	,accessed	,extension	,kind	,owner	,group	,inode	,links	,version	,score	}"
+complete -c lla -n "__fish_use_subcommand" -l sort-collation -d 'Compare names by code point (bytes) or with accents and case set aside (unicode) (overrides config setting)' -r -f -a "{unicode	,bytes	}"
+complete -c lla -n "__fish_use_subcommand" -l limit -l head -d 'Show only the first N entries in sort order, picked without sorting the whole listing' -r
+complete -c lla -n "__fish_use_subcommand" -l tail -d 'Show only the last N entries in sort order' -r
 complete -c lla -n "__fish_use_subcommand" -s f -l filter -d 'Filter files by name or extension' -r
-complete -c lla -n "__fish_use_subcommand" -l preset -d 'Apply a named filter preset defined in your config' -r
+complete -c lla -n "__fish_use_subcommand" -l fuzzy-query -d 'Keep names that fuzzy-match QUERY, best matches first; same as --filter fuzzy:QUERY' -r
+complete -c lla -n "__fish_use_subcommand" -l fuzzy-min-score -d 'Drop fuzzy matches scoring below SCORE' -r
+complete -c lla -n "__fish_use_subcommand" -l preset -d 'Apply a named filter preset, optionally with parameters (e.g. stale:days=90)' -r
 complete -c lla -n "__fish_use_subcommand" -l size -d 'Filter by file size (e.g., \'>10M\', \'5K..2G\')' -r
 complete -c lla -n "__fish_use_subcommand" -l modified -d 'Filter by modified time (e.g., \'<7d\', \'2023-01-01..2023-12-31\')' -r
 complete -c lla -n "__fish_use_subcommand" -l created -d 'Filter by creation time using the same syntax as --modified' -r
+complete -c lla -n "__fish_use_subcommand" -l accessed -d 'Filter by last access time using the same syntax as --modified' -r
+complete -c lla -n "__fish_use_subcommand" -l newer -d 'Only show entries modified after PATH was' -r
+complete -c lla -n "__fish_use_subcommand" -l older -d 'Only show entries modified before PATH was' -r
+complete -c lla -n "__fish_use_subcommand" -l owner -d 'Only show entries owned by this user name or uid' -r
+complete -c lla -n "__fish_use_subcommand" -l group -d 'Only show entries owned by this group name or gid' -r
+complete -c lla -n "__fish_use_subcommand" -l perm -d 'Filter by permission bits as find -perm does: exactly MODE, all of -MODE, or any of /MODE (e.g. 644, -g+w, /u+x); repeatable' -r
+complete -c lla -n "__fish_use_subcommand" -l where -d 'Filter with a boolean expression, e.g. \'ext in (rs, toml) and size > 10M\'; repeatable' -r
+complete -c lla -n "__fish_use_subcommand" -l field-filter -d 'Filter on a plugin field, compared by its declared type, e.g. complexity>20 or tags~release; repeatable' -r
+complete -c lla -n "__fish_use_subcommand" -l type -d 'Only show files whose contents are of this type, detected from the file header; repeatable or comma-separated' -r -f -a "{image	,text	,binary	,executable	,archive	}"
+complete -c lla -n "__fish_use_subcommand" -l git-status -d 'Only show entries with this Git status; directories are kept when anything inside them matches; repeatable or comma-separated' -r -f -a "{modified	,untracked	,staged	,conflicted	,ignored	}"
 complete -c lla -n "__fish_use_subcommand" -l refine -d 'Refine a previous listing (or cache) without re-walking the filesystem using additional filters' -r
 complete -c lla -n "__fish_use_subcommand" -l enable-plugin -d 'Enable specific plugins' -r
 complete -c lla -n "__fish_use_subcommand" -l search-pipe -d 'After --search finishes, run plugin action(s) on matching files (syntax: plugin:action[:arg...])' -r
 complete -c lla -n "__fish_use_subcommand" -l disable-plugin -d 'Disable specific plugins' -r
 complete -c lla -n "__fish_use_subcommand" -l plugins-dir -d 'Specify the plugins directory' -r
+complete -c lla -n "__fish_use_subcommand" -l format-template -d 'Print one line per entry from a template such as \'{icon} {name:<40} {size:>8h} {modified:%Y-%m-%d}\'' -r
+complete -c lla -n "__fish_use_subcommand" -l format -d 'Print entries with a template saved under [formatters.templates]' -r
 complete -c lla -n "__fish_use_subcommand" -l permission-format -d 'Format for displaying permissions (symbolic, octal, binary, verbose, compact)' -r -f -a "{symbolic	,octal	,binary	,verbose	,compact	}"
 complete -c lla -n "__fish_use_subcommand" -l date-format -d 'Format absolute dates in long format using chrono strftime syntax (e.g., \'%Y-%m-%d %H:%M\')' -r
 complete -c lla -n "__fish_use_subcommand" -s h -l help -d 'Print help information'
@@ -21,6 +41,8 @@ complete -c lla -n "__fish_use_subcommand" -l json -d 'Output a single JSON arra
 complete -c lla -n "__fish_use_subcommand" -l ndjson -d 'Output newline-delimited JSON (one object per line)'
 complete -c lla -n "__fish_use_subcommand" -l csv -d 'Output CSV with header row'
 complete -c lla -n "__fish_use_subcommand" -l pretty -d 'Pretty print JSON (only applies to --json)'
+complete -c lla -n "__fish_use_subcommand" -l stdin -d 'Read the paths to list from stdin (newline or NUL separated) instead of walking a directory'
+complete -c lla -n "__fish_use_subcommand" -l watch -d 'Re-render the listing whenever the directory changes (human views and --ndjson)'
 complete -c lla -n "__fish_use_subcommand" -s l -l long -d 'Use long listing format (overrides config format)'
 complete -c lla -n "__fish_use_subcommand" -s t -l tree -d 'Use tree listing format (overrides config format)'
 complete -c lla -n "__fish_use_subcommand" -s T -l table -d 'Use table listing format (overrides config format)'
@@ -33,10 +55,13 @@ complete -c lla -n "__fish_use_subcommand" -s F -l fuzzy -d 'Use interactive fuz
 complete -c lla -n "__fish_use_subcommand" -l icons -d 'Show icons for files and directories (overrides config setting)'
 complete -c lla -n "__fish_use_subcommand" -l no-icons -d 'Hide icons for files and directories (overrides config setting)'
 complete -c lla -n "__fish_use_subcommand" -l no-color -d 'Disable all colors in the output'
+complete -c lla -n "__fish_use_subcommand" -s 0 -l print0 -d 'Terminate each entry with a NUL byte instead of a newline (implies plain output)'
 complete -c lla -n "__fish_use_subcommand" -s r -l sort-reverse -d 'Reverse the sort order'
 complete -c lla -n "__fish_use_subcommand" -l sort-dirs-first -d 'List directories before files (overrides config setting)'
 complete -c lla -n "__fish_use_subcommand" -l sort-case-sensitive -d 'Enable case-sensitive sorting (overrides config setting)'
 complete -c lla -n "__fish_use_subcommand" -l sort-natural -d 'Use natural sorting for numbers (overrides config setting)'
+complete -c lla -n "__fish_use_subcommand" -l setuid -d 'Only show entries with the setuid bit set (same as --perm -u+s)'
+complete -c lla -n "__fish_use_subcommand" -l world-writable -d 'Only show entries anyone can write to (same as --perm -o+w)'
 complete -c lla -n "__fish_use_subcommand" -s c -l case-sensitive -d 'Enable case-sensitive filtering (overrides config setting)'
 complete -c lla -n "__fish_use_subcommand" -s R -l recursive -d 'Use recursive listing format'
 complete -c lla -n "__fish_use_subcommand" -l include-dirs -d 'Include directory sizes in metadata (recursive and potentially expensive)'
@@ -55,6 +80,10 @@ complete -c lla -n "__fish_use_subcommand" -s A -l almost-all -d 'Show all files
 complete -c lla -n "__fish_use_subcommand" -l dotfiles-only -d 'Show only dot files and directories (those starting with a dot)'
 complete -c lla -n "__fish_use_subcommand" -l respect-gitignore -d 'Hide files that match .gitignore (and git exclude) rules'
 complete -c lla -n "__fish_use_subcommand" -l no-gitignore -d 'Disable .gitignore filtering even if enabled in config'
+complete -c lla -n "__fish_use_subcommand" -s x -l one-file-system -d 'Do not descend into directories on other file systems'
+complete -c lla -n "__fish_use_subcommand" -l cross-file-systems -d 'Descend into mount points even if one_file_system is set in config'
+complete -c lla -n "__fish_use_subcommand" -l no-llaignore -d 'Show files hidden by .llaignore rules'
+complete -c lla -n "__fish_use_subcommand" -l explain-ignore -d 'Print each path hidden by .llaignore, with the rule and file that hid it, to stderr'
 complete -c lla -n "__fish_use_subcommand" -l hide-group -d 'Hide group column in long format'
 complete -c lla -n "__fish_use_subcommand" -l relative-dates -d 'Show relative dates (e.g., \'2h ago\') in long format'
 complete -c lla -n "__fish_use_subcommand" -s i -l inode -d 'Show inode numbers in long format'
@@ -63,6 +92,8 @@ complete -c lla -n "__fish_use_subcommand" -l allocated-size -d 'Show allocated
 complete -c lla -n "__fish_use_subcommand" -s @ -l extended -d 'Show extended attribute names and sizes in long format'
 complete -c lla -n "__fish_use_subcommand" -s Z -l context -d 'Show ACL or SELinux security context in long format'
 complete -c lla -n "__fish_use_subcommand" -s M -l mounts -d 'Show source, mount point, and filesystem in long format'
+complete -c lla -n "__fish_use_subcommand" -l mime -d 'Show the content type detected from each file\'s header'
+complete -c lla -n "__fish_use_subcommand" -l classify -d 'Append an indicator to names: / directory, * executable, @ symlink, | FIFO, = socket'
 complete -c lla -n "__fish_use_subcommand" -f -a "diff" -d 'Compare two directories or a directory against a git reference'
 complete -c lla -n "__fish_use_subcommand" -f -a "jump" -d 'Jump to a bookmarked or recent directory'
 complete -c lla -n "__fish_use_subcommand" -f -a "install" -d 'Install a plugin'
@@ -74,6 +105,8 @@ complete -c lla -n "__fish_use_subcommand" -f -a "config" -d 'View or modify con
 complete -c lla -n "__fish_use_subcommand" -f -a "update" -d 'Update installed plugins'
 complete -c lla -n "__fish_use_subcommand" -f -a "upgrade" -d 'Upgrade the lla CLI to the latest (or specified) release'
 complete -c lla -n "__fish_use_subcommand" -f -a "clean" -d 'This command will clean up invalid plugins'
+complete -c lla -n "__fish_use_subcommand" -f -a "cache" -d 'Inspect and bound the listing cache'
+complete -c lla -n "__fish_use_subcommand" -f -a "preset" -d 'List, inspect and try filter presets'
 complete -c lla -n "__fish_use_subcommand" -f -a "shortcut" -d 'Manage command shortcuts'
 complete -c lla -n "__fish_use_subcommand" -f -a "completion" -d 'Generate shell completion scripts'
 complete -c lla -n "__fish_use_subcommand" -f -a "theme" -d 'Interactive theme manager'
@@ -113,6 +146,24 @@ complete -c lla -n "__fish_seen_subcommand_from upgrade" -s v -l version -d 'Upg
 complete -c lla -n "__fish_seen_subcommand_from upgrade" -l path -d 'Install location for the lla binary (defaults to the current executable path)' -r
 complete -c lla -n "__fish_seen_subcommand_from upgrade" -s h -l help -d 'Print help information'
 complete -c lla -n "__fish_seen_subcommand_from clean" -s h -l help -d 'Print help information'
+complete -c lla -n "__fish_seen_subcommand_from cache; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from clear; and not __fish_seen_subcommand_from prune; and not __fish_seen_subcommand_from help" -s h -l help -d 'Print help information'
+complete -c lla -n "__fish_seen_subcommand_from cache; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from clear; and not __fish_seen_subcommand_from prune; and not __fish_seen_subcommand_from help" -f -a "stats" -d 'Show where the listing cache lives and how large it is'
+complete -c lla -n "__fish_seen_subcommand_from cache; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from clear; and not __fish_seen_subcommand_from prune; and not __fish_seen_subcommand_from help" -f -a "clear" -d 'Remove all cached listings'
+complete -c lla -n "__fish_seen_subcommand_from cache; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from clear; and not __fish_seen_subcommand_from prune; and not __fish_seen_subcommand_from help" -f -a "prune" -d 'Remove old cached listings until the cache fits its bounds'
+complete -c lla -n "__fish_seen_subcommand_from cache; and not __fish_seen_subcommand_from stats; and not __fish_seen_subcommand_from clear; and not __fish_seen_subcommand_from prune; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c lla -n "__fish_seen_subcommand_from cache; and __fish_seen_subcommand_from stats" -s h -l help -d 'Print help information'
+complete -c lla -n "__fish_seen_subcommand_from cache; and __fish_seen_subcommand_from clear" -s h -l help -d 'Print help information'
+complete -c lla -n "__fish_seen_subcommand_from cache; and __fish_seen_subcommand_from prune" -l max-age -d 'Remove listings older than this (e.g. 12h, 7d)' -r
+complete -c lla -n "__fish_seen_subcommand_from cache; and __fish_seen_subcommand_from prune" -l max-size -d 'Then remove the oldest listings until the cache is at most this size' -r
+complete -c lla -n "__fish_seen_subcommand_from cache; and __fish_seen_subcommand_from prune" -s h -l help -d 'Print help information'
+complete -c lla -n "__fish_seen_subcommand_from preset; and not __fish_seen_subcommand_from list; and not __fish_seen_subcommand_from show; and not __fish_seen_subcommand_from test; and not __fish_seen_subcommand_from help" -s h -l help -d 'Print help information'
+complete -c lla -n "__fish_seen_subcommand_from preset; and not __fish_seen_subcommand_from list; and not __fish_seen_subcommand_from show; and not __fish_seen_subcommand_from test; and not __fish_seen_subcommand_from help" -f -a "list" -d 'List the presets from your config and the nearest .lla.toml'
+complete -c lla -n "__fish_seen_subcommand_from preset; and not __fish_seen_subcommand_from list; and not __fish_seen_subcommand_from show; and not __fish_seen_subcommand_from test; and not __fish_seen_subcommand_from help" -f -a "show" -d 'Show what a preset resolves to'
+complete -c lla -n "__fish_seen_subcommand_from preset; and not __fish_seen_subcommand_from list; and not __fish_seen_subcommand_from show; and not __fish_seen_subcommand_from test; and not __fish_seen_subcommand_from help" -f -a "test" -d 'Show a preset and how many entries it matches'
+complete -c lla -n "__fish_seen_subcommand_from preset; and not __fish_seen_subcommand_from list; and not __fish_seen_subcommand_from show; and not __fish_seen_subcommand_from test; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c lla -n "__fish_seen_subcommand_from preset; and __fish_seen_subcommand_from list" -s h -l help -d 'Print help information'
+complete -c lla -n "__fish_seen_subcommand_from preset; and __fish_seen_subcommand_from show" -s h -l help -d 'Print help information'
+complete -c lla -n "__fish_seen_subcommand_from preset; and __fish_seen_subcommand_from test" -s h -l help -d 'Print help information'
 complete -c lla -n "__fish_seen_subcommand_from shortcut; and not __fish_seen_subcommand_from add; and not __fish_seen_subcommand_from create; and not __fish_seen_subcommand_from remove; and not __fish_seen_subcommand_from export; and not __fish_seen_subcommand_from import; and not __fish_seen_subcommand_from list; and not __fish_seen_subcommand_from help" -s h -l help -d 'Print help information'
 complete -c lla -n "__fish_seen_subcommand_from shortcut; and not __fish_seen_subcommand_from add; and not __fish_seen_subcommand_from create; and not __fish_seen_subcommand_from remove; and not __fish_seen_subcommand_from export; and not __fish_seen_subcommand_from import; and not __fish_seen_subcommand_from list; and not __fish_seen_subcommand_from help" -f -a "add" -d 'Add a new shortcut'
 complete -c lla -n "__fish_seen_subcommand_from shortcut; and not __fish_seen_subcommand_from add; and not __fish_seen_subcommand_from create; and not __fish_seen_subcommand_from remove; and not __fish_seen_subcommand_from export; and not __fish_seen_subcommand_from import; and not __fish_seen_subcommand_from list; and not __fish_seen_subcommand_from help" -f -a "create" -d 'Interactively create a new shortcut'
@@ -21,24 +21,45 @@ Register-ArgumentCompleter -Native -CommandName 'lla' -ScriptBlock {
 
     $completions = @(switch ($command) {
         'lla' {
+            [CompletionResult]::new('--from-file', 'from-file', [CompletionResultType]::ParameterName, 'Read the paths to list from a file (newline or NUL separated) instead of walking a directory')
+            [CompletionResult]::new('--interval', 'interval', [CompletionResultType]::ParameterName, 'With --watch, wait this long for changes to settle before updating (default: 0.3)')
             [CompletionResult]::new('--search', 'search', [CompletionResultType]::ParameterName, 'Search file contents with ripgrep for the given pattern')
             [CompletionResult]::new('--search-context', 'search-context', [CompletionResultType]::ParameterName, 'Number of context lines to show before and after matches (default: 2)')
             [CompletionResult]::new('-d', 'd', [CompletionResultType]::ParameterName, 'Set the depth for tree listing (default from config)')
             [CompletionResult]::new('--depth', 'depth', [CompletionResultType]::ParameterName, 'Set the depth for tree listing (default from config)')
             [CompletionResult]::new('--hyperlink', 'hyperlink', [CompletionResultType]::ParameterName, 'Emit OSC 8 file hyperlinks (always, auto, never)')
-            [CompletionResult]::new('-s', 's', [CompletionResultType]::ParameterName, 'Sort files by name, size, or date')
-            [CompletionResult]::new('--sort', 'sort', [CompletionResultType]::ParameterName, 'Sort files by name, size, or date')
+            [CompletionResult]::new('--quote', 'quote', [CompletionResultType]::ParameterName, 'Quote file names for scripts: literal, shell, or c (implies plain output)')
+            [CompletionResult]::new('-s', 's', [CompletionResultType]::ParameterName, 'Sort files by one or more keys or sortable plugin fields (field:<name>); list keys like size,-date,name to break ties, with - flipping a key''s direction')
+            [CompletionResult]::new('--sort', 'sort', [CompletionResultType]::ParameterName, 'Sort files by one or more keys or sortable plugin fields (field:<name>); list keys like size,-date,name to break ties, with - flipping a key''s direction')
+            [CompletionResult]::new('--sort-collation', 'sort-collation', [CompletionResultType]::ParameterName, 'Compare names by code point (bytes) or with accents and case set aside (unicode) (overrides config setting)')
+            [CompletionResult]::new('--limit', 'limit', [CompletionResultType]::ParameterName, 'Show only the first N entries in sort order, picked without sorting the whole listing')
+            [CompletionResult]::new('--head', 'head', [CompletionResultType]::ParameterName, 'Show only the first N entries in sort order, picked without sorting the whole listing')
+            [CompletionResult]::new('--tail', 'tail', [CompletionResultType]::ParameterName, 'Show only the last N entries in sort order')
             [CompletionResult]::new('-f', 'f', [CompletionResultType]::ParameterName, 'Filter files by name or extension')
             [CompletionResult]::new('--filter', 'filter', [CompletionResultType]::ParameterName, 'Filter files by name or extension')
-            [CompletionResult]::new('--preset', 'preset', [CompletionResultType]::ParameterName, 'Apply a named filter preset defined in your config')
+            [CompletionResult]::new('--fuzzy-query', 'fuzzy-query', [CompletionResultType]::ParameterName, 'Keep names that fuzzy-match QUERY, best matches first; same as --filter fuzzy:QUERY')
+            [CompletionResult]::new('--fuzzy-min-score', 'fuzzy-min-score', [CompletionResultType]::ParameterName, 'Drop fuzzy matches scoring below SCORE')
+            [CompletionResult]::new('--preset', 'preset', [CompletionResultType]::ParameterName, 'Apply a named filter preset, optionally with parameters (e.g. stale:days=90)')
             [CompletionResult]::new('--size', 'size', [CompletionResultType]::ParameterName, 'Filter by file size (e.g., ''>10M'', ''5K..2G'')')
             [CompletionResult]::new('--modified', 'modified', [CompletionResultType]::ParameterName, 'Filter by modified time (e.g., ''<7d'', ''2023-01-01..2023-12-31'')')
             [CompletionResult]::new('--created', 'created', [CompletionResultType]::ParameterName, 'Filter by creation time using the same syntax as --modified')
+            [CompletionResult]::new('--accessed', 'accessed', [CompletionResultType]::ParameterName, 'Filter by last access time using the same syntax as --modified')
+            [CompletionResult]::new('--newer', 'newer', [CompletionResultType]::ParameterName, 'Only show entries modified after PATH was')
+            [CompletionResult]::new('--older', 'older', [CompletionResultType]::ParameterName, 'Only show entries modified before PATH was')
+            [CompletionResult]::new('--owner', 'owner', [CompletionResultType]::ParameterName, 'Only show entries owned by this user name or uid')
+            [CompletionResult]::new('--group', 'group', [CompletionResultType]::ParameterName, 'Only show entries owned by this group name or gid')
+            [CompletionResult]::new('--perm', 'perm', [CompletionResultType]::ParameterName, 'Filter by permission bits as find -perm does: exactly MODE, all of -MODE, or any of /MODE (e.g. 644, -g+w, /u+x); repeatable')
+            [CompletionResult]::new('--where', 'where', [CompletionResultType]::ParameterName, 'Filter with a boolean expression, e.g. ''ext in (rs, toml) and size > 10M''; repeatable')
+            [CompletionResult]::new('--field-filter', 'field-filter', [CompletionResultType]::ParameterName, 'Filter on a plugin field, compared by its declared type, e.g. complexity>20 or tags~release; repeatable')
+            [CompletionResult]::new('--type', 'type', [CompletionResultType]::ParameterName, 'Only show files whose contents are of this type, detected from the file header; repeatable or comma-separated')
+            [CompletionResult]::new('--git-status', 'git-status', [CompletionResultType]::ParameterName, 'Only show entries with this Git status; directories are kept when anything inside them matches; repeatable or comma-separated')
             [CompletionResult]::new('--refine', 'refine', [CompletionResultType]::ParameterName, 'Refine a previous listing (or cache) without re-walking the filesystem using additional filters')
             [CompletionResult]::new('--enable-plugin', 'enable-plugin', [CompletionResultType]::ParameterName, 'Enable specific plugins')
             [CompletionResult]::new('--search-pipe', 'search-pipe', [CompletionResultType]::ParameterName, 'After --search finishes, run plugin action(s) on matching files (syntax: plugin:action[:arg...])')
             [CompletionResult]::new('--disable-plugin', 'disable-plugin', [CompletionResultType]::ParameterName, 'Disable specific plugins')
             [CompletionResult]::new('--plugins-dir', 'plugins-dir', [CompletionResultType]::ParameterName, 'Specify the plugins directory')
+            [CompletionResult]::new('--format-template', 'format-template', [CompletionResultType]::ParameterName, 'Print one line per entry from a template such as ''{icon} {name:<40} {size:>8h} {modified:%Y-%m-%d}''')
+            [CompletionResult]::new('--format', 'format', [CompletionResultType]::ParameterName, 'Print entries with a template saved under [formatters.templates]')
             [CompletionResult]::new('--permission-format', 'permission-format', [CompletionResultType]::ParameterName, 'Format for displaying permissions (symbolic, octal, binary, verbose, compact)')
             [CompletionResult]::new('--date-format', 'date-format', [CompletionResultType]::ParameterName, 'Format absolute dates in long format using chrono strftime syntax (e.g., ''%Y-%m-%d %H:%M'')')
             [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
@@ -49,6 +70,8 @@ Register-ArgumentCompleter -Native -CommandName 'lla' -ScriptBlock {
             [CompletionResult]::new('--ndjson', 'ndjson', [CompletionResultType]::ParameterName, 'Output newline-delimited JSON (one object per line)')
             [CompletionResult]::new('--csv', 'csv', [CompletionResultType]::ParameterName, 'Output CSV with header row')
             [CompletionResult]::new('--pretty', 'pretty', [CompletionResultType]::ParameterName, 'Pretty print JSON (only applies to --json)')
+            [CompletionResult]::new('--stdin', 'stdin', [CompletionResultType]::ParameterName, 'Read the paths to list from stdin (newline or NUL separated) instead of walking a directory')
+            [CompletionResult]::new('--watch', 'watch', [CompletionResultType]::ParameterName, 'Re-render the listing whenever the directory changes (human views and --ndjson)')
             [CompletionResult]::new('-l', 'l', [CompletionResultType]::ParameterName, 'Use long listing format (overrides config format)')
             [CompletionResult]::new('--long', 'long', [CompletionResultType]::ParameterName, 'Use long listing format (overrides config format)')
             [CompletionResult]::new('-t', 't', [CompletionResultType]::ParameterName, 'Use tree listing format (overrides config format)')
@@ -68,11 +91,15 @@ Register-ArgumentCompleter -Native -CommandName 'lla' -ScriptBlock {
             [CompletionResult]::new('--icons', 'icons', [CompletionResultType]::ParameterName, 'Show icons for files and directories (overrides config setting)')
             [CompletionResult]::new('--no-icons', 'no-icons', [CompletionResultType]::ParameterName, 'Hide icons for files and directories (overrides config setting)')
             [CompletionResult]::new('--no-color', 'no-color', [CompletionResultType]::ParameterName, 'Disable all colors in the output')
+            [CompletionResult]::new('-0', '0', [CompletionResultType]::ParameterName, 'Terminate each entry with a NUL byte instead of a newline (implies plain output)')
+            [CompletionResult]::new('--print0', 'print0', [CompletionResultType]::ParameterName, 'Terminate each entry with a NUL byte instead of a newline (implies plain output)')
             [CompletionResult]::new('-r', 'r', [CompletionResultType]::ParameterName, 'Reverse the sort order')
             [CompletionResult]::new('--sort-reverse', 'sort-reverse', [CompletionResultType]::ParameterName, 'Reverse the sort order')
             [CompletionResult]::new('--sort-dirs-first', 'sort-dirs-first', [CompletionResultType]::ParameterName, 'List directories before files (overrides config setting)')
             [CompletionResult]::new('--sort-case-sensitive', 'sort-case-sensitive', [CompletionResultType]::ParameterName, 'Enable case-sensitive sorting (overrides config setting)')
             [CompletionResult]::new('--sort-natural', 'sort-natural', [CompletionResultType]::ParameterName, 'Use natural sorting for numbers (overrides config setting)')
+            [CompletionResult]::new('--setuid', 'setuid', [CompletionResultType]::ParameterName, 'Only show entries with the setuid bit set (same as --perm -u+s)')
+            [CompletionResult]::new('--world-writable', 'world-writable', [CompletionResultType]::ParameterName, 'Only show entries anyone can write to (same as --perm -o+w)')
             [CompletionResult]::new('-c', 'c', [CompletionResultType]::ParameterName, 'Enable case-sensitive filtering (overrides config setting)')
             [CompletionResult]::new('--case-sensitive', 'case-sensitive', [CompletionResultType]::ParameterName, 'Enable case-sensitive filtering (overrides config setting)')
             [CompletionResult]::new('-R', 'R', [CompletionResultType]::ParameterName, 'Use recursive listing format')
@@ -96,6 +123,11 @@ Register-ArgumentCompleter -Native -CommandName 'lla' -ScriptBlock {
             [CompletionResult]::new('--dotfiles-only', 'dotfiles-only', [CompletionResultType]::ParameterName, 'Show only dot files and directories (those starting with a dot)')
             [CompletionResult]::new('--respect-gitignore', 'respect-gitignore', [CompletionResultType]::ParameterName, 'Hide files that match .gitignore (and git exclude) rules')
             [CompletionResult]::new('--no-gitignore', 'no-gitignore', [CompletionResultType]::ParameterName, 'Disable .gitignore filtering even if enabled in config')
+            [CompletionResult]::new('-x', 'x', [CompletionResultType]::ParameterName, 'Do not descend into directories on other file systems')
+            [CompletionResult]::new('--one-file-system', 'one-file-system', [CompletionResultType]::ParameterName, 'Do not descend into directories on other file systems')
+            [CompletionResult]::new('--cross-file-systems', 'cross-file-systems', [CompletionResultType]::ParameterName, 'Descend into mount points even if one_file_system is set in config')
+            [CompletionResult]::new('--no-llaignore', 'no-llaignore', [CompletionResultType]::ParameterName, 'Show files hidden by .llaignore rules')
+            [CompletionResult]::new('--explain-ignore', 'explain-ignore', [CompletionResultType]::ParameterName, 'Print each path hidden by .llaignore, with the rule and file that hid it, to stderr')
             [CompletionResult]::new('--hide-group', 'hide-group', [CompletionResultType]::ParameterName, 'Hide group column in long format')
             [CompletionResult]::new('--relative-dates', 'relative-dates', [CompletionResultType]::ParameterName, 'Show relative dates (e.g., ''2h ago'') in long format')
             [CompletionResult]::new('-i', 'i', [CompletionResultType]::ParameterName, 'Show inode numbers in long format')
@@ -109,6 +141,8 @@ Register-ArgumentCompleter -Native -CommandName 'lla' -ScriptBlock {
             [CompletionResult]::new('--context', 'context', [CompletionResultType]::ParameterName, 'Show ACL or SELinux security context in long format')
             [CompletionResult]::new('-M', 'M', [CompletionResultType]::ParameterName, 'Show source, mount point, and filesystem in long format')
             [CompletionResult]::new('--mounts', 'mounts', [CompletionResultType]::ParameterName, 'Show source, mount point, and filesystem in long format')
+            [CompletionResult]::new('--mime', 'mime', [CompletionResultType]::ParameterName, 'Show the content type detected from each file''s header')
+            [CompletionResult]::new('--classify', 'classify', [CompletionResultType]::ParameterName, 'Append an indicator to names: / directory, * executable, @ symlink, | FIFO, = socket')
             [CompletionResult]::new('diff', 'diff', [CompletionResultType]::ParameterValue, 'Compare two directories or a directory against a git reference')
             [CompletionResult]::new('jump', 'jump', [CompletionResultType]::ParameterValue, 'Jump to a bookmarked or recent directory')
             [CompletionResult]::new('install', 'install', [CompletionResultType]::ParameterValue, 'Install a plugin')
@@ -120,6 +154,8 @@ Register-ArgumentCompleter -Native -CommandName 'lla' -ScriptBlock {
             [CompletionResult]::new('update', 'update', [CompletionResultType]::ParameterValue, 'Update installed plugins')
             [CompletionResult]::new('upgrade', 'upgrade', [CompletionResultType]::ParameterValue, 'Upgrade the lla CLI to the latest (or specified) release')
             [CompletionResult]::new('clean', 'clean', [CompletionResultType]::ParameterValue, 'This command will clean up invalid plugins')
+            [CompletionResult]::new('cache', 'cache', [CompletionResultType]::ParameterValue, 'Inspect and bound the listing cache')
+            [CompletionResult]::new('preset', 'preset', [CompletionResultType]::ParameterValue, 'List, inspect and try filter presets')
             [CompletionResult]::new('shortcut', 'shortcut', [CompletionResultType]::ParameterValue, 'Manage command shortcuts')
             [CompletionResult]::new('completion', 'completion', [CompletionResultType]::ParameterValue, 'Generate shell completion scripts')
             [CompletionResult]::new('theme', 'theme', [CompletionResultType]::ParameterValue, 'Interactive theme manager')
@@ -220,6 +256,62 @@ Register-ArgumentCompleter -Native -CommandName 'lla' -ScriptBlock {
             [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
             break
         }
+        'lla;cache' {
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('stats', 'stats', [CompletionResultType]::ParameterValue, 'Show where the listing cache lives and how large it is')
+            [CompletionResult]::new('clear', 'clear', [CompletionResultType]::ParameterValue, 'Remove all cached listings')
+            [CompletionResult]::new('prune', 'prune', [CompletionResultType]::ParameterValue, 'Remove old cached listings until the cache fits its bounds')
+            [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
+            break
+        }
+        'lla;cache;stats' {
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
+            break
+        }
+        'lla;cache;clear' {
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
+            break
+        }
+        'lla;cache;prune' {
+            [CompletionResult]::new('--max-age', 'max-age', [CompletionResultType]::ParameterName, 'Remove listings older than this (e.g. 12h, 7d)')
+            [CompletionResult]::new('--max-size', 'max-size', [CompletionResultType]::ParameterName, 'Then remove the oldest listings until the cache is at most this size')
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
+            break
+        }
+        'lla;cache;help' {
+            break
+        }
+        'lla;preset' {
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('list', 'list', [CompletionResultType]::ParameterValue, 'List the presets from your config and the nearest .lla.toml')
+            [CompletionResult]::new('show', 'show', [CompletionResultType]::ParameterValue, 'Show what a preset resolves to')
+            [CompletionResult]::new('test', 'test', [CompletionResultType]::ParameterValue, 'Show a preset and how many entries it matches')
+            [CompletionResult]::new('help', 'help', [CompletionResultType]::ParameterValue, 'Print this message or the help of the given subcommand(s)')
+            break
+        }
+        'lla;preset;list' {
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
+            break
+        }
+        'lla;preset;show' {
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
+            break
+        }
+        'lla;preset;test' {
+            [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
+            [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
+            break
+        }
+        'lla;preset;help' {
+            break
+        }
         'lla;shortcut' {
             [CompletionResult]::new('-h', 'h', [CompletionResultType]::ParameterName, 'Print help information')
             [CompletionResult]::new('--help', 'help', [CompletionResultType]::ParameterName, 'Print help information')
@@ -40,6 +40,8 @@ pub struct EntryMetadata {
     pub mount_source: ::prost::alloc::string::String,
     #[prost(string, tag = "19")]
     pub filesystem: ::prost::alloc::string::String,
+    #[prost(enumeration = "FileKind", tag = "20")]
+    pub kind: i32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -276,6 +278,52 @@ pub struct ListActionsResponse {
     #[prost(message, repeated, tag = "1")]
     pub actions: ::prost::alloc::vec::Vec<ActionInfo>,
 }
+/// File type as reported by lstat(2). UNKNOWN is sent by hosts that predate
+/// this field; use is_dir/is_file/is_symlink in that case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FileKind {
+    Unknown = 0,
+    Regular = 1,
+    Directory = 2,
+    Symlink = 3,
+    Fifo = 4,
+    Socket = 5,
+    BlockDevice = 6,
+    CharDevice = 7,
+}
+impl FileKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            FileKind::Unknown => "FILE_KIND_UNKNOWN",
+            FileKind::Regular => "FILE_KIND_REGULAR",
+            FileKind::Directory => "FILE_KIND_DIRECTORY",
+            FileKind::Symlink => "FILE_KIND_SYMLINK",
+            FileKind::Fifo => "FILE_KIND_FIFO",
+            FileKind::Socket => "FILE_KIND_SOCKET",
+            FileKind::BlockDevice => "FILE_KIND_BLOCK_DEVICE",
+            FileKind::CharDevice => "FILE_KIND_CHAR_DEVICE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FILE_KIND_UNKNOWN" => Some(Self::Unknown),
+            "FILE_KIND_REGULAR" => Some(Self::Regular),
+            "FILE_KIND_DIRECTORY" => Some(Self::Directory),
+            "FILE_KIND_SYMLINK" => Some(Self::Symlink),
+            "FILE_KIND_FIFO" => Some(Self::Fifo),
+            "FILE_KIND_SOCKET" => Some(Self::Socket),
+            "FILE_KIND_BLOCK_DEVICE" => Some(Self::BlockDevice),
+            "FILE_KIND_CHAR_DEVICE" => Some(Self::CharDevice),
+            _ => None,
+        }
+    }
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum NullValue {
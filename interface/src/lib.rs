@@ -13,6 +13,36 @@ pub mod proto {
     include!(concat!(env!("OUT_DIR"), "/lla_plugin.rs"));
 }
 
+impl proto::FileKind {
+    /// Kind encoded in the `S_IFMT` bits of a POSIX mode, as stored by archives.
+    pub fn from_mode(mode: u32) -> Self {
+        match mode & 0o170000 {
+            0o100000 => Self::Regular,
+            0o040000 => Self::Directory,
+            0o120000 => Self::Symlink,
+            0o010000 => Self::Fifo,
+            0o140000 => Self::Socket,
+            0o060000 => Self::BlockDevice,
+            0o020000 => Self::CharDevice,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl proto::EntryMetadata {
+    /// The entry's kind, falling back to the `is_dir`/`is_file`/`is_symlink`
+    /// flags for metadata produced without one, such as by older hosts and
+    /// plugins.
+    pub fn file_kind(&self) -> proto::FileKind {
+        match self.kind() {
+            proto::FileKind::Unknown if self.is_symlink => proto::FileKind::Symlink,
+            proto::FileKind::Unknown if self.is_dir => proto::FileKind::Directory,
+            proto::FileKind::Unknown if self.is_file => proto::FileKind::Regular,
+            kind => kind,
+        }
+    }
+}
+
 pub const PLUGIN_API_VERSION: u32 = 3;
 pub const PLUGIN_CREATE_SYMBOL_V3: &[u8] = b"_plugin_create_v3\0";
 pub const MAX_BATCH_ENTRIES: usize = 512;
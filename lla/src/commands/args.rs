@@ -1,13 +1,23 @@
 use crate::config::{validate_long_date_format, Config, ShortcutCommand};
 use crate::error::{LlaError, Result};
-use crate::filter::{parse_size_range, parse_time_range, NumericRange, TimeRange};
+use crate::filter::{
+    parse_age, parse_size, parse_size_range, parse_time_range, resolve_group, resolve_user,
+    FieldFilter, GitStatusKind, NumericRange, PermFilter, TimeRange, WhereExpr,
+};
+use crate::formatter::quoting::{PlainOutput, QuotingStyle};
+use crate::formatter::template::Template;
+use crate::sorter::{is_valid_sort_spec, Collation, EntryLimit, SortKey, SORT_KEYS};
+use crate::utils::content_type::ContentType;
+use crate::utils::llaignore::LlaIgnoreMode;
 use clap::{App, Arg, ArgGroup, ArgMatches, SubCommand};
 use clap_complete::Shell;
 use std::path::PathBuf;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
+#[derive(Clone)]
 pub struct Args {
     pub directory: String,
+    pub operands: Vec<String>,
     pub depth: Option<usize>,
     pub long_format: bool,
     pub tree_format: bool,
@@ -19,6 +29,7 @@ pub struct Args {
     pub git_format: bool,
     pub fuzzy_format: bool,
     pub recursive_format: bool,
+    pub format_template: Option<Template>,
     pub show_icons: bool,
     pub hyperlinks: bool,
     pub no_color: bool,
@@ -27,7 +38,11 @@ pub struct Args {
     pub sort_dirs_first: bool,
     pub sort_case_sensitive: bool,
     pub sort_natural: bool,
+    pub sort_collation: Collation,
+    pub limit: Option<EntryLimit>,
     pub filter: Option<String>,
+    pub fuzzy_query: Option<String>,
+    pub fuzzy_min_score: i64,
     pub presets: Vec<String>,
     pub size_filter: Option<NumericRange>,
     pub size_filter_raw: Option<String>,
@@ -35,6 +50,19 @@ pub struct Args {
     pub modified_filter_raw: Option<String>,
     pub created_filter: Option<TimeRange>,
     pub created_filter_raw: Option<String>,
+    pub accessed_filter: Option<TimeRange>,
+    pub accessed_filter_raw: Option<String>,
+    /// Modification time of the `--newer` reference.
+    pub newer_than: Option<SystemTime>,
+    /// Modification time of the `--older` reference.
+    pub older_than: Option<SystemTime>,
+    pub where_filters: Vec<WhereExpr>,
+    pub field_filters: Vec<FieldFilter>,
+    pub content_types: Vec<ContentType>,
+    pub git_status: Vec<GitStatusKind>,
+    pub owner_filter: Option<u32>,
+    pub group_filter: Option<u32>,
+    pub perm_filters: Vec<PermFilter>,
     pub case_sensitive: bool,
     pub refine_filters: Vec<String>,
     pub enable_plugin: Vec<String>,
@@ -54,6 +82,8 @@ pub struct Args {
     pub almost_all: bool,
     pub dotfiles_only: bool,
     pub respect_gitignore: bool,
+    pub one_file_system: bool,
+    pub llaignore: LlaIgnoreMode,
     pub permission_format: String,
     pub hide_group: bool,
     pub relative_dates: bool,
@@ -64,7 +94,12 @@ pub struct Args {
     pub show_xattrs: bool,
     pub show_context: bool,
     pub show_mounts: bool,
+    pub show_mime: bool,
+    pub classify: bool,
+    pub plain_output: Option<PlainOutput>,
     pub output_mode: OutputMode,
+    pub path_list: Option<PathListSource>,
+    pub watch: Option<Duration>,
     pub command: Option<Command>,
     pub search: Option<String>,
     pub search_context: usize,
@@ -78,6 +113,7 @@ pub struct SearchPipelineSpec {
     pub args: Vec<String>,
 }
 
+#[derive(Clone)]
 pub enum Command {
     Install(InstallSource),
     ListPlugins,
@@ -95,6 +131,8 @@ pub enum Command {
     Clean,
     Shortcut(ShortcutAction),
     Jump(JumpAction),
+    Cache(CacheAction),
+    Preset(PresetAction),
     GenerateCompletion(Shell, Option<String>, Option<String>),
     Theme,
     ThemePull,
@@ -122,6 +160,7 @@ impl PluginOutputFormat {
     }
 }
 
+#[derive(Clone)]
 pub enum InstallSource {
     Prebuilt,
     GitHub(String),
@@ -134,6 +173,7 @@ pub struct UpgradeCommand {
     pub install_path: Option<PathBuf>,
 }
 
+#[derive(Clone)]
 pub enum ShortcutAction {
     Add(String, ShortcutCommand),
     Remove(String),
@@ -166,6 +206,20 @@ pub enum JumpAction {
     Setup(Option<String>),
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CacheAction {
+    Stats,
+    Clear,
+    Prune { max_age: Duration, max_size: u64 },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PresetAction {
+    List,
+    Show(String),
+    Test { preset: String, directory: String },
+}
+
 #[derive(Clone)]
 pub enum ConfigAction {
     View,
@@ -174,6 +228,12 @@ pub enum ConfigAction {
     DiffDefault,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathListSource {
+    Stdin,
+    File(PathBuf),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum OutputMode {
     Human,
@@ -190,8 +250,9 @@ impl Args {
             .about(env!("CARGO_PKG_DESCRIPTION"))
             .arg(
                 Arg::with_name("directory")
-                    .help("The directory to list")
+                    .help("The directories, files or archives to list")
                     .index(1)
+                    .multiple_values(true)
                     .default_value("."),
             )
             .subcommand(
@@ -284,6 +345,37 @@ impl Args {
                     .args(&["json", "ndjson", "csv"]) // mutually exclusive
                     .multiple(false),
             )
+            .arg(
+                Arg::with_name("stdin")
+                    .long("stdin")
+                    .help("Read the paths to list from stdin (newline or NUL separated) instead of walking a directory"),
+            )
+            .arg(
+                Arg::with_name("from-file")
+                    .long("from-file")
+                    .takes_value(true)
+                    .value_name("PATH")
+                    .help("Read the paths to list from a file (newline or NUL separated) instead of walking a directory"),
+            )
+            .group(
+                ArgGroup::new("path_list")
+                    .args(&["stdin", "from-file"])
+                    .multiple(false),
+            )
+            .arg(
+                Arg::with_name("watch")
+                    .long("watch")
+                    .conflicts_with_all(&["fuzzy", "json", "csv", "stdin", "from-file"])
+                    .help("Re-render the listing whenever the directory changes (human views and --ndjson)"),
+            )
+            .arg(
+                Arg::with_name("interval")
+                    .long("interval")
+                    .takes_value(true)
+                    .value_name("SECONDS")
+                    .requires("watch")
+                    .help("With --watch, wait this long for changes to settle before updating (default: 0.3)"),
+            )
             .arg(
                 Arg::with_name("search")
                     .long("search")
@@ -381,14 +473,29 @@ impl Args {
                     .long("no-color")
                     .help("Disable all colors in the output"),
             )
+            .arg(
+                Arg::with_name("print0")
+                    .short('0')
+                    .long("print0")
+                    .help("Terminate each entry with a NUL byte instead of a newline (implies plain output)"),
+            )
+            .arg(
+                Arg::with_name("quote")
+                    .long("quote")
+                    .takes_value(true)
+                    .value_name("STYLE")
+                    .possible_values(["literal", "shell", "c"])
+                    .help("Quote file names for scripts: literal, shell, or c (implies plain output)"),
+            )
             .arg(
                 Arg::with_name("sort")
                     .short('s')
                     .long("sort")
-                    .help("Sort files by name, size, or date")
+                    .help("Sort files by one or more keys or sortable plugin fields (field:<name>); list keys like size,-date,name to break ties, with - flipping a key's direction")
                     .takes_value(true)
-                    .possible_values(["name", "size", "date"])
-                    .default_value(&config.default_sort),
+                    .value_name("KEYS")
+                    .allow_hyphen_values(true)
+                    .value_parser(SortSpecParser),
             )
             .arg(
                 Arg::with_name("sort-reverse")
@@ -411,6 +518,33 @@ impl Args {
                     .long("sort-natural")
                     .help("Use natural sorting for numbers (overrides config setting)"),
             )
+            .arg(
+                Arg::with_name("sort-collation")
+                    .long("sort-collation")
+                    .takes_value(true)
+                    .value_name("MODE")
+                    .possible_values(Collation::NAMES)
+                    .help("Compare names by code point (bytes) or with accents and case set aside (unicode) (overrides config setting)"),
+            )
+            .arg(
+                Arg::with_name("limit")
+                    .long("limit")
+                    .visible_alias("head")
+                    .takes_value(true)
+                    .value_name("N")
+                    .validator(validate_entry_count)
+                    .conflicts_with("fuzzy")
+                    .help("Show only the first N entries in sort order, picked without sorting the whole listing"),
+            )
+            .arg(
+                Arg::with_name("tail")
+                    .long("tail")
+                    .takes_value(true)
+                    .value_name("N")
+                    .validator(validate_entry_count)
+                    .conflicts_with_all(&["limit", "fuzzy"])
+                    .help("Show only the last N entries in sort order"),
+            )
             .arg(
                 Arg::with_name("filter")
                     .short('f')
@@ -418,12 +552,33 @@ impl Args {
                     .takes_value(true)
                     .help("Filter files by name or extension"),
             )
+            .arg(
+                Arg::with_name("fuzzy-query")
+                    .long("fuzzy-query")
+                    .takes_value(true)
+                    .value_name("QUERY")
+                    .help("Keep names that fuzzy-match QUERY, best matches first; same as --filter fuzzy:QUERY"),
+            )
+            .arg(
+                Arg::with_name("fuzzy-min-score")
+                    .long("fuzzy-min-score")
+                    .takes_value(true)
+                    .value_name("SCORE")
+                    .validator(|value| {
+                        value
+                            .parse::<i64>()
+                            .map(|_| ())
+                            .map_err(|_| format!("expected a whole number, got '{}'", value))
+                    })
+                    .help("Drop fuzzy matches scoring below SCORE"),
+            )
             .arg(
                 Arg::with_name("preset")
                     .long("preset")
                     .takes_value(true)
                     .multiple(true)
-                    .help("Apply a named filter preset defined in your config"),
+                    .value_name("NAME[:KEY=VALUE,...]")
+                    .help("Apply a named filter preset, optionally with parameters (e.g. stale:days=90)"),
             )
             .arg(
                 Arg::with_name("size")
@@ -443,6 +598,95 @@ impl Args {
                     .takes_value(true)
                     .help("Filter by creation time using the same syntax as --modified"),
             )
+            .arg(
+                Arg::with_name("accessed")
+                    .long("accessed")
+                    .takes_value(true)
+                    .help("Filter by last access time using the same syntax as --modified"),
+            )
+            .arg(
+                Arg::with_name("newer")
+                    .long("newer")
+                    .takes_value(true)
+                    .value_name("PATH")
+                    .help("Only show entries modified after PATH was"),
+            )
+            .arg(
+                Arg::with_name("older")
+                    .long("older")
+                    .takes_value(true)
+                    .value_name("PATH")
+                    .help("Only show entries modified before PATH was"),
+            )
+            .arg(
+                Arg::with_name("owner")
+                    .long("owner")
+                    .takes_value(true)
+                    .value_name("USER")
+                    .help("Only show entries owned by this user name or uid"),
+            )
+            .arg(
+                Arg::with_name("group")
+                    .long("group")
+                    .takes_value(true)
+                    .value_name("GROUP")
+                    .help("Only show entries owned by this group name or gid"),
+            )
+            .arg(
+                Arg::with_name("perm")
+                    .long("perm")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .allow_hyphen_values(true)
+                    .value_name("MODE")
+                    .help("Filter by permission bits as find -perm does: exactly MODE, all of -MODE, or any of /MODE (e.g. 644, -g+w, /u+x); repeatable"),
+            )
+            .arg(
+                Arg::with_name("setuid")
+                    .long("setuid")
+                    .help("Only show entries with the setuid bit set (same as --perm -u+s)"),
+            )
+            .arg(
+                Arg::with_name("world-writable")
+                    .long("world-writable")
+                    .help("Only show entries anyone can write to (same as --perm -o+w)"),
+            )
+            .arg(
+                Arg::with_name("where")
+                    .long("where")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .value_name("EXPR")
+                    .help("Filter with a boolean expression, e.g. 'ext in (rs, toml) and size > 10M'; repeatable"),
+            )
+            .arg(
+                Arg::with_name("field-filter")
+                    .long("field-filter")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .value_name("FIELD<OP>VALUE")
+                    .help("Filter on a plugin field, compared by its declared type, e.g. complexity>20 or tags~release; repeatable"),
+            )
+            .arg(
+                Arg::with_name("type")
+                    .long("type")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .use_value_delimiter(true)
+                    .possible_values(ContentType::NAMES)
+                    .value_name("TYPE")
+                    .help("Only show files whose contents are of this type, detected from the file header; repeatable or comma-separated"),
+            )
+            .arg(
+                Arg::with_name("git-status")
+                    .long("git-status")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .use_value_delimiter(true)
+                    .possible_values(GitStatusKind::NAMES)
+                    .value_name("STATUS")
+                    .help("Only show entries with this Git status; directories are kept when anything inside them matches; repeatable or comma-separated"),
+            )
             .arg(
                 Arg::with_name("case-sensitive")
                     .short('c')
@@ -489,6 +733,23 @@ impl Args {
                     .long("recursive")
                     .help("Use recursive listing format"),
             )
+            .arg(
+                Arg::with_name("format-template")
+                    .long("format-template")
+                    .takes_value(true)
+                    .value_name("TEMPLATE")
+                    .conflicts_with_all(&["long", "tree", "table", "grid", "sizemap", "timeline", "git", "fuzzy"])
+                    .help("Print one line per entry from a template such as '{icon} {name:<40} {size:>8h} {modified:%Y-%m-%d}'"),
+            )
+            .arg(
+                Arg::with_name("format")
+                    .long("format")
+                    .takes_value(true)
+                    .value_name("NAME")
+                    .possible_values(config.formatters.templates.keys().map(String::as_str))
+                    .conflicts_with_all(&["format-template", "long", "tree", "table", "grid", "sizemap", "timeline", "git", "fuzzy"])
+                    .help("Print entries with a template saved under [formatters.templates]"),
+            )
             .arg(
                 Arg::with_name("include-dirs")
                     .long("include-dirs")
@@ -578,6 +839,33 @@ impl Args {
                     .args(&["respect-gitignore", "no-gitignore"])
                     .multiple(false),
             )
+            .arg(
+                Arg::with_name("one-file-system")
+                    .short('x')
+                    .long("one-file-system")
+                    .help("Do not descend into directories on other file systems"),
+            )
+            .arg(
+                Arg::with_name("cross-file-systems")
+                    .long("cross-file-systems")
+                    .help("Descend into mount points even if one_file_system is set in config"),
+            )
+            .group(
+                ArgGroup::with_name("file_system_handling")
+                    .args(&["one-file-system", "cross-file-systems"])
+                    .multiple(false),
+            )
+            .arg(
+                Arg::with_name("no-llaignore")
+                    .long("no-llaignore")
+                    .help("Show files hidden by .llaignore rules"),
+            )
+            .arg(
+                Arg::with_name("explain-ignore")
+                    .long("explain-ignore")
+                    .conflicts_with("no-llaignore")
+                    .help("Print each path hidden by .llaignore, with the rule and file that hid it, to stderr"),
+            )
             .arg(
                 Arg::with_name("permission-format")
                     .long("permission-format")
@@ -638,6 +926,16 @@ impl Args {
                     .long("mounts")
                     .help("Show source, mount point, and filesystem in long format"),
             )
+            .arg(
+                Arg::with_name("mime")
+                    .long("mime")
+                    .help("Show the content type detected from each file's header"),
+            )
+            .arg(
+                Arg::with_name("classify")
+                    .long("classify")
+                    .help("Append an indicator to names: / directory, * executable, @ symlink, | FIFO, = socket"),
+            )
             .subcommand(
                 SubCommand::with_name("install")
                     .about("Install a plugin")
@@ -802,6 +1100,71 @@ impl Args {
             .subcommand(
                 SubCommand::with_name("clean").about("This command will clean up invalid plugins"),
             )
+            .subcommand(
+                SubCommand::with_name("cache")
+                    .about("Inspect and bound the listing cache")
+                    .setting(clap::AppSettings::SubcommandRequiredElseHelp)
+                    .subcommand(
+                        SubCommand::with_name("stats")
+                            .about("Show where the listing cache lives and how large it is"),
+                    )
+                    .subcommand(SubCommand::with_name("clear").about("Remove all cached listings"))
+                    .subcommand(
+                        SubCommand::with_name("prune")
+                            .about("Remove old cached listings until the cache fits its bounds")
+                            .arg(
+                                Arg::with_name("max-age")
+                                    .long("max-age")
+                                    .takes_value(true)
+                                    .value_name("AGE")
+                                    .default_value("30d")
+                                    .help("Remove listings older than this (e.g. 12h, 7d)"),
+                            )
+                            .arg(
+                                Arg::with_name("max-size")
+                                    .long("max-size")
+                                    .takes_value(true)
+                                    .value_name("SIZE")
+                                    .default_value("256M")
+                                    .help("Then remove the oldest listings until the cache is at most this size"),
+                            ),
+                    ),
+            )
+            .subcommand(
+                SubCommand::with_name("preset")
+                    .about("List, inspect and try filter presets")
+                    .setting(clap::AppSettings::SubcommandRequiredElseHelp)
+                    .subcommand(
+                        SubCommand::with_name("list")
+                            .about("List the presets from your config and the nearest .lla.toml"),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("show")
+                            .about("Show what a preset resolves to")
+                            .arg(
+                                Arg::with_name("preset")
+                                    .help("Preset name, optionally with parameters (e.g. stale:days=90)")
+                                    .required(true)
+                                    .index(1),
+                            ),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("test")
+                            .about("Show a preset and how many entries it matches")
+                            .arg(
+                                Arg::with_name("preset")
+                                    .help("Preset name, optionally with parameters (e.g. stale:days=90)")
+                                    .required(true)
+                                    .index(1),
+                            )
+                            .arg(
+                                Arg::with_name("directory")
+                                    .help("Directory to try the preset on")
+                                    .index(2)
+                                    .default_value("."),
+                            ),
+                    ),
+            )
             .subcommand(
                 SubCommand::with_name("shortcut")
                     .about("Manage command shortcuts")
@@ -936,6 +1299,7 @@ impl Args {
             if config.get_shortcut(potential_shortcut).is_some() {
                 return Ok(Self {
                     directory: ".".to_string(),
+                    operands: vec![".".to_string()],
                     depth: config.default_depth,
                     long_format: config.default_format == "long",
                     tree_format: config.default_format == "tree",
@@ -947,15 +1311,20 @@ impl Args {
                     git_format: config.default_format == "git",
                     fuzzy_format: false,
                     recursive_format: false,
+                    format_template: None,
                     show_icons: config.show_icons,
                     hyperlinks: false,
                     no_color: false,
-                    sort_by: config.default_sort.clone(),
+                    sort_by: config.default_sort_keys(),
                     sort_reverse: false,
                     sort_dirs_first: config.sort.dirs_first,
                     sort_case_sensitive: config.sort.case_sensitive,
                     sort_natural: config.sort.natural,
+                    sort_collation: config.sort_collation(),
+                    limit: None,
                     filter: None,
+                    fuzzy_query: None,
+                    fuzzy_min_score: 0,
                     presets: Vec::new(),
                     size_filter: None,
                     size_filter_raw: None,
@@ -963,6 +1332,17 @@ impl Args {
                     modified_filter_raw: None,
                     created_filter: None,
                     created_filter_raw: None,
+                    accessed_filter: None,
+                    accessed_filter_raw: None,
+                    newer_than: None,
+                    older_than: None,
+                    where_filters: Vec::new(),
+                    field_filters: Vec::new(),
+                    content_types: Vec::new(),
+                    git_status: Vec::new(),
+                    owner_filter: None,
+                    group_filter: None,
+                    perm_filters: Vec::new(),
                     case_sensitive: config.filter.case_sensitive,
                     refine_filters: Vec::new(),
                     enable_plugin: Vec::new(),
@@ -982,6 +1362,8 @@ impl Args {
                     almost_all: false,
                     dotfiles_only: false,
                     respect_gitignore: config.filter.respect_gitignore,
+                    one_file_system: config.one_file_system,
+                    llaignore: LlaIgnoreMode::On,
                     permission_format: config.permission_format.clone(),
                     hide_group: config.formatters.long.hide_group,
                     relative_dates: config.formatters.long.relative_dates,
@@ -1007,7 +1389,15 @@ impl Args {
                         &["context", "security_context", "security-context", "acl"],
                     ),
                     show_mounts: configured_column(config, &["mount", "mounts"]),
+                    show_mime: configured_column(
+                        config,
+                        &["mime", "mime_type", "mime-type", "content_type"],
+                    ),
+                    classify: false,
+                    plain_output: None,
                     output_mode: OutputMode::Human,
+                    path_list: None,
+                    watch: None,
                     command: Some(Command::Shortcut(ShortcutAction::Run(
                         potential_shortcut.clone(),
                         args[2..].to_vec(),
@@ -1023,10 +1413,27 @@ impl Args {
         Self::from_matches(&matches, config)
     }
 
+    /// Arguments for listing `directory` with just `preset` applied, as
+    /// `lla <directory> --preset <preset>` would.
+    pub fn with_preset(config: &Config, preset: &str, directory: &str) -> Result<Self> {
+        let matches = Self::build_cli(config)
+            .try_get_matches_from(["lla", directory, "--preset", preset])
+            .map_err(|err| LlaError::Parse(err.to_string()))?;
+        Self::from_matches(&matches, config)
+    }
+
     pub fn get_cli(config: &Config) -> App<'_> {
         Self::build_cli(config)
     }
 
+    /// Copy of these arguments scoped to a single path operand.
+    pub fn for_operand(&self, operand: &str) -> Self {
+        let mut args = self.clone();
+        args.directory = operand.to_string();
+        args.operands = vec![operand.to_string()];
+        args
+    }
+
     fn from_matches(matches: &ArgMatches, config: &Config) -> Result<Self> {
         let command = if let Some(completion_matches) = matches.subcommand_matches("completion") {
             let shell = match completion_matches.value_of("shell").unwrap() {
@@ -1088,6 +1495,29 @@ impl Args {
             }
         } else if matches.subcommand_matches("clean").is_some() {
             Some(Command::Clean)
+        } else if let Some(cache_matches) = matches.subcommand_matches("cache") {
+            if let Some(prune_matches) = cache_matches.subcommand_matches("prune") {
+                Some(Command::Cache(CacheAction::Prune {
+                    max_age: parse_age(prune_matches.value_of("max-age").unwrap())?,
+                    max_size: parse_size(prune_matches.value_of("max-size").unwrap())?,
+                }))
+            } else if cache_matches.subcommand_matches("clear").is_some() {
+                Some(Command::Cache(CacheAction::Clear))
+            } else {
+                Some(Command::Cache(CacheAction::Stats))
+            }
+        } else if let Some(preset_matches) = matches.subcommand_matches("preset") {
+            let preset = |m: &ArgMatches| m.value_of("preset").unwrap().to_string();
+            if let Some(show_matches) = preset_matches.subcommand_matches("show") {
+                Some(Command::Preset(PresetAction::Show(preset(show_matches))))
+            } else if let Some(test_matches) = preset_matches.subcommand_matches("test") {
+                Some(Command::Preset(PresetAction::Test {
+                    preset: preset(test_matches),
+                    directory: test_matches.value_of("directory").unwrap().to_string(),
+                }))
+            } else {
+                Some(Command::Preset(PresetAction::List))
+            }
         } else if let Some(diff_matches) = matches.subcommand_matches("diff") {
             let left = diff_matches
                 .value_of("left")
@@ -1271,7 +1701,8 @@ impl Args {
             || matches.is_present("allocated-size")
             || matches.is_present("extended")
             || matches.is_present("context")
-            || matches.is_present("mounts");
+            || matches.is_present("mounts")
+            || matches.is_present("mime");
 
         let has_view_format_flag = matches.is_present("long")
             || matches.is_present("tree")
@@ -1281,7 +1712,9 @@ impl Args {
             || matches.is_present("timeline")
             || matches.is_present("git")
             || matches.is_present("fuzzy")
-            || matches.is_present("recursive");
+            || matches.is_present("recursive")
+            || matches.is_present("format-template")
+            || matches.is_present("format");
         let has_format_flag = has_view_format_flag || has_long_metadata_flag;
 
         let preset_names: Vec<String> = matches
@@ -1293,37 +1726,90 @@ impl Args {
         let mut size_raw = matches.value_of("size").map(String::from);
         let mut modified_raw = matches.value_of("modified").map(String::from);
         let mut created_raw = matches.value_of("created").map(String::from);
+        let mut accessed_raw = matches.value_of("accessed").map(String::from);
         let mut preset_refinements: Vec<String> = Vec::new();
+        let mut where_raw: Vec<String> = Vec::new();
+        let mut owner_raw = matches.value_of("owner").map(String::from);
+        let mut group_raw = matches.value_of("group").map(String::from);
+        let mut perm_raw: Vec<String> = Vec::new();
+        let mut setuid = matches.is_present("setuid");
+        let mut world_writable = matches.is_present("world-writable");
 
-        for preset in &preset_names {
-            let preset_cfg = config
-                .filter
-                .presets
-                .get(preset)
-                .ok_or_else(|| LlaError::Filter(format!("Unknown preset '{}'", preset)))?;
-
-            if let Some(pattern) = &preset_cfg.filter {
-                pattern_filters.push(pattern.clone());
-            }
+        for spec in &preset_names {
+            let preset = config.resolve_preset(spec)?;
+            pattern_filters.extend(preset.filters);
 
             if size_raw.is_none() {
-                size_raw = preset_cfg.size.clone();
+                size_raw = preset.size;
             }
             if modified_raw.is_none() {
-                modified_raw = preset_cfg.modified.clone();
+                modified_raw = preset.modified;
             }
             if created_raw.is_none() {
-                created_raw = preset_cfg.created.clone();
+                created_raw = preset.created;
+            }
+            if accessed_raw.is_none() {
+                accessed_raw = preset.accessed;
             }
 
-            preset_refinements.extend(preset_cfg.refine.clone());
+            preset_refinements.extend(preset.refine);
+            where_raw.extend(preset.where_exprs);
+            if owner_raw.is_none() {
+                owner_raw = preset.owner;
+            }
+            if group_raw.is_none() {
+                group_raw = preset.group;
+            }
+            perm_raw.extend(preset.perms);
+            setuid |= preset.setuid;
+            world_writable |= preset.world_writable;
         }
 
         if let Some(cli_filter) = matches.value_of("filter") {
             pattern_filters.push(cli_filter.to_string());
         }
 
-        let filter = combine_pattern_filters(&pattern_filters);
+        let mut filter = combine_pattern_filters(&pattern_filters);
+        // A lone `fuzzy:` filter is a fuzzy query, so its matches are scored.
+        let fuzzy_query = match matches.value_of("fuzzy-query") {
+            Some(query) => Some(query.to_string()),
+            None => match filter.as_deref().and_then(|f| f.strip_prefix("fuzzy:")) {
+                Some(query) if !query.contains(" AND ") && !query.contains(" OR ") => {
+                    let query = query.to_string();
+                    filter = None;
+                    Some(query)
+                }
+                _ => None,
+            },
+        };
+        // `--sort` defaults to the configured order, which a fuzzy query
+        // replaces unless the flag is given.
+        let sort_by = match matches.value_of("sort") {
+            Some(spec) => spec.to_string(),
+            None if fuzzy_query.is_some() => "score".to_string(),
+            None => {
+                let configured = config.default_sort_keys();
+                let keys: Vec<&str> = configured
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|key| key.trim_start_matches('-') != "score")
+                    .collect();
+                if keys.is_empty() {
+                    "name".to_string()
+                } else {
+                    keys.join(",")
+                }
+            }
+        };
+        if fuzzy_query.is_none()
+            && SortKey::parse_list(&sort_by)
+                .iter()
+                .any(|key| key.name == "score")
+        {
+            return Err(LlaError::Parse(
+                "--sort score needs --fuzzy-query or --filter fuzzy:<query>".to_string(),
+            ));
+        }
         let refine_filters = {
             let mut refinements = preset_refinements;
             if let Some(values) = matches.values_of("refine") {
@@ -1335,6 +1821,7 @@ impl Args {
         let size_filter_raw = size_raw.clone();
         let modified_filter_raw = modified_raw.clone();
         let created_filter_raw = created_raw.clone();
+        let accessed_filter_raw = accessed_raw.clone();
         let size_filter = match &size_raw {
             Some(raw) => Some(parse_size_range(raw)?),
             None => None,
@@ -1348,6 +1835,47 @@ impl Args {
             Some(raw) => Some(parse_time_range(raw, now)?),
             None => None,
         };
+        let accessed_filter = match &accessed_raw {
+            Some(raw) => Some(parse_time_range(raw, now)?),
+            None => None,
+        };
+        let newer_than = matches.value_of("newer").map(reference_mtime).transpose()?;
+        let older_than = matches.value_of("older").map(reference_mtime).transpose()?;
+        if let Some(values) = matches.values_of("where") {
+            where_raw.extend(values.map(String::from));
+        }
+        let where_filters = where_raw
+            .iter()
+            .map(|raw| WhereExpr::parse(raw, now))
+            .collect::<Result<Vec<_>>>()?;
+        let field_filters = matches
+            .values_of("field-filter")
+            .map(|values| values.map(FieldFilter::parse).collect::<Result<Vec<_>>>())
+            .transpose()?
+            .unwrap_or_default();
+        let owner_filter = owner_raw.as_deref().map(resolve_user).transpose()?;
+        let group_filter = group_raw.as_deref().map(resolve_group).transpose()?;
+        if let Some(values) = matches.values_of("perm") {
+            perm_raw.extend(values.map(String::from));
+        }
+        if setuid {
+            perm_raw.push("-u+s".to_string());
+        }
+        if world_writable {
+            perm_raw.push("-o+w".to_string());
+        }
+        let perm_filters = perm_raw
+            .iter()
+            .map(|raw| PermFilter::parse(raw))
+            .collect::<Result<Vec<_>>>()?;
+        let content_types = matches
+            .values_of("type")
+            .map(|values| values.filter_map(ContentType::parse).collect())
+            .unwrap_or_default();
+        let git_status = matches
+            .values_of("git-status")
+            .map(|values| values.filter_map(GitStatusKind::parse).collect())
+            .unwrap_or_default();
 
         let search_pipelines = matches
             .values_of("search-pipe")
@@ -1364,8 +1892,31 @@ impl Args {
             .to_string();
         validate_long_date_format("date-format", &date_format)?;
 
+        let operands: Vec<String> = matches
+            .values_of("directory")
+            .map(|values| values.map(String::from).collect())
+            .unwrap_or_else(|| vec![".".to_string()]);
+
+        // --print0 and --quote produce output for other programs, so colors,
+        // icons and hyperlinks are switched off with them.
+        let plain_output =
+            (matches.is_present("print0") || matches.is_present("quote")).then(|| PlainOutput {
+                quoting: matches
+                    .value_of("quote")
+                    .and_then(QuotingStyle::parse)
+                    .unwrap_or(QuotingStyle::Literal),
+                print0: matches.is_present("print0"),
+            });
+
+        let watch = if matches.is_present("watch") {
+            Some(parse_watch_interval(matches.value_of("interval"))?)
+        } else {
+            None
+        };
+
         Ok(Args {
-            directory: matches.value_of("directory").unwrap_or(".").to_string(),
+            directory: operands[0].clone(),
+            operands,
             depth: matches
                 .value_of("depth")
                 .and_then(|s| s.parse().ok())
@@ -1389,9 +1940,25 @@ impl Args {
             fuzzy_format: matches.is_present("fuzzy"),
             recursive_format: matches.is_present("recursive")
                 || (!has_format_flag && config.default_format == "recursive"),
-            show_icons: matches.is_present("icons")
-                || (!matches.is_present("no-icons") && config.show_icons),
+            format_template: match (
+                matches.value_of("format-template"),
+                matches.value_of("format"),
+            ) {
+                (Some(source), _) => Some(Template::parse(source)?),
+                (None, Some(name)) => Some(named_template(config, name)?),
+                (None, None) if !has_format_flag => config
+                    .formatters
+                    .templates
+                    .contains_key(&config.default_format)
+                    .then(|| named_template(config, &config.default_format))
+                    .transpose()?,
+                (None, None) => None,
+            },
+            show_icons: plain_output.is_none()
+                && (matches.is_present("icons")
+                    || (!matches.is_present("no-icons") && config.show_icons)),
             hyperlinks: match matches.value_of("hyperlink") {
+                _ if plain_output.is_some() => false,
                 Some("always") => true,
                 Some("auto") | Some("automatic") => {
                     atty::is(atty::Stream::Stdout)
@@ -1399,17 +1966,28 @@ impl Args {
                 }
                 _ => false,
             },
-            no_color: matches.is_present("no-color"),
-            sort_by: matches
-                .value_of("sort")
-                .unwrap_or(&config.default_sort)
-                .to_string(),
+            no_color: matches.is_present("no-color") || plain_output.is_some(),
+            sort_by,
             sort_reverse: matches.is_present("sort-reverse"),
             sort_dirs_first: matches.is_present("sort-dirs-first") || config.sort.dirs_first,
             sort_case_sensitive: matches.is_present("sort-case-sensitive")
                 || config.sort.case_sensitive,
             sort_natural: matches.is_present("sort-natural") || config.sort.natural,
+            sort_collation: matches
+                .value_of("sort-collation")
+                .and_then(Collation::parse)
+                .unwrap_or_else(|| config.sort_collation()),
+            limit: match (matches.value_of("limit"), matches.value_of("tail")) {
+                (Some(count), _) => count.parse().ok().map(EntryLimit::Head),
+                (None, Some(count)) => count.parse().ok().map(EntryLimit::Tail),
+                (None, None) => None,
+            },
             filter,
+            fuzzy_query,
+            fuzzy_min_score: matches
+                .value_of("fuzzy-min-score")
+                .and_then(|score| score.parse().ok())
+                .unwrap_or(0),
             presets: preset_names,
             size_filter,
             size_filter_raw,
@@ -1417,6 +1995,17 @@ impl Args {
             modified_filter_raw,
             created_filter,
             created_filter_raw,
+            accessed_filter,
+            accessed_filter_raw,
+            newer_than,
+            older_than,
+            where_filters,
+            field_filters,
+            content_types,
+            git_status,
+            owner_filter,
+            group_filter,
+            perm_filters,
             case_sensitive: matches.is_present("case-sensitive") || config.filter.case_sensitive,
             refine_filters,
             enable_plugin: matches
@@ -1453,6 +2042,20 @@ impl Args {
             } else {
                 config.filter.respect_gitignore
             },
+            one_file_system: if matches.is_present("one-file-system") {
+                true
+            } else if matches.is_present("cross-file-systems") {
+                false
+            } else {
+                config.one_file_system
+            },
+            llaignore: if matches.is_present("no-llaignore") {
+                LlaIgnoreMode::Off
+            } else if matches.is_present("explain-ignore") {
+                LlaIgnoreMode::Explain
+            } else {
+                LlaIgnoreMode::On
+            },
             permission_format: matches
                 .value_of("permission-format")
                 .unwrap_or(&config.permission_format)
@@ -1484,6 +2087,10 @@ impl Args {
                 ),
             show_mounts: matches.is_present("mounts")
                 || configured_column(config, &["mount", "mounts"]),
+            show_mime: matches.is_present("mime")
+                || configured_column(config, &["mime", "mime_type", "mime-type", "content_type"]),
+            classify: matches.is_present("classify"),
+            plain_output,
             output_mode: {
                 let pretty = matches.is_present("pretty");
                 if matches.is_present("json") {
@@ -1496,6 +2103,14 @@ impl Args {
                     OutputMode::Human
                 }
             },
+            path_list: if matches.is_present("stdin") {
+                Some(PathListSource::Stdin)
+            } else {
+                matches
+                    .value_of("from-file")
+                    .map(|path| PathListSource::File(PathBuf::from(path)))
+            },
+            watch,
             command,
             search: matches.value_of("search").map(String::from),
             search_context: matches
@@ -1507,6 +2122,109 @@ impl Args {
     }
 }
 
+/// The template saved as `name` under `[formatters.templates]`.
+fn named_template(config: &Config, name: &str) -> Result<Template> {
+    let source = config.formatters.templates.get(name).ok_or_else(|| {
+        LlaError::Parse(if config.formatters.templates.is_empty() {
+            format!(
+                "Unknown format '{}' (no templates are saved under [formatters.templates])",
+                name
+            )
+        } else {
+            let names: Vec<&str> = config
+                .formatters
+                .templates
+                .keys()
+                .map(String::as_str)
+                .collect();
+            format!(
+                "Unknown format '{}' (templates: {})",
+                name,
+                names.join(", ")
+            )
+        })
+    })?;
+    Template::parse(source)
+}
+
+fn validate_entry_count(value: &str) -> std::result::Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(()),
+        _ => Err(format!("expected a positive whole number, got '{}'", value)),
+    }
+}
+
+fn parse_watch_interval(value: Option<&str>) -> Result<Duration> {
+    let Some(value) = value else {
+        return Ok(Duration::from_millis(300));
+    };
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs > 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| {
+            LlaError::Parse(format!(
+                "Invalid --interval '{}': expected a positive number of seconds",
+                value
+            ))
+        })
+}
+
+/// The full-precision modification time of a `--newer`/`--older` reference.
+fn reference_mtime(path: &str) -> Result<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| {
+            LlaError::Filter(format!(
+                "Cannot read the modification time of '{}': {}",
+                path, e
+            ))
+        })
+}
+
+/// Validates `--sort` key lists while offering the single keys to shell
+/// completions, which a plain validator cannot do.
+#[derive(Clone)]
+struct SortSpecParser;
+
+impl clap::builder::TypedValueParser for SortSpecParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&Arg>,
+        value: &std::ffi::OsStr,
+    ) -> std::result::Result<String, clap::Error> {
+        let value = value.to_string_lossy();
+        if is_valid_sort_spec(&value) {
+            return Ok(value.into_owned());
+        }
+        let arg = arg.map_or_else(|| "--sort".to_string(), ToString::to_string);
+        Err(cmd.clone().error(
+            clap::ErrorKind::InvalidValue,
+            format!(
+                "Invalid value '{}' for '{}': expected a comma-separated list of {}, score, or field:<name>, each optionally prefixed with -",
+                value,
+                arg,
+                SORT_KEYS.join(", ")
+            ),
+        ))
+    }
+
+    fn possible_values(
+        &self,
+    ) -> Option<Box<dyn Iterator<Item = clap::PossibleValue<'static>> + '_>> {
+        Some(Box::new(
+            SORT_KEYS
+                .iter()
+                .chain(["score"].iter())
+                .map(|key| clap::PossibleValue::new(key)),
+        ))
+    }
+}
+
 fn configured_column(config: &Config, names: &[&str]) -> bool {
     config
         .formatters
@@ -1580,6 +2298,7 @@ mod rich_metadata_tests {
                 "--extended",
                 "--context",
                 "--mounts",
+                "--mime",
                 "--hyperlink=always",
             ])
             .unwrap();
@@ -1592,6 +2311,7 @@ mod rich_metadata_tests {
         assert!(args.show_xattrs);
         assert!(args.show_context);
         assert!(args.show_mounts);
+        assert!(args.show_mime);
         assert!(args.hyperlinks);
     }
 
@@ -1613,3 +2333,446 @@ mod rich_metadata_tests {
         assert!(!args.show_symlink_target);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(argv: &[&str]) -> Args {
+        let config = Config::default();
+        let matches = Args::build_cli(&config).try_get_matches_from(argv).unwrap();
+        Args::from_matches(&matches, &config).unwrap()
+    }
+
+    #[test]
+    fn accepts_multiple_path_operands() {
+        let args = parse(&["lla", "src", "tests", "Cargo.toml"]);
+        assert_eq!(args.operands, vec!["src", "tests", "Cargo.toml"]);
+        assert_eq!(args.directory, "src");
+
+        let scoped = args.for_operand("Cargo.toml");
+        assert_eq!(scoped.directory, "Cargo.toml");
+        assert_eq!(scoped.operands, vec!["Cargo.toml"]);
+    }
+
+    #[test]
+    fn defaults_to_current_directory_and_keeps_subcommands() {
+        let args = parse(&["lla"]);
+        assert_eq!(args.operands, vec!["."]);
+
+        let args = parse(&["lla", "diff", "left", "right"]);
+        assert!(matches!(args.command, Some(Command::Diff(_))));
+    }
+
+    #[test]
+    fn parses_cache_subcommands() {
+        assert!(matches!(
+            parse(&["lla", "cache", "stats"]).command,
+            Some(Command::Cache(CacheAction::Stats))
+        ));
+        let Some(Command::Cache(action)) = parse(&[
+            "lla",
+            "cache",
+            "prune",
+            "--max-age",
+            "7d",
+            "--max-size",
+            "1M",
+        ])
+        .command
+        else {
+            panic!("expected a cache command");
+        };
+        assert_eq!(
+            action,
+            CacheAction::Prune {
+                max_age: Duration::from_secs(7 * 86_400),
+                max_size: 1024 * 1024,
+            }
+        );
+    }
+
+    #[test]
+    fn watch_interval_defaults_and_validates() {
+        assert_eq!(parse(&["lla"]).watch, None);
+        assert_eq!(
+            parse(&["lla", "--watch"]).watch,
+            Some(Duration::from_millis(300))
+        );
+        assert_eq!(
+            parse(&["lla", "--watch", "--interval", "1.5"]).watch,
+            Some(Duration::from_millis(1500))
+        );
+        assert!(parse_watch_interval(Some("0")).is_err());
+        assert!(parse_watch_interval(Some("soon")).is_err());
+    }
+
+    #[test]
+    fn one_file_system_follows_config_unless_overridden() {
+        assert!(!parse(&["lla", "-R"]).one_file_system);
+        assert!(parse(&["lla", "-R", "-x"]).one_file_system);
+
+        let config = Config {
+            one_file_system: true,
+            ..Config::default()
+        };
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla", "-R", "--cross-file-systems"])
+            .unwrap();
+        assert!(
+            !Args::from_matches(&matches, &config)
+                .unwrap()
+                .one_file_system
+        );
+    }
+
+    #[test]
+    fn plain_output_disables_decorations() {
+        let args = parse(&["lla", "-0", "--icons", "--hyperlink=always"]);
+        assert_eq!(
+            args.plain_output,
+            Some(PlainOutput {
+                quoting: QuotingStyle::Literal,
+                print0: true,
+            })
+        );
+        assert!(args.no_color);
+        assert!(!args.show_icons);
+        assert!(!args.hyperlinks);
+
+        let args = parse(&["lla", "--quote=shell"]);
+        assert_eq!(
+            args.plain_output.map(|plain| plain.quoting),
+            Some(QuotingStyle::Shell)
+        );
+        assert!(parse(&["lla"]).plain_output.is_none());
+    }
+
+    #[test]
+    fn where_expressions_combine_presets_and_flags() {
+        let mut config = Config::default();
+        config.filter.presets.insert(
+            "big".to_string(),
+            crate::config::FilterPreset {
+                where_expr: Some("size > 10M".to_string()),
+                ..Default::default()
+            },
+        );
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla", "--preset", "big", "--where", "ext = rs", "src"])
+            .unwrap();
+        let args = Args::from_matches(&matches, &config).unwrap();
+        let sources: Vec<&str> = args.where_filters.iter().map(|e| e.source()).collect();
+        assert_eq!(sources, vec!["size > 10M", "ext = rs"]);
+        assert_eq!(args.operands, vec!["src"]);
+
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla", "--where", "size >"])
+            .unwrap();
+        assert!(matches!(
+            Args::from_matches(&matches, &config),
+            Err(LlaError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn field_filters_are_repeatable_and_validated() {
+        let args = parse(&[
+            "lla",
+            "--field-filter",
+            "complexity>20",
+            "--field-filter",
+            "tags~release",
+        ]);
+        let filters: Vec<String> = args.field_filters.iter().map(ToString::to_string).collect();
+        assert_eq!(filters, vec!["complexity>20", "tags~release"]);
+
+        let config = Config::default();
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla", "--field-filter", "complexity"])
+            .unwrap();
+        assert!(Args::from_matches(&matches, &config).is_err());
+    }
+
+    #[test]
+    fn sort_accepts_plugin_field_keys() {
+        assert_eq!(
+            parse(&["lla", "--sort", "field:complexity"]).sort_by,
+            "field:complexity"
+        );
+
+        let config = Config::default();
+        for key in ["bogus", "field:"] {
+            assert!(Args::build_cli(&config)
+                .try_get_matches_from(["lla", "--sort", key])
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn content_types_accept_lists_and_repeats() {
+        let args = parse(&["lla", "--type", "image,archive", "--type", "text"]);
+        assert_eq!(
+            args.content_types,
+            vec![ContentType::Image, ContentType::Archive, ContentType::Text]
+        );
+
+        let config = Config::default();
+        assert!(Args::build_cli(&config)
+            .try_get_matches_from(["lla", "--type", "video"])
+            .is_err());
+    }
+
+    #[test]
+    fn permission_filters_combine_presets_flags_and_shortcuts() {
+        let mut config = Config::default();
+        config.filter.presets.insert(
+            "risky".to_string(),
+            crate::config::FilterPreset {
+                owner: Some("0".to_string()),
+                perm: Some("/g+w".to_string()),
+                setuid: true,
+                ..Default::default()
+            },
+        );
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from([
+                "lla", "--preset", "risky", "--perm", "-644", "--group", "20",
+            ])
+            .unwrap();
+        let args = Args::from_matches(&matches, &config).unwrap();
+        assert_eq!(args.owner_filter, Some(0));
+        assert_eq!(args.group_filter, Some(20));
+        let perms: Vec<String> = args.perm_filters.iter().map(ToString::to_string).collect();
+        assert_eq!(perms, vec!["/g+w", "-644", "-u+s"]);
+    }
+
+    #[test]
+    fn newer_and_older_take_their_bound_from_a_reference_file() {
+        let reference = std::env::current_exe().unwrap();
+        let reference = reference.to_str().unwrap();
+        let args = parse(&["lla", "--newer", reference, "--accessed", "this-week"]);
+        let expected = std::fs::metadata(reference).unwrap().modified().unwrap();
+        assert_eq!(args.newer_than, Some(expected));
+        assert!(args.accessed_filter.is_some());
+
+        let config = Config::default();
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla", "--older", "/no/such/reference"])
+            .unwrap();
+        assert!(Args::from_matches(&matches, &config).is_err());
+    }
+
+    #[test]
+    fn llaignore_is_on_unless_disabled_or_explained() {
+        assert_eq!(parse(&["lla"]).llaignore, LlaIgnoreMode::On);
+        assert_eq!(
+            parse(&["lla", "--no-llaignore"]).llaignore,
+            LlaIgnoreMode::Off
+        );
+        assert_eq!(
+            parse(&["lla", "-R", "--explain-ignore"]).llaignore,
+            LlaIgnoreMode::Explain
+        );
+        assert!(Args::build_cli(&Config::default())
+            .try_get_matches_from(["lla", "--no-llaignore", "--explain-ignore"])
+            .is_err());
+    }
+
+    #[test]
+    fn git_status_accepts_lists_and_repeats() {
+        let args = parse(&[
+            "lla",
+            "--git-status",
+            "modified,untracked",
+            "--git-status",
+            "staged",
+        ]);
+        assert_eq!(
+            args.git_status,
+            [
+                GitStatusKind::Modified,
+                GitStatusKind::Untracked,
+                GitStatusKind::Staged
+            ]
+        );
+        assert!(Args::build_cli(&Config::default())
+            .try_get_matches_from(["lla", "--git-status", "dirty"])
+            .is_err());
+    }
+
+    #[test]
+    fn fuzzy_queries_sort_by_score_unless_told_otherwise() {
+        let args = parse(&["lla", "--filter", "fuzzy:main"]);
+        assert_eq!(args.fuzzy_query.as_deref(), Some("main"));
+        assert_eq!(args.filter, None);
+        assert_eq!(args.sort_by, "score");
+
+        let args = parse(&[
+            "lla",
+            "--fuzzy-query",
+            "main",
+            "-s",
+            "size",
+            "--fuzzy-min-score",
+            "40",
+        ]);
+        assert_eq!(args.sort_by, "size");
+        assert_eq!(args.fuzzy_min_score, 40);
+
+        let args = parse(&["lla", "--filter", "fuzzy:main AND .rs"]);
+        assert_eq!(args.fuzzy_query, None);
+        assert_eq!(args.sort_by, "name");
+
+        let matches = Args::build_cli(&Config::default())
+            .try_get_matches_from(["lla", "--sort", "score"])
+            .unwrap();
+        assert!(Args::from_matches(&matches, &Config::default()).is_err());
+    }
+
+    #[test]
+    fn parameterized_presets_extend_their_parents() {
+        let mut config = Config::default();
+        config.filter.presets.insert(
+            "source".to_string(),
+            crate::config::FilterPreset {
+                filter: Some("ext:rs".to_string()),
+                ..Default::default()
+            },
+        );
+        config.filter.presets.insert(
+            "stale".to_string(),
+            crate::config::FilterPreset {
+                extends: vec!["source".to_string()],
+                params: [("days".to_string(), "30".to_string())].into(),
+                modified: Some("<${days}d".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let args = Args::with_preset(&config, "stale:days=90", "src").unwrap();
+        assert_eq!(args.filter.as_deref(), Some("ext:rs"));
+        assert_eq!(args.modified_filter_raw.as_deref(), Some("<90d"));
+        assert_eq!(args.presets, ["stale:days=90"]);
+        assert_eq!(args.operands, ["src"]);
+
+        let Some(Command::Preset(action)) = parse(&["lla", "preset", "test", "stale"]).command
+        else {
+            panic!("expected a preset command");
+        };
+        assert_eq!(
+            action,
+            PresetAction::Test {
+                preset: "stale".to_string(),
+                directory: ".".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn sort_accepts_key_lists_and_configured_keys() {
+        assert_eq!(
+            parse(&["lla", "--sort", "size,-modified,name"]).sort_by,
+            "size,-modified,name"
+        );
+        assert_eq!(parse(&["lla", "-s", "-size"]).sort_by, "-size");
+        assert!(Args::build_cli(&Config::default())
+            .try_get_matches_from(["lla", "--sort", "size,,name"])
+            .is_err());
+
+        let mut config = Config::default();
+        config.sort.keys = vec!["-date".to_string(), "name".to_string()];
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla"])
+            .unwrap();
+        let args = Args::from_matches(&matches, &config).unwrap();
+        assert_eq!(args.sort_by, "-date,name");
+
+        config.sort.keys = vec!["score".to_string(), "size".to_string()];
+        let args = Args::from_matches(&matches, &config).unwrap();
+        assert_eq!(args.sort_by, "size");
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla", "--sort", "size,score"])
+            .unwrap();
+        assert!(Args::from_matches(&matches, &config).is_err());
+    }
+
+    #[test]
+    fn sort_collation_defaults_from_config() {
+        assert_eq!(parse(&["lla"]).sort_collation, Collation::Bytes);
+
+        let mut config = Config::default();
+        config.sort.collation = "unicode".to_string();
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla"])
+            .unwrap();
+        let args = Args::from_matches(&matches, &config).unwrap();
+        assert_eq!(args.sort_collation, Collation::Unicode);
+
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla", "--sort-collation", "bytes"])
+            .unwrap();
+        let args = Args::from_matches(&matches, &config).unwrap();
+        assert_eq!(args.sort_collation, Collation::Bytes);
+    }
+
+    #[test]
+    fn limit_head_and_tail_pick_an_end() {
+        assert_eq!(parse(&["lla"]).limit, None);
+        assert_eq!(
+            parse(&["lla", "--limit", "20"]).limit,
+            Some(EntryLimit::Head(20))
+        );
+        assert_eq!(
+            parse(&["lla", "--head", "3"]).limit,
+            Some(EntryLimit::Head(3))
+        );
+        assert_eq!(
+            parse(&["lla", "--tail", "5"]).limit,
+            Some(EntryLimit::Tail(5))
+        );
+        for argv in [
+            &["lla", "--limit", "0"][..],
+            &["lla", "--tail", "many"],
+            &["lla", "--head", "2", "--tail", "2"],
+        ] {
+            assert!(Args::build_cli(&Config::default())
+                .try_get_matches_from(argv)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn format_selects_a_saved_template() {
+        let mut config = Config::default();
+        config
+            .formatters
+            .templates
+            .insert("compact".to_string(), "{name} {size:>8h}".to_string());
+        let parse_with = |argv: &[&str]| {
+            let matches = Args::build_cli(&config).try_get_matches_from(argv).unwrap();
+            Args::from_matches(&matches, &config)
+        };
+
+        assert!(parse_with(&["lla"]).unwrap().format_template.is_none());
+        assert!(parse_with(&["lla", "--format", "compact"])
+            .unwrap()
+            .format_template
+            .is_some());
+        assert!(parse_with(&["lla", "--format-template", "{path}"])
+            .unwrap()
+            .format_template
+            .is_some());
+        assert!(parse_with(&["lla", "--format-template", "{path"]).is_err());
+        assert!(Args::build_cli(&config)
+            .try_get_matches_from(["lla", "--format", "missing"])
+            .is_err());
+
+        config.default_format = "compact".to_string();
+        let matches = Args::build_cli(&config)
+            .try_get_matches_from(["lla"])
+            .unwrap();
+        let args = Args::from_matches(&matches, &config).unwrap();
+        assert!(args.format_template.is_some() && !args.long_format);
+    }
+}
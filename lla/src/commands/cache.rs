@@ -0,0 +1,43 @@
+use crate::commands::args::CacheAction;
+use crate::error::Result;
+use crate::utils::cache::ListingCache;
+use chrono::{DateTime, Local};
+use colored::*;
+use lla_plugin_utils::ui::format_size;
+use std::time::SystemTime;
+
+pub fn handle_cache(action: &CacheAction) -> Result<()> {
+    let cache = ListingCache::new()?;
+    match action {
+        CacheAction::Stats => {
+            let stats = cache.stats()?;
+            println!("{} {}", "Directory:".bold(), stats.directory.display());
+            println!("{} {}", "Listings: ".bold(), stats.listings);
+            println!("{} {}", "Size:     ".bold(), format_size(stats.total_bytes));
+            if let (Some(oldest), Some(newest)) = (stats.oldest, stats.newest) {
+                println!("{} {}", "Oldest:   ".bold(), format_time(oldest));
+                println!("{} {}", "Newest:   ".bold(), format_time(newest));
+            }
+        }
+        CacheAction::Clear => {
+            let removed = cache.clear()?;
+            println!("{} Removed {} cached listing(s)", "✓".green(), removed);
+        }
+        CacheAction::Prune { max_age, max_size } => {
+            let (removed, bytes) = cache.prune(*max_age, *max_size)?;
+            println!(
+                "{} Removed {} cached listing(s), freeing {}",
+                "✓".green(),
+                removed,
+                format_size(bytes)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Local>::from(time)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
@@ -1,9 +1,11 @@
 use crate::commands::args::{Args, Command, InstallSource, ShortcutAction};
+use crate::commands::cache;
 use crate::commands::diff;
 use crate::commands::file_utils::list_directory;
 use crate::commands::init_wizard;
 use crate::commands::jump;
 use crate::commands::plugin_utils::{handle_plugin_action, list_plugins, use_plugins};
+use crate::commands::preset;
 use crate::commands::search::run_search;
 use crate::config::{self, Config, ShortcutCommand};
 use crate::error::{LlaError, Result};
@@ -198,7 +200,7 @@ pub fn handle_command(
         }
         Some(Command::ListPlugins) => list_plugins(plugin_manager),
         Some(Command::Use) => use_plugins(plugin_manager),
-        Some(Command::Diff(diff_args)) => diff::run(diff_args.clone()),
+        Some(Command::Diff(diff_args)) => diff::run(diff_args.clone(), config, args.llaignore),
         Some(Command::InitConfig { defaults_only }) => {
             if *defaults_only {
                 config::initialize_config()
@@ -262,6 +264,8 @@ pub fn handle_command(
             plugin_manager.print_manifest(&resolved, true)
         }
         Some(Command::Jump(action)) => jump::handle_jump(action, config),
+        Some(Command::Cache(action)) => cache::handle_cache(action),
+        Some(Command::Preset(action)) => preset::handle_preset(action, config, plugin_manager),
         Some(Command::Clean) => unreachable!(),
         None => {
             if args.search.is_some() {
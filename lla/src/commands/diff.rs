@@ -1,7 +1,10 @@
 use crate::commands::args::{DiffCommand, DiffTarget};
+use crate::config::Config;
 use crate::error::{LlaError, Result};
 use crate::theme;
 use crate::utils::color::colorize_size;
+use crate::utils::exclude::ExcludeSet;
+use crate::utils::llaignore::LlaIgnoreMode;
 use colored::Colorize;
 use ignore::WalkBuilder;
 use similar::TextDiff;
@@ -12,7 +15,7 @@ use std::process::Command;
 use std::str;
 use unicode_width::UnicodeWidthStr;
 
-pub fn run(diff: DiffCommand) -> Result<()> {
+pub fn run(diff: DiffCommand, config: &Config, llaignore: LlaIgnoreMode) -> Result<()> {
     let DiffCommand { left, target } = diff;
     let left_entry = resolve_path(&left)?;
 
@@ -21,7 +24,7 @@ pub fn run(diff: DiffCommand) -> Result<()> {
             let right_entry = resolve_path(&right)?;
             match (left_entry.kind, right_entry.kind) {
                 (PathKind::Directory, PathKind::Directory) => {
-                    diff_directories(&left_entry.path, &right_entry.path)
+                    diff_directories(&left_entry.path, &right_entry.path, config, llaignore)
                 }
                 (PathKind::File, PathKind::File) => diff_files(&left_entry.path, &right_entry.path),
                 (PathKind::Directory, PathKind::File) => Err(LlaError::Other(format!(
@@ -37,7 +40,9 @@ pub fn run(diff: DiffCommand) -> Result<()> {
             }
         }
         DiffTarget::Git { reference } => match left_entry.kind {
-            PathKind::Directory => diff_directory_with_git(&left_entry.path, &reference),
+            PathKind::Directory => {
+                diff_directory_with_git(&left_entry.path, &reference, config, llaignore)
+            }
             PathKind::File => diff_file_with_git(&left_entry.path, &reference),
         },
     }
@@ -87,9 +92,14 @@ fn resolve_path(path: &str) -> Result<ResolvedPath> {
     })
 }
 
-fn diff_directories(left: &Path, right: &Path) -> Result<()> {
-    let left_entries = collect_local_entries(left)?;
-    let right_entries = collect_local_entries(right)?;
+fn diff_directories(
+    left: &Path,
+    right: &Path,
+    config: &Config,
+    llaignore: LlaIgnoreMode,
+) -> Result<()> {
+    let left_entries = collect_local_entries(left, &ExcludeSet::new(config, left, llaignore))?;
+    let right_entries = collect_local_entries(right, &ExcludeSet::new(config, right, llaignore))?;
 
     render_diff(
         &left.display().to_string(),
@@ -99,9 +109,20 @@ fn diff_directories(left: &Path, right: &Path) -> Result<()> {
     )
 }
 
-fn diff_directory_with_git(left: &Path, reference: &str) -> Result<()> {
-    let left_entries = collect_local_entries(left)?;
-    let git_entries = collect_git_entries(left, reference)?;
+fn diff_directory_with_git(
+    left: &Path,
+    reference: &str,
+    config: &Config,
+    llaignore: LlaIgnoreMode,
+) -> Result<()> {
+    let excludes = ExcludeSet::new(config, left, llaignore);
+    let left_entries = collect_local_entries(left, &excludes)?;
+    let mut git_entries = collect_git_entries(left, reference)?;
+    // Drop excluded paths from the git side too, otherwise they would all show
+    // up as removed from the working tree.
+    if !excludes.is_empty() {
+        git_entries.retain(|path, _| !excludes.contains(&left.join(path)));
+    }
 
     // When comparing against git we treat the git reference as the "left"/baseline
     // side so that additions/removals are reported from the perspective of the
@@ -323,16 +344,23 @@ fn print_text_diff(left_label: &str, right_label: &str, left_text: &str, right_t
     }
 }
 
-fn collect_local_entries(root: &Path) -> Result<BTreeMap<String, u64>> {
+fn collect_local_entries(root: &Path, excludes: &ExcludeSet) -> Result<BTreeMap<String, u64>> {
     let mut entries = BTreeMap::new();
 
+    let excludes = excludes.clone();
     let mut builder = WalkBuilder::new(root);
     builder
         .follow_links(false)
         .hidden(false)
         .git_ignore(true)
         .git_exclude(true)
-        .parents(true);
+        .parents(true)
+        .filter_entry(move |entry| {
+            !excludes.contains_entry(
+                entry.path(),
+                entry.file_type().is_some_and(|ft| ft.is_dir()),
+            )
+        });
 
     for dent in builder.build() {
         let entry = dent.map_err(|e| {
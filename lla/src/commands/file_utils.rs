@@ -1,32 +1,50 @@
-use crate::commands::args::{Args, OutputMode};
+use crate::commands::args::{Args, OutputMode, PathListSource};
+use crate::commands::{path_list, watch};
 use crate::config::Config;
 use crate::error::{LlaError, Result};
 use crate::filter::{
-    CaseInsensitiveFilter, CompositeFilter, ExtensionFilter, FileFilter, FilterOperation,
-    GlobFilter, PatternFilter, RegexFilter,
+    CaseInsensitiveFilter, CompositeFilter, ExtensionFilter, FieldMatcher, FileFilter,
+    FilterOperation, FuzzyFilter, FuzzyQuery, GitStatusFilter, GlobFilter, PatternFilter,
+    RegexFilter,
 };
 use crate::formatter::column_config::parse_columns;
+use crate::formatter::serializable::{
+    FUZZY_POSITIONS_FIELD, FUZZY_SCORE_FIELD, MIME_FIELD, OPERAND_FIELD,
+};
 use crate::formatter::{csv as csv_writer, json as json_writer};
 use crate::formatter::{
     DefaultFormatter, FileFormatter, FuzzyFormatter, GitFormatter, GridFormatter, LongFormatter,
-    RecursiveFormatter, SizeMapFormatter, TableFormatter, TimelineFormatter, TreeFormatter,
+    RecursiveFormatter, SizeMapFormatter, TableFormatter, TemplateFormatter, TimelineFormatter,
+    TreeFormatter,
 };
 use crate::lister::{
     archive as archive_lister, BasicLister, FileLister, FuzzyLister, RecursiveLister,
 };
 use crate::plugin::PluginManager;
-use crate::sorter::{AlphabeticalSorter, DateSorter, FileSorter, SizeSorter, SortOptions};
+use crate::sorter::{
+    AlphabeticalSorter, DateSorter, ExtensionSorter, FieldSorter, FileSorter, KindSorter,
+    MultiKeySorter, NumericSorter, OwnershipSorter, ScoreSorter, SizeSorter, SortEntry, SortKey,
+    SortKeys, SortOptions, TopK, VersionSorter,
+};
 use crate::utils::cache::ListingCache;
-use crate::utils::{fs_metadata, hyperlink};
+use crate::utils::dir_size::{DirSizeIndex, LazyDirSizes, SizeBasis};
+use crate::utils::exclude::ExcludeSet;
+use crate::utils::llaignore::LlaIgnoreMode;
+use crate::utils::{content_type, fs_metadata, hyperlink};
+use colored::Colorize;
 use ignore::WalkBuilder;
+use lla_plugin_interface::manifest::FieldDescriptor;
 use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata};
 use rayon::prelude::*;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
-use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
 
 pub fn list_directory(
     args: &Args,
@@ -35,8 +53,10 @@ pub fn list_directory(
     config_error: Option<crate::error::LlaError>,
 ) -> Result<()> {
     hyperlink::set_enabled(args.hyperlinks && matches!(args.output_mode, OutputMode::Human));
-    // Record directory visit for jump history (respect exclude_paths inside)
-    crate::commands::jump::record_visit(&args.directory, config);
+    // Record directory visits for jump history (respect exclude_paths inside)
+    for operand in &args.operands {
+        crate::commands::jump::record_visit(operand, config);
+    }
     if let Some(error) = config_error {
         eprintln!("Warning: {}", error);
     }
@@ -52,121 +72,294 @@ pub fn list_directory(
         }
     }
 
-    let lister = create_lister(args, config);
-    let sorter = create_sorter(args);
-    let filter = create_filter(args);
     let formatter = create_formatter(args, config);
-    let format = get_format(args);
+    if let Some(interval) = args.watch {
+        return watch::run(args, config, plugin_manager, formatter.as_ref(), interval);
+    }
+    render_listing(args, config, plugin_manager, formatter.as_ref())
+}
+
+/// Runs one pass of the list, filter, sort and format pipeline for the
+/// requested operands and writes the result to stdout.
+pub fn render_listing(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+    formatter: &dyn FileFormatter,
+) -> Result<()> {
+    if let Some(source) = &args.path_list {
+        return list_path_list(args, config, plugin_manager, formatter, source);
+    }
+    if args.operands.len() > 1 {
+        return list_operands(args, config, plugin_manager, formatter);
+    }
+
+    if operand_kind(&args.directory) == OperandKind::Directory && should_stream(args, formatter) {
+        return stream_directory(args, config, plugin_manager, formatter);
+    }
+
+    let mut decorated_files = collect_operand(args, config, plugin_manager)?;
+    if !matches!(args.output_mode, OutputMode::Human) {
+        tag_operand(&mut decorated_files, &args.directory);
+    }
+    write_entries(args, decorated_files, formatter, plugin_manager)
+}
+
+/// Collects the entries of every accessible operand, tagged with the operand
+/// they came from. Operands that cannot be accessed are skipped.
+pub fn collect_listing(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+) -> Result<Vec<DecoratedEntry>> {
+    let mut entries = Vec::new();
+    for operand in &args.operands {
+        if Path::new(operand).symlink_metadata().is_err() {
+            continue;
+        }
+        let mut operand_entries =
+            collect_operand(&args.for_operand(operand), config, plugin_manager)?;
+        tag_operand(&mut operand_entries, operand);
+        entries.extend(operand_entries);
+    }
+    Ok(entries)
+}
 
-    // Archive auto-detection branch
-    let p = std::path::Path::new(&args.directory);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OperandKind {
+    Archive,
+    File,
+    Directory,
+}
+
+fn operand_kind(operand: &str) -> OperandKind {
+    let p = Path::new(operand);
     let path_is_symlink = p
         .symlink_metadata()
         .map(|metadata| metadata.is_symlink())
         .unwrap_or(false);
-    let path_is_archive =
-        !path_is_symlink && p.is_file() && archive_lister::is_archive_path_str(&args.directory);
-    if path_is_archive {
-        let decorated_files =
-            list_and_decorate_archive_entries(args, &filter, plugin_manager, format)?;
-        let decorated_files = if !args.tree_format && !args.recursive_format {
-            sort_files(decorated_files, &sorter, args)?
-        } else {
-            decorated_files
-        };
+    if !path_is_symlink && p.is_file() && archive_lister::is_archive_path_str(operand) {
+        OperandKind::Archive
+    } else if p.is_file() || path_is_symlink {
+        OperandKind::File
+    } else {
+        OperandKind::Directory
+    }
+}
 
-        return match args.output_mode {
-            OutputMode::Human => {
-                let formatted_output = formatter.format_files(
-                    decorated_files.as_slice(),
-                    plugin_manager,
-                    args.depth,
-                )?;
-                println!("{}", formatted_output);
-                Ok(())
+/// Lists several path operands the way `ls a b` does. Human views print file
+/// operands together first, then each directory or archive under its own
+/// header. Machine output merges everything into one stream and tags each
+/// entry with the operand it came from.
+fn list_operands(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+    formatter: &dyn FileFormatter,
+) -> Result<()> {
+    let mut missing = 0usize;
+    let mut file_operands = Vec::new();
+    let mut container_operands = Vec::new();
+    for operand in &args.operands {
+        if let Err(err) = Path::new(operand).symlink_metadata() {
+            eprintln!("lla: cannot access '{}': {}", operand, err);
+            missing += 1;
+            continue;
+        }
+        match operand_kind(operand) {
+            OperandKind::File => file_operands.push(operand),
+            OperandKind::Archive | OperandKind::Directory => container_operands.push(operand),
+        }
+    }
+
+    match args.output_mode {
+        OutputMode::Human => {
+            let mut printed_any = false;
+            if !file_operands.is_empty() {
+                let mut files = Vec::new();
+                for operand in &file_operands {
+                    files.extend(collect_operand(
+                        &args.for_operand(operand),
+                        config,
+                        plugin_manager,
+                    )?);
+                }
+                files = sort_and_limit(files, &create_sorter(args, plugin_manager)?, args)?;
+                write_entries(args, files, formatter, plugin_manager)?;
+                printed_any = true;
             }
-            OutputMode::Json { pretty } => {
-                let include_git_status = args.git_format;
-                json_writer::write_json_array_stream(
-                    decorated_files,
-                    plugin_manager,
-                    pretty,
-                    include_git_status,
-                )
+
+            // NUL-separated output is for xargs and friends, which cannot use
+            // section headers.
+            let print_headers = !args.plain_output.is_some_and(|plain| plain.print0);
+            for operand in container_operands {
+                if print_headers {
+                    if printed_any {
+                        println!();
+                    }
+                    if crate::theme::is_no_color() {
+                        println!("{}:", operand);
+                    } else {
+                        println!("{}:", operand.bold());
+                    }
+                }
+                printed_any = true;
+
+                let operand_args = args.for_operand(operand);
+                if operand_kind(operand) == OperandKind::Directory
+                    && should_stream(&operand_args, formatter)
+                {
+                    stream_directory(&operand_args, config, plugin_manager, formatter)?;
+                } else {
+                    let entries = collect_operand(&operand_args, config, plugin_manager)?;
+                    write_entries(&operand_args, entries, formatter, plugin_manager)?;
+                }
             }
-            OutputMode::Ndjson => {
-                let include_git_status = args.git_format;
-                json_writer::write_ndjson_stream(
-                    decorated_files,
-                    plugin_manager,
-                    include_git_status,
-                )
+        }
+        OutputMode::Ndjson => {
+            // NDJSON is line-delimited, so each operand can be written (or
+            // streamed) on its own without buffering the others.
+            for operand in file_operands.into_iter().chain(container_operands) {
+                let operand_args = args.for_operand(operand);
+                if operand_kind(operand) == OperandKind::Directory
+                    && should_stream(&operand_args, formatter)
+                {
+                    stream_directory(&operand_args, config, plugin_manager, formatter)?;
+                } else {
+                    let mut entries = collect_operand(&operand_args, config, plugin_manager)?;
+                    tag_operand(&mut entries, operand);
+                    write_entries(&operand_args, entries, formatter, plugin_manager)?;
+                }
             }
-            OutputMode::Csv => {
-                let include_git_status = args.git_format;
-                csv_writer::write_csv_stream(decorated_files, plugin_manager, include_git_status)
+        }
+        OutputMode::Json { .. } | OutputMode::Csv => {
+            let mut merged = Vec::new();
+            for operand in file_operands.into_iter().chain(container_operands) {
+                let mut entries =
+                    collect_operand(&args.for_operand(operand), config, plugin_manager)?;
+                tag_operand(&mut entries, operand);
+                merged.extend(entries);
             }
-        };
+            write_entries(args, merged, formatter, plugin_manager)?;
+        }
     }
 
-    // Single file path handling: allow listing one file
-    if p.is_file() || path_is_symlink {
-        let decorated_files = list_and_decorate_single_file(args, &filter, plugin_manager, format)?;
-        let decorated_files = if !args.tree_format && !args.recursive_format {
-            sort_files(decorated_files, &sorter, args)?
-        } else {
-            decorated_files
-        };
+    if missing > 0 {
+        return Err(LlaError::Other(format!(
+            "{} of {} paths could not be accessed",
+            missing,
+            args.operands.len()
+        )));
+    }
+    Ok(())
+}
 
-        return match args.output_mode {
-            OutputMode::Human => {
-                let formatted_output = formatter.format_files(
-                    decorated_files.as_slice(),
-                    plugin_manager,
-                    args.depth,
-                )?;
-                println!("{}", formatted_output);
-                Ok(())
-            }
-            OutputMode::Json { pretty } => {
-                let include_git_status = args.git_format;
-                json_writer::write_json_array_stream(
-                    decorated_files,
-                    plugin_manager,
-                    pretty,
-                    include_git_status,
-                )
-            }
-            OutputMode::Ndjson => {
-                let include_git_status = args.git_format;
-                json_writer::write_ndjson_stream(
-                    decorated_files,
-                    plugin_manager,
-                    include_git_status,
-                )
-            }
-            OutputMode::Csv => {
-                let include_git_status = args.git_format;
-                csv_writer::write_csv_stream(decorated_files, plugin_manager, include_git_status)
-            }
-        };
+/// Runs paths read from `--stdin` or `--from-file` through the usual stat,
+/// filter, decoration, sort and formatter pipeline without walking anything.
+fn list_path_list(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+    formatter: &dyn FileFormatter,
+    source: &PathListSource,
+) -> Result<()> {
+    let (paths, missing) = path_list::split_missing(path_list::read_path_list(source)?);
+    let entries = collect_path_list(args, config, plugin_manager, paths)?;
+    write_entries(args, entries, formatter, plugin_manager)?;
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+    path_list::report_missing(&missing, !matches!(args.output_mode, OutputMode::Human))?;
+    Err(LlaError::Other(format!(
+        "{} listed path(s) could not be accessed",
+        missing.len()
+    )))
+}
+
+/// Stats, filters, decorates and sorts the paths of a path list.
+fn collect_path_list(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+    paths: Vec<PathBuf>,
+) -> Result<Vec<DecoratedEntry>> {
+    let filter = create_filter(args);
+    // Listed paths can come from anywhere, so each directory is its own root.
+    let dir_sizes = needs_directory_sizes(args, config)
+        .then(|| DirSizeIndex::compute(&paths, args.one_file_system, args.llaignore));
+    let excludes = ExcludeSet::new(config, Path::new(&args.directory), args.llaignore);
+    let mut entries = decorate_paths(args, &excludes, &filter, paths, dir_sizes.as_ref());
+    FieldFilters::resolve(args, plugin_manager)?.decorate(
+        plugin_manager,
+        &mut entries,
+        get_format(args),
+    );
+
+    if !args.refine_filters.is_empty() {
+        entries = apply_refine_filters(entries, args)?;
+    }
+    sort_and_limit(entries, &create_sorter(args, plugin_manager)?, args)
+}
+
+/// Records which path operand an entry was listed from, for machine output.
+fn tag_operand(entries: &mut [DecoratedEntry], operand: &str) {
+    for entry in entries {
+        entry
+            .custom_fields
+            .insert(OPERAND_FIELD.to_string(), operand.to_string());
+    }
+}
+
+/// Lists, filters, decorates and sorts the entries of a single path operand,
+/// which may be a directory, a single file or an archive.
+fn collect_operand(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+) -> Result<Vec<DecoratedEntry>> {
+    let lister = create_lister(args, config);
+    let sorter = create_sorter(args, plugin_manager)?;
+    let filter = create_filter(args);
+    let format = get_format(args);
+
+    match operand_kind(&args.directory) {
+        OperandKind::Archive => {
+            let entries = list_and_decorate_archive_entries(args, &filter, plugin_manager, format)?;
+            return sort_and_limit(entries, &sorter, args);
+        }
+        OperandKind::File => {
+            let entries = list_and_decorate_single_file(args, &filter, plugin_manager, format)?;
+            return sort_and_limit(entries, &sorter, args);
+        }
+        OperandKind::Directory => {}
     }
 
     let mut listing_cache: Option<ListingCache> = None;
     let mut cache_key: Option<String> = None;
     let mut cache_summary: Option<String> = None;
     let mut cached_entries: Option<Vec<DecoratedEntry>> = None;
+    let root = Path::new(&args.directory);
+    let recursive = args.tree_format || args.recursive_format;
 
-    if !path_is_archive && p.is_dir() {
+    if root.is_dir() {
         let context = ListingContext::from_args(args, config);
         cache_summary = Some(context.summary());
         let key = context.cache_key();
         cache_key = Some(key.clone());
         let cache = ListingCache::new()?;
-        if !args.refine_filters.is_empty() {
-            if let Some(entries) = cache.load(&key)? {
-                cached_entries = Some(entries);
-            }
+        // Recursive directory sizes depend on every descendant, so a partial
+        // refresh cannot keep them accurate. Explaining .llaignore needs a
+        // real walk, and staging files changes their Git status without
+        // touching any directory.
+        if !args.refine_filters.is_empty()
+            && !needs_directory_sizes(args, config)
+            && args.llaignore != LlaIgnoreMode::Explain
+            && args.git_status.is_empty()
+        {
+            cached_entries = cache.load_fresh(&key, root, |dir| {
+                rewalk_subtree(args, config, &lister, &filter, plugin_manager, format, dir)
+            })?;
         }
         listing_cache = Some(cache);
     }
@@ -181,45 +374,80 @@ pub fn list_directory(
             cache_key.as_ref(),
             cache_summary.as_ref(),
         ) {
-            cache.save(key, summary, &fresh)?;
+            cache.save(key, summary, root, recursive, &fresh)?;
         }
         fresh
     };
 
     if !args.refine_filters.is_empty() {
-        decorated_files =
-            apply_refine_filters(decorated_files, &args.refine_filters, args.case_sensitive)?;
+        decorated_files = apply_refine_filters(decorated_files, args)?;
     }
 
-    let decorated_files = if !args.tree_format && !args.recursive_format {
-        sort_files(decorated_files, &sorter, args)?
-    } else {
-        decorated_files
-    };
+    let mut kept = sort_and_limit(decorated_files, &sorter, args)?;
+    if needs_directory_sizes(args, config) && sizes_after_limit(args) {
+        apply_kept_directory_sizes(args, &mut kept);
+    }
+    Ok(kept)
+}
 
+/// Lists the subtree at `dir` again for a cached listing of
+/// `args.directory`, including `dir` itself unless it is the listed root.
+fn rewalk_subtree(
+    args: &Args,
+    config: &Config,
+    lister: &Arc<dyn FileLister + Send + Sync>,
+    filter: &Arc<dyn FileFilter + Send + Sync>,
+    plugin_manager: &mut PluginManager,
+    format: &str,
+    dir: &Path,
+) -> Result<Vec<DecoratedEntry>> {
+    let root = Path::new(&args.directory);
+    let mut scoped = args.for_operand(&dir.to_string_lossy());
+    if dir != root {
+        let level = dir
+            .strip_prefix(root)
+            .map_or(0, |rel| rel.components().count());
+        scoped.depth = args.depth.map(|depth| depth.saturating_sub(level));
+    }
+    let mut raw_paths = walk_paths(&scoped, config, lister)?;
+    // The gitignore walker leaves out the directory it starts from, but a
+    // refreshed subtree has to carry it.
+    if dir != root && !raw_paths.iter().any(|path| path == dir) {
+        raw_paths.insert(0, dir.to_path_buf());
+    }
+    decorate_listing(&scoped, config, filter, plugin_manager, format, raw_paths)
+}
+
+fn write_entries(
+    args: &Args,
+    decorated_files: Vec<DecoratedEntry>,
+    formatter: &dyn FileFormatter,
+    plugin_manager: &mut PluginManager,
+) -> Result<()> {
+    // Only include git status if git format was requested
+    let include_git_status = args.git_format;
     match args.output_mode {
         OutputMode::Human => {
             let formatted_output =
                 formatter.format_files(decorated_files.as_slice(), plugin_manager, args.depth)?;
-            println!("{}", formatted_output);
+            if args.plain_output.is_some() {
+                // Plain records carry their own terminators.
+                print!("{}", formatted_output);
+            } else {
+                println!("{}", formatted_output);
+            }
             Ok(())
         }
-        OutputMode::Json { pretty } => {
-            // Only include git status if git format was requested
-            let include_git_status = args.git_format;
-            json_writer::write_json_array_stream(
-                decorated_files,
-                plugin_manager,
-                pretty,
-                include_git_status,
-            )
-        }
+        OutputMode::Json { pretty } => json_writer::write_json_array_stream(
+            decorated_files,
+            plugin_manager,
+            pretty,
+            include_git_status,
+        ),
         OutputMode::Ndjson => {
-            let include_git_status = args.git_format;
             json_writer::write_ndjson_stream(decorated_files, plugin_manager, include_git_status)
         }
         OutputMode::Csv => {
-            let include_git_status = args.git_format;
             csv_writer::write_csv_stream(decorated_files, plugin_manager, include_git_status)
         }
     }
@@ -228,6 +456,8 @@ pub fn list_directory(
 pub fn get_format(args: &Args) -> &'static str {
     if args.fuzzy_format {
         "fuzzy"
+    } else if args.format_template.is_some() {
+        "template"
     } else if args.long_format {
         "long"
     } else if args.tree_format {
@@ -286,44 +516,58 @@ fn metadata_for_path(
     Ok((metadata, target_metadata))
 }
 
-fn calculate_dir_size(path: &Path) -> std::io::Result<u64> {
-    if !path.is_dir() {
-        return Ok(0);
-    }
+/// Recursive directory totals for every directory below the listed root, when
+/// the current view needs them before the listing is limited.
+fn directory_sizes(args: &Args, config: &Config) -> Option<DirSizeIndex> {
+    upfront_directory_sizes(args, config).then(|| {
+        DirSizeIndex::compute(
+            &[PathBuf::from(&args.directory)],
+            args.one_file_system,
+            args.llaignore,
+        )
+    })
+}
 
-    // Directory entries are already processed in parallel by the caller. Spawning a
-    // nested Rayon traversal at every level creates a huge number of tiny tasks and
-    // makes metadata-heavy trees dramatically slower. Keep one iterative walk per
-    // top-level directory and avoid metadata syscalls for directories and symlinks.
-    let mut pending = vec![path.to_path_buf()];
-    let mut files = Vec::new();
+fn upfront_directory_sizes(args: &Args, config: &Config) -> bool {
+    needs_directory_sizes(args, config) && !sizes_after_limit(args)
+}
 
-    while let Some(directory) = pending.pop() {
-        for entry in fs::read_dir(directory)? {
-            let entry = entry?;
-            let file_type = entry.file_type()?;
+/// Whether directory totals can wait until `--limit`, `--head` or `--tail` has
+/// picked its entries, because nothing ranks or filters on them. Only the kept
+/// directories are then walked.
+fn sizes_after_limit(args: &Args) -> bool {
+    args.limit.is_some()
+        && !SortKey::parse_list(&args.sort_by)
+            .iter()
+            .any(|key| key.name == "size")
+        && args.size_filter.is_none()
+        && args.where_filters.is_empty()
+        && args.refine_filters.is_empty()
+}
 
-            if file_type.is_symlink() {
-                continue;
-            }
-            if file_type.is_dir() {
-                pending.push(entry.path());
-            } else {
-                files.push(entry.path());
-            }
+/// Totals the directories a limited listing kept, leaving out those on
+/// another file system than the listed root under `--one-file-system`.
+fn apply_kept_directory_sizes(args: &Args, entries: &mut [DecoratedEntry]) {
+    let root_device = if args.one_file_system {
+        fs_metadata::device_id(Path::new(&args.directory))
+    } else {
+        None
+    };
+    let directories: Vec<PathBuf> = entries
+        .iter()
+        .filter(|entry| entry.metadata.as_ref().is_some_and(|m| m.is_dir))
+        .map(|entry| PathBuf::from(&entry.path))
+        .filter(|path| root_device.is_none() || fs_metadata::device_id(path) == root_device)
+        .collect();
+    if directories.is_empty() {
+        return;
+    }
+    let index = DirSizeIndex::compute(&directories, args.one_file_system, args.llaignore);
+    for entry in entries {
+        if let Some(metadata) = entry.metadata.as_mut().filter(|m| m.is_dir) {
+            index.apply(Path::new(&entry.path), metadata);
         }
     }
-
-    // Flatten the work before parallelizing it. This keeps a single bounded Rayon
-    // job graph instead of recursively spawning a new parallel reduction for every
-    // directory, while still parallelizing the expensive metadata calls.
-    files
-        .into_par_iter()
-        .try_fold(
-            || 0u64,
-            |total, file| Ok(total.saturating_add(fs::symlink_metadata(file)?.len())),
-        )
-        .try_reduce(|| 0u64, |left, right| Ok(left.saturating_add(right)))
 }
 
 fn needs_directory_sizes(args: &Args, config: &Config) -> bool {
@@ -353,8 +597,11 @@ fn needs_directory_sizes(args: &Args, config: &Config) -> bool {
                     .iter()
                     .any(|column| column == "size")))
         || args.sizemap_format
+        || (args.tree_format && args.plain_output.is_none())
         || args.fuzzy_format
-        || args.sort_by == "size"
+        || SortKey::parse_list(&args.sort_by)
+            .iter()
+            .any(|key| key.name == "size")
         || args.size_filter.is_some()
 }
 
@@ -366,35 +613,65 @@ pub fn list_and_decorate_files(
     plugin_manager: &mut PluginManager,
     format: &str,
 ) -> Result<Vec<DecoratedEntry>> {
-    let raw_paths = if args.respect_gitignore && !args.fuzzy_format {
-        list_files_with_gitignore(args, config)?
+    let raw_paths = walk_paths(args, config, lister)?;
+    decorate_listing(args, config, filter, plugin_manager, format, raw_paths)
+}
+
+fn walk_paths(
+    args: &Args,
+    config: &Config,
+    lister: &Arc<dyn FileLister + Send + Sync>,
+) -> Result<Vec<PathBuf>> {
+    if args.respect_gitignore && !args.fuzzy_format {
+        list_files_with_gitignore(args, config)
     } else {
         lister.list_files(
             &args.directory,
             args.tree_format || args.recursive_format,
             args.depth,
-        )?
-    };
+        )
+    }
+}
 
-    let should_calculate_dir_sizes = needs_directory_sizes(args, config);
+fn decorate_listing(
+    args: &Args,
+    config: &Config,
+    filter: &Arc<dyn FileFilter + Send + Sync>,
+    plugin_manager: &mut PluginManager,
+    format: &str,
+    raw_paths: Vec<PathBuf>,
+) -> Result<Vec<DecoratedEntry>> {
+    let dir_sizes = directory_sizes(args, config);
+    let excludes = ExcludeSet::new(config, Path::new(&args.directory), args.llaignore);
+    let mut decorated_entries =
+        decorate_paths(args, &excludes, filter, raw_paths, dir_sizes.as_ref());
+    FieldFilters::resolve(args, plugin_manager)?.decorate(
+        plugin_manager,
+        &mut decorated_entries,
+        format,
+    );
 
-    let entries: Vec<DecoratedEntry> = raw_paths
+    Ok(decorated_entries)
+}
+
+/// Stats, filters and builds entries for a batch of walked paths. Plugin
+/// decoration is left to the caller so it can run once per batch.
+fn decorate_paths(
+    args: &Args,
+    excludes: &ExcludeSet,
+    filter: &Arc<dyn FileFilter + Send + Sync>,
+    raw_paths: Vec<PathBuf>,
+    dir_sizes: Option<&DirSizeIndex>,
+) -> Vec<DecoratedEntry> {
+    // Recursive walkers prune excluded subtrees themselves; `excludes` catches
+    // the direct children returned by flat and fuzzy listers.
+    let fuzzy = args
+        .fuzzy_query
+        .as_deref()
+        .map(|query| FuzzyQuery::new(query, args.fuzzy_min_score));
+    raw_paths
         .into_par_iter()
-        .filter(|path| {
-            // Exclude entries if they live under any excluded prefix
-            if config.exclude_paths.is_empty() {
-                return true;
-            }
-            // Ensure we compare absolute paths for robust prefix checks
-            let path_abs = match path.canonicalize() {
-                Ok(abs) => abs,
-                Err(_) => path.clone(),
-            };
-            !config
-                .exclude_paths
-                .iter()
-                .any(|ex| path_abs.starts_with(ex))
-        })
+        .filter(|path| !excludes.contains(path))
         .filter_map(|path| {
             let (mut metadata, target_metadata) = match metadata_for_path(&path, args) {
                 Ok(metadata) => metadata,
@@ -485,7 +762,9 @@ pub fn list_and_decorate_files(
                 let include_dirs = !args.no_dirs;
                 let include_files = !args.no_files;
 
-                (metadata.is_dir && include_dirs) || (metadata.is_file && include_files)
+                // FIFOs, sockets and devices are neither, but are files to ls.
+                (metadata.is_dir && include_dirs)
+                    || ((metadata.is_file || is_special_file(&metadata)) && include_files)
             };
 
             if !should_include {
@@ -500,17 +779,15 @@ pub fn list_and_decorate_files(
                 return None;
             }
 
-            if should_calculate_dir_sizes && metadata.is_dir {
-                if let Ok(dir_size) = calculate_dir_size(&path) {
-                    metadata.size = dir_size;
-                }
+            if let Some(dir_sizes) = dir_sizes.filter(|_| metadata.is_dir) {
+                dir_sizes.apply(&path, &mut metadata);
             }
 
-            if !matches_metadata_filters(args, &metadata) {
+            let mut custom_fields = HashMap::new();
+            if !matches_metadata_filters(args, &path, &metadata, &mut custom_fields) {
                 return None;
             }
 
-            let mut custom_fields = HashMap::new();
             if metadata.is_symlink {
                 if !args.show_symlink_target {
                     custom_fields.insert("hide_symlink_target".to_string(), "true".to_string());
@@ -527,6 +804,12 @@ pub fn list_and_decorate_files(
                     custom_fields.insert("invalid_symlink".to_string(), "true".to_string());
                 }
             }
+            if let Some(matched) = fuzzy.as_ref().and_then(|query| query.score(&path)) {
+                custom_fields.insert(FUZZY_SCORE_FIELD.to_string(), matched.score.to_string());
+                let positions: Vec<String> =
+                    matched.positions.iter().map(ToString::to_string).collect();
+                custom_fields.insert(FUZZY_POSITIONS_FIELD.to_string(), positions.join(","));
+            }
 
             Some(DecoratedEntry {
                 path: path.to_string_lossy().into_owned(),
@@ -535,17 +818,127 @@ pub fn list_and_decorate_files(
                 typed_fields: Default::default(),
             })
         })
-        .collect();
+        .collect()
+}
 
-    let mut decorated_entries = entries;
-    plugin_manager.decorate_entries(&mut decorated_entries, format);
+/// Whether the listing can be rendered incrementally, one directory at a time,
+/// instead of being collected and sorted as a whole first.
+fn should_stream(args: &Args, formatter: &dyn FileFormatter) -> bool {
+    let recursive_listing = args.tree_format || args.recursive_format;
+    if !recursive_listing || args.fuzzy_format || !args.refine_filters.is_empty() {
+        return false;
+    }
+    // A limited listing holds only the entries it keeps, so it walks batch by
+    // batch whatever the output and writes them at the end.
+    if args.limit.is_some() {
+        return true;
+    }
 
-    Ok(decorated_entries)
+    match args.output_mode {
+        OutputMode::Human => formatter.supports_streaming(),
+        OutputMode::Ndjson => true,
+        OutputMode::Json { .. } | OutputMode::Csv => false,
+    }
 }
 
-fn list_files_with_gitignore(args: &Args, config: &Config) -> Result<Vec<PathBuf>> {
-    let should_recurse = args.tree_format || args.recursive_format;
-    let mut builder = WalkBuilder::new(&args.directory);
+/// Walks, filters, decorates and prints the listing one directory at a time so
+/// output starts immediately and memory stays bounded by the largest directory.
+/// Entries are sorted within each directory rather than across the whole tree.
+/// With a limit, each batch is offered to a `TopK` instead and the kept
+/// entries are written once the walk ends.
+fn stream_directory(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+    formatter: &dyn FileFormatter,
+) -> Result<()> {
+    let lister = create_lister(args, config);
+    let filter = create_filter(args);
+    let sorter = create_sorter(args, plugin_manager)?;
+    let format = get_format(args);
+    // Each batch walks only the subtrees earlier batches did not cover.
+    let mut dir_sizes = upfront_directory_sizes(args, config).then(|| {
+        LazyDirSizes::new(
+            Path::new(&args.directory),
+            args.one_file_system,
+            args.llaignore,
+        )
+    });
+    let field_filters = FieldFilters::resolve(args, plugin_manager)?;
+    // Built once so the `.llaignore` matchers it caches serve every batch.
+    let excludes = ExcludeSet::new(config, Path::new(&args.directory), args.llaignore);
+
+    let mut top = args
+        .limit
+        .map(|limit| TopK::new(Arc::clone(&sorter), sort_options(args), limit));
+    let mut ndjson = (top.is_none() && matches!(args.output_mode, OutputMode::Ndjson))
+        .then(|| json_writer::NdjsonWriter::new(args.git_format));
+
+    let mut emit = |paths: Vec<PathBuf>| -> Result<()> {
+        if let Some(dir_sizes) = dir_sizes.as_mut() {
+            dir_sizes.cover(&paths);
+        }
+        let mut entries = decorate_paths(
+            args,
+            &excludes,
+            &filter,
+            paths,
+            dir_sizes.as_ref().map(LazyDirSizes::index),
+        );
+        if entries.is_empty() {
+            return Ok(());
+        }
+        field_filters.decorate(plugin_manager, &mut entries, format);
+        if entries.is_empty() {
+            return Ok(());
+        }
+        if let Some(top) = top.as_mut() {
+            top.extend(entries);
+            return Ok(());
+        }
+        let entries = sort_files(entries, &sorter, args)?;
+
+        if let Some(writer) = ndjson.as_mut() {
+            let mut entries = entries;
+            tag_operand(&mut entries, &args.directory);
+            return writer.write_batch(&entries);
+        }
+
+        let output = formatter.format_batch(&entries, plugin_manager, args.depth)?;
+        if !output.is_empty() {
+            let mut stdout = io::stdout().lock();
+            if args.plain_output.is_some() {
+                write!(stdout, "{}", output)?;
+            } else {
+                writeln!(stdout, "{}", output)?;
+            }
+            stdout.flush()?;
+        }
+        Ok(())
+    };
+
+    if args.respect_gitignore {
+        stream_files_with_gitignore(args, config, &mut emit)?;
+    } else {
+        lister.list_files_batched(&args.directory, true, args.depth, &mut emit)?;
+    }
+
+    let Some(top) = top else {
+        return Ok(());
+    };
+    let mut kept = top.into_entries();
+    if needs_directory_sizes(args, config) && sizes_after_limit(args) {
+        apply_kept_directory_sizes(args, &mut kept);
+    }
+    let mut entries = sort_files(kept, &sorter, args)?;
+    if !matches!(args.output_mode, OutputMode::Human) {
+        tag_operand(&mut entries, &args.directory);
+    }
+    write_entries(args, entries, formatter, plugin_manager)
+}
+
+fn gitignore_walk_builder(root: &Path, args: &Args, excludes: &ExcludeSet) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
     builder
         .hidden(false)
         .follow_links(false)
@@ -555,12 +948,95 @@ fn list_files_with_gitignore(args: &Args, config: &Config) -> Result<Vec<PathBuf
         .parents(true)
         .ignore(true)
         .require_git(false)
-        .same_file_system(true);
+        .same_file_system(args.one_file_system);
+
+    let skip_git_dirs = args.respect_gitignore;
+    let excludes = excludes.clone();
+    builder.filter_entry(move |entry| {
+        !(skip_git_dirs && path_contains_git_dir(entry.path()))
+            && !excludes.contains_entry(
+                entry.path(),
+                entry.file_type().is_some_and(|ft| ft.is_dir()),
+            )
+    });
+    builder
+}
 
-    if args.respect_gitignore {
-        builder.filter_entry(|entry| !path_contains_git_dir(entry.path()));
+/// Batched counterpart of `list_files_with_gitignore`: each directory is read
+/// with its own single-level walk, which still picks up ignore files from every
+/// parent, so the batches line up with directories.
+fn stream_files_with_gitignore(
+    args: &Args,
+    config: &Config,
+    sink: &mut dyn FnMut(Vec<PathBuf>) -> Result<()>,
+) -> Result<()> {
+    let max_depth = args.depth.unwrap_or(usize::MAX);
+    let max_entries = config.listers.recursive.max_entries.unwrap_or(usize::MAX);
+    let root_device = if args.one_file_system {
+        fs_metadata::device_id(Path::new(&args.directory))
+    } else {
+        None
+    };
+    let excludes = ExcludeSet::new(config, Path::new(&args.directory), args.llaignore);
+
+    let mut files_seen = 0usize;
+    let mut pending = vec![(PathBuf::from(&args.directory), 0usize)];
+    while let Some((current, level)) = pending.pop() {
+        if level >= max_depth || files_seen >= max_entries {
+            continue;
+        }
+
+        let mut builder = gitignore_walk_builder(&current, args, &excludes);
+        builder.max_depth(Some(1));
+
+        let mut batch = Vec::new();
+        let mut subdirectories = Vec::new();
+        for dent in builder.build() {
+            let entry = dent.map_err(|err| LlaError::Other(err.to_string()))?;
+            if entry.depth() == 0 || path_contains_git_dir(entry.path()) {
+                continue;
+            }
+
+            match entry.file_type() {
+                Some(ft) if ft.is_file() => {
+                    if files_seen >= max_entries {
+                        break;
+                    }
+                    files_seen += 1;
+                }
+                Some(ft)
+                    if ft.is_dir()
+                        && (root_device.is_none()
+                            || fs_metadata::device_id(entry.path()) == root_device) =>
+                {
+                    subdirectories.push(entry.path().to_path_buf());
+                }
+                _ => {}
+            }
+            batch.push(entry.into_path());
+        }
+
+        subdirectories.sort();
+        pending.extend(
+            subdirectories
+                .into_iter()
+                .rev()
+                .map(|subdirectory| (subdirectory, level + 1)),
+        );
+
+        if !batch.is_empty() {
+            sink(batch)?;
+        }
     }
 
+    Ok(())
+}
+
+fn list_files_with_gitignore(args: &Args, config: &Config) -> Result<Vec<PathBuf>> {
+    let should_recurse = args.tree_format || args.recursive_format;
+    let excludes = ExcludeSet::new(config, Path::new(&args.directory), args.llaignore);
+    let mut builder = gitignore_walk_builder(Path::new(&args.directory), args, &excludes);
+
     if !should_recurse {
         builder.max_depth(Some(1));
     } else if let Some(depth) = args.depth {
@@ -602,6 +1078,10 @@ fn list_files_with_gitignore(args: &Args, config: &Config) -> Result<Vec<PathBuf
     Ok(entries)
 }
 
+fn is_special_file(metadata: &EntryMetadata) -> bool {
+    !(metadata.is_dir || metadata.is_file || metadata.is_symlink)
+}
+
 fn path_contains_git_dir(path: &Path) -> bool {
     path.components()
         .any(|component| component.as_os_str() == ".git")
@@ -615,6 +1095,7 @@ pub fn list_and_decorate_archive_entries(
 ) -> Result<Vec<DecoratedEntry>> {
     use std::path::Path;
 
+    let field_filters = FieldFilters::resolve(args, plugin_manager)?;
     let archive_path = Path::new(&args.directory);
     let lower = args.directory.to_lowercase();
     let entries = if lower.ends_with(".zip") {
@@ -701,7 +1182,7 @@ pub fn list_and_decorate_archive_entries(
             let include_symlinks = !args.no_symlinks;
 
             (md.is_dir && include_dirs)
-                || (md.is_file && include_files)
+                || ((md.is_file || is_special_file(&md)) && include_files)
                 || (md.is_symlink && include_symlinks)
         };
 
@@ -709,7 +1190,7 @@ pub fn list_and_decorate_archive_entries(
             continue;
         }
 
-        if !matches_metadata_filters(args, &md) {
+        if !matches_metadata_filters(args, &pb, &md, &mut entry.custom_fields) {
             continue;
         }
 
@@ -722,10 +1203,15 @@ pub fn list_and_decorate_archive_entries(
             continue;
         }
 
-        plugin_manager.decorate_entry(&mut entry, format);
+        if field_filters.is_empty() {
+            plugin_manager.decorate_entry(&mut entry, format);
+        }
         filtered.push(entry);
     }
 
+    if !field_filters.is_empty() {
+        field_filters.decorate(plugin_manager, &mut filtered, format);
+    }
     Ok(filtered)
 }
 
@@ -754,16 +1240,15 @@ pub fn list_and_decorate_single_file(
     let (mut metadata, target_metadata) = metadata_for_path(path, args)?;
 
     if args.include_dirs && metadata.is_dir {
-        if let Ok(dir_size) = calculate_dir_size(path) {
-            metadata.size = dir_size;
-        }
+        DirSizeIndex::compute(&[path.to_path_buf()], args.one_file_system, args.llaignore)
+            .apply(path, &mut metadata);
     }
 
-    if !matches_metadata_filters(args, &metadata) {
+    let mut custom_fields = HashMap::new();
+    if !matches_metadata_filters(args, path, &metadata, &mut custom_fields) {
         return Ok(entries);
     }
 
-    let mut custom_fields = HashMap::new();
     if metadata.is_symlink {
         if !args.show_symlink_target {
             custom_fields.insert("hide_symlink_target".to_string(), "true".to_string());
@@ -788,34 +1273,76 @@ pub fn list_and_decorate_single_file(
         typed_fields: Default::default(),
     };
 
-    plugin_manager.decorate_entry(&mut entry, format);
-    entries.push(entry);
+    let field_filters = FieldFilters::resolve(args, plugin_manager)?;
+    if field_filters.is_empty() {
+        plugin_manager.decorate_entry(&mut entry, format);
+        entries.push(entry);
+    } else {
+        entries.push(entry);
+        field_filters.decorate(plugin_manager, &mut entries, format);
+    }
     Ok(entries)
 }
 
+fn sort_options(args: &Args) -> SortOptions {
+    SortOptions {
+        reverse: args.sort_reverse,
+        dirs_first: args.sort_dirs_first,
+        case_sensitive: args.sort_case_sensitive,
+        natural: args.sort_natural,
+        collation: args.sort_collation,
+    }
+}
+
+/// Sorts `files` and keeps the entries `--limit`, `--head` or `--tail` asks
+/// for, selecting them before sorting so only the kept entries are sorted.
+pub fn sort_and_limit(
+    files: Vec<DecoratedEntry>,
+    sorter: &Arc<dyn FileSorter + Send + Sync>,
+    args: &Args,
+) -> Result<Vec<DecoratedEntry>> {
+    let Some(limit) = args.limit else {
+        return sort_files(files, sorter, args);
+    };
+    let mut top = TopK::new(Arc::clone(sorter), sort_options(args), limit);
+    top.extend(files);
+    sort_files(top.into_entries(), sorter, args)
+}
+
 pub fn sort_files(
     files: Vec<DecoratedEntry>,
     sorter: &Arc<dyn FileSorter + Send + Sync>,
     args: &Args,
 ) -> Result<Vec<DecoratedEntry>> {
-    let mut entries_with_paths: Vec<(PathBuf, &DecoratedEntry)> = files
+    let keys: Vec<SortKeys> = files.iter().map(|_| SortKeys::default()).collect();
+    let mut sort_entries: Vec<SortEntry> = files
         .iter()
-        .map(|entry| (PathBuf::from(&entry.path), entry))
+        .zip(&keys)
+        .map(|(entry, keys)| SortEntry::new(entry, keys))
         .collect();
 
-    let options = SortOptions {
-        reverse: args.sort_reverse,
-        dirs_first: args.sort_dirs_first,
-        case_sensitive: args.sort_case_sensitive,
-        natural: args.sort_natural,
-    };
+    let options = sort_options(args);
 
-    sorter.sort_files_with_metadata(&mut entries_with_paths, options)?;
+    if args.tree_format || args.recursive_format {
+        // Trees and recursive sections order entries within their directory,
+        // the way streamed listings do, so every output agrees.
+        let mut directories: BTreeMap<&Path, Vec<SortEntry>> = BTreeMap::new();
+        for entry in sort_entries {
+            let parent = entry.path.parent().unwrap_or(Path::new(""));
+            directories.entry(parent).or_default().push(entry);
+        }
+        sort_entries = Vec::with_capacity(files.len());
+        for mut siblings in directories.into_values() {
+            sorter.sort_files_with_metadata(&mut siblings, options)?;
+            sort_entries.extend(siblings);
+        }
+    } else {
+        sorter.sort_files_with_metadata(&mut sort_entries, options)?;
+    }
 
-    let sorted_files = entries_with_paths
+    let sorted_files = sort_entries
         .into_iter()
-        .map(|(_, entry)| entry)
-        .cloned()
+        .map(|sorted| sorted.entry.clone())
         .collect();
 
     Ok(sorted_files)
@@ -823,57 +1350,170 @@ pub fn sort_files(
 
 pub fn create_lister(args: &Args, config: &Config) -> Arc<dyn FileLister + Send + Sync> {
     if args.fuzzy_format {
-        Arc::new(FuzzyLister::new(config.clone(), args.respect_gitignore))
+        Arc::new(FuzzyLister::new(
+            config.clone(),
+            args.respect_gitignore,
+            args.one_file_system,
+            args.llaignore,
+        ))
     } else if args.tree_format || args.recursive_format {
-        Arc::new(RecursiveLister::new(config.clone()))
+        Arc::new(RecursiveLister::new(
+            config.clone(),
+            args.one_file_system,
+            args.llaignore,
+        ))
     } else {
         Arc::new(BasicLister)
     }
 }
 
-pub fn create_sorter(args: &Args) -> Arc<dyn FileSorter + Send + Sync> {
-    let sorter: Arc<dyn FileSorter + Send + Sync> = match args.sort_by.as_str() {
-        "name" => Arc::new(AlphabeticalSorter),
-        "size" => Arc::new(SizeSorter),
-        "date" => Arc::new(DateSorter),
-        _ => Arc::new(AlphabeticalSorter),
-    };
+/// Builds the sorter for `--sort`, a list of keys each breaking the ties of
+/// the one before. Entries equal on every key are ordered by name, ascending
+/// even with `--sort-reverse`.
+pub fn create_sorter(
+    args: &Args,
+    plugin_manager: &PluginManager,
+) -> Result<Arc<dyn FileSorter + Send + Sync>> {
+    let keys = SortKey::parse_list(&args.sort_by);
+    let sorted_by_name = keys.iter().any(|key| key.name == "name");
+
+    let mut sorters: Vec<(Arc<dyn FileSorter + Send + Sync>, bool)> = Vec::new();
+    for key in keys {
+        let sorter: Arc<dyn FileSorter + Send + Sync> = match key.name.as_str() {
+            "size" => Arc::new(SizeSorter),
+            "date" => Arc::new(DateSorter::Modified),
+            "created" => Arc::new(DateSorter::Created),
+            "accessed" => Arc::new(DateSorter::Accessed),
+            "extension" => Arc::new(ExtensionSorter),
+            "kind" => Arc::new(KindSorter),
+            "owner" => Arc::new(OwnershipSorter::Owner),
+            "group" => Arc::new(OwnershipSorter::Group),
+            "inode" => Arc::new(NumericSorter::Inode),
+            "links" => Arc::new(NumericSorter::Links),
+            "version" => Arc::new(VersionSorter),
+            "score" => Arc::new(ScoreSorter),
+            name => match name.strip_prefix("field:") {
+                Some(field) => {
+                    let (_, descriptor) = sort_field(field, plugin_manager)?;
+                    Arc::new(FieldSorter::new(field.to_string(), descriptor.field_type))
+                }
+                None => Arc::new(AlphabeticalSorter),
+            },
+        };
+        sorters.push((sorter, key.reverse));
+    }
+
+    let sorter = MultiKeySorter::new(sorters);
+    if sorted_by_name {
+        Ok(Arc::new(sorter))
+    } else {
+        Ok(Arc::new(
+            sorter.with_tie_break(Arc::new(AlphabeticalSorter)),
+        ))
+    }
+}
 
-    sorter
+/// The plugin and descriptor behind `--sort field:<name>`.
+fn sort_field(field: &str, plugin_manager: &PluginManager) -> Result<(String, FieldDescriptor)> {
+    match plugin_manager.field_descriptor(field) {
+        Some((plugin, descriptor)) if descriptor.sortable => Ok((plugin, descriptor)),
+        found => {
+            let reason = match found {
+                Some((plugin, _)) => {
+                    format!("field '{}' of plugin '{}' is not sortable", field, plugin)
+                }
+                None => format!("no enabled plugin provides field '{}'", field),
+            };
+            let available = plugin_manager.sortable_fields();
+            Err(LlaError::Plugin(if available.is_empty() {
+                format!(
+                    "Cannot sort by {}: {} (no sortable fields are available)",
+                    field, reason
+                )
+            } else {
+                format!(
+                    "Cannot sort by {}: {} (sortable fields: {})",
+                    field,
+                    reason,
+                    available.join(", ")
+                )
+            }))
+        }
+    }
 }
 
 pub fn create_filter(args: &Args) -> Arc<dyn FileFilter + Send + Sync> {
+    let name_filter = create_name_filter(args);
+    if args.git_status.is_empty() && args.fuzzy_query.is_none() {
+        return Arc::from(name_filter);
+    }
+    let mut composite = CompositeFilter::new(FilterOperation::And);
+    composite.add_filter(name_filter);
+    if let Some(query) = &args.fuzzy_query {
+        composite.add_filter(Box::new(FuzzyFilter::new(query, args.fuzzy_min_score)));
+    }
+    if !args.git_status.is_empty() {
+        composite.add_filter(Box::new(GitStatusFilter::new(args.git_status.clone())));
+    }
+    Arc::new(composite)
+}
+
+fn create_name_filter(args: &Args) -> Box<dyn FileFilter + Send + Sync> {
     match &args.filter {
         Some(filter_str) => {
             if filter_str.contains(" AND ") {
                 let mut composite = CompositeFilter::new(FilterOperation::And);
                 for part in filter_str.split(" AND ") {
-                    composite.add_filter(create_base_filter(part.trim(), !args.case_sensitive));
+                    composite.add_filter(create_base_filter(
+                        part.trim(),
+                        !args.case_sensitive,
+                        args.fuzzy_min_score,
+                    ));
                 }
-                Arc::new(composite)
+                Box::new(composite)
             } else if filter_str.contains(" OR ") {
                 let mut composite = CompositeFilter::new(FilterOperation::Or);
                 for part in filter_str.split(" OR ") {
-                    composite.add_filter(create_base_filter(part.trim(), !args.case_sensitive));
+                    composite.add_filter(create_base_filter(
+                        part.trim(),
+                        !args.case_sensitive,
+                        args.fuzzy_min_score,
+                    ));
                 }
-                Arc::new(composite)
+                Box::new(composite)
             } else if let Some(pattern) = filter_str.strip_prefix("NOT ") {
                 let mut composite = CompositeFilter::new(FilterOperation::Not);
-                composite.add_filter(create_base_filter(pattern, !args.case_sensitive));
-                Arc::new(composite)
+                composite.add_filter(create_base_filter(
+                    pattern,
+                    !args.case_sensitive,
+                    args.fuzzy_min_score,
+                ));
+                Box::new(composite)
             } else if let Some(pattern) = filter_str.strip_prefix("XOR ") {
                 let mut composite = CompositeFilter::new(FilterOperation::Xor);
-                composite.add_filter(create_base_filter(pattern, !args.case_sensitive));
-                Arc::new(composite)
+                composite.add_filter(create_base_filter(
+                    pattern,
+                    !args.case_sensitive,
+                    args.fuzzy_min_score,
+                ));
+                Box::new(composite)
             } else {
-                Arc::from(create_base_filter(filter_str, !args.case_sensitive))
+                create_base_filter(filter_str, !args.case_sensitive, args.fuzzy_min_score)
             }
         }
-        None => Arc::new(PatternFilter::new("".to_string())),
+        None => Box::new(PatternFilter::new("".to_string())),
     }
 }
 
-fn create_base_filter(pattern: &str, case_insensitive: bool) -> Box<dyn FileFilter + Send + Sync> {
+fn create_base_filter(
+    pattern: &str,
+    case_insensitive: bool,
+    fuzzy_min_score: i64,
+) -> Box<dyn FileFilter + Send + Sync> {
+    // Fuzzy matching always ignores case, like the fuzzy finder.
+    if let Some(query) = pattern.strip_prefix("fuzzy:") {
+        return Box::new(FuzzyFilter::new(query, fuzzy_min_score));
+    }
     let base_filter: Box<dyn FileFilter + Send + Sync> =
         if let Some(pattern) = pattern.strip_prefix("regex:") {
             Box::new(RegexFilter::new(pattern.to_string()))
@@ -898,6 +1538,15 @@ pub fn create_formatter(args: &Args, config: &Config) -> Box<dyn FileFormatter>
             args.show_icons,
             args.permission_format.clone(),
         ))
+    } else if let Some(template) = &args.format_template {
+        Box::new(TemplateFormatter::new(
+            template.clone(),
+            args.plain_output,
+            args.permission_format.clone(),
+            &args.date_format,
+            args.relative_dates,
+            args.classify,
+        ))
     } else if args.long_format {
         let columns =
             add_requested_metadata_columns(parse_columns(&config.formatters.long.columns), args);
@@ -908,9 +1557,15 @@ pub fn create_formatter(args: &Args, config: &Config) -> Box<dyn FileFormatter>
             args.relative_dates,
             args.date_format.clone(),
             columns,
+            args.classify,
         ))
     } else if args.tree_format {
-        Box::new(TreeFormatter::new(args.show_icons))
+        Box::new(TreeFormatter::new(
+            args.show_icons,
+            args.plain_output,
+            args.include_dirs.then(|| size_basis(args)),
+            args.classify,
+        ))
     } else if args.table_format {
         let columns =
             add_requested_metadata_columns(parse_columns(&config.formatters.table.columns), args);
@@ -924,17 +1579,36 @@ pub fn create_formatter(args: &Args, config: &Config) -> Box<dyn FileFormatter>
             args.show_icons,
             args.grid_ignore || config.formatters.grid.ignore_width,
             config.formatters.grid.max_width,
+            args.classify,
         ))
     } else if args.sizemap_format {
-        Box::new(SizeMapFormatter::new(args.show_icons))
+        Box::new(SizeMapFormatter::new(args.show_icons, size_basis(args)))
     } else if args.timeline_format {
         Box::new(TimelineFormatter::new(args.show_icons))
     } else if args.git_format {
         Box::new(GitFormatter::new(args.show_icons))
     } else if args.recursive_format {
-        Box::new(RecursiveFormatter::new(args.show_icons))
+        Box::new(RecursiveFormatter::new(
+            args.show_icons,
+            args.plain_output,
+            args.operands.iter().map(PathBuf::from).collect(),
+        ))
     } else {
-        Box::new(DefaultFormatter::new(args.show_icons))
+        Box::new(DefaultFormatter::new(
+            args.show_icons,
+            args.plain_output,
+            args.classify,
+        ))
+    }
+}
+
+/// Views that show a single size report allocated bytes when they were asked
+/// for with `--allocated-size`.
+fn size_basis(args: &Args) -> SizeBasis {
+    if args.show_allocated_size {
+        SizeBasis::Allocated
+    } else {
+        SizeBasis::Apparent
     }
 }
 
@@ -973,10 +1647,94 @@ fn add_requested_metadata_columns(
     if args.show_mounts {
         insert_before_name(&mut columns, ColumnKey::Mount);
     }
+    if args.show_mime {
+        insert_before_name(&mut columns, ColumnKey::Mime);
+    }
     columns
 }
 
-fn matches_metadata_filters(args: &Args, metadata: &EntryMetadata) -> bool {
+/// `--field-filter` matchers compiled against the declared types of the
+/// plugin fields they name, and the plugins that provide those fields or the
+/// `--sort field:` key. Those plugins decorate every entry whatever the view.
+struct FieldFilters {
+    matchers: Vec<FieldMatcher>,
+    plugins: HashSet<String>,
+}
+
+impl FieldFilters {
+    fn resolve(args: &Args, plugin_manager: &PluginManager) -> Result<Self> {
+        let now = SystemTime::now();
+        let mut matchers = Vec::with_capacity(args.field_filters.len());
+        let mut plugins = HashSet::new();
+        for filter in &args.field_filters {
+            let Some((plugin, descriptor)) = plugin_manager.field_descriptor(&filter.field) else {
+                let available = plugin_manager.filterable_fields();
+                return Err(LlaError::Filter(if available.is_empty() {
+                    format!(
+                        "No enabled plugin provides field '{}' (no filterable fields are available)",
+                        filter.field
+                    )
+                } else {
+                    format!(
+                        "No enabled plugin provides field '{}' (filterable fields: {})",
+                        filter.field,
+                        available.join(", ")
+                    )
+                }));
+            };
+            if !descriptor.filterable {
+                return Err(LlaError::Filter(format!(
+                    "Field '{}' of plugin '{}' is not filterable",
+                    filter.field, plugin
+                )));
+            }
+            matchers.push(filter.compile(descriptor.field_type, now)?);
+            plugins.insert(plugin);
+        }
+        for key in SortKey::parse_list(&args.sort_by) {
+            if let Some(field) = key.name.strip_prefix("field:") {
+                plugins.insert(sort_field(field, plugin_manager)?.0);
+            }
+        }
+        Ok(Self { matchers, plugins })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Decorates the entries and drops those that fail a field filter. Only
+    /// the plugins providing filtered fields see every entry; the others
+    /// decorate just the entries that are kept.
+    fn decorate(
+        &self,
+        plugin_manager: &mut PluginManager,
+        entries: &mut Vec<DecoratedEntry>,
+        format: &str,
+    ) {
+        if self.is_empty() {
+            plugin_manager.decorate_entries(entries, format);
+            return;
+        }
+        plugin_manager.decorate_entries_for_filter(entries, format, &self.plugins);
+        entries.retain(|entry| self.matchers.iter().all(|matcher| matcher.matches(entry)));
+        plugin_manager.decorate_entries_skipping(entries, format, &self.plugins);
+    }
+}
+
+/// Applies the metadata and content filters. A content type sniffed along the
+/// way is recorded in `custom_fields` so machine output does not read the file
+/// header a second time.
+fn matches_metadata_filters(
+    args: &Args,
+    path: &Path,
+    metadata: &EntryMetadata,
+    custom_fields: &mut HashMap<String, String>,
+) -> bool {
+    if !matches_ownership_filters(args, metadata) {
+        return false;
+    }
+
     if let Some(size_range) = &args.size_filter {
         if !size_range.matches(metadata.size) {
             return false;
@@ -995,15 +1753,82 @@ fn matches_metadata_filters(args: &Args, metadata: &EntryMetadata) -> bool {
         }
     }
 
+    if let Some(accessed_range) = &args.accessed_filter {
+        if metadata.accessed == 0 || !accessed_range.matches_epoch_secs(metadata.accessed) {
+            return false;
+        }
+    }
+
+    if args.newer_than.is_some_and(|reference| {
+        compare_mtime(args, path, metadata, reference) != Some(Ordering::Greater)
+    }) || args.older_than.is_some_and(|reference| {
+        compare_mtime(args, path, metadata, reference) != Some(Ordering::Less)
+    }) {
+        return false;
+    }
+
+    if !args
+        .where_filters
+        .iter()
+        .all(|expr| expr.matches(path, metadata))
+    {
+        return false;
+    }
+
+    // Content sniffing opens the file, so it runs after the cheaper checks,
+    // and only when `--type` or the `mime` field of machine output needs it.
+    let records_mime = args.show_mime && !matches!(args.output_mode, OutputMode::Human);
+    if args.content_types.is_empty() && !records_mime {
+        return true;
+    }
+    let sniffed = content_type::sniff(path, metadata);
+    if !args.content_types.is_empty()
+        && !sniffed.is_some_and(|sniffed| args.content_types.iter().any(|t| sniffed.is(*t)))
+    {
+        return false;
+    }
+    if let Some(sniffed) = sniffed {
+        custom_fields.insert(MIME_FIELD.to_string(), sniffed.mime.to_string());
+    }
     true
 }
 
-fn apply_refine_filters(
-    entries: Vec<DecoratedEntry>,
-    refinements: &[String],
-    case_sensitive: bool,
-) -> Result<Vec<DecoratedEntry>> {
-    if refinements.is_empty() {
+/// Orders an entry's modification time against a `--newer`/`--older`
+/// reference. Whole seconds decide unless they tie, when the entry is read
+/// again for its full-precision time; `None` when that read fails.
+fn compare_mtime(
+    args: &Args,
+    path: &Path,
+    metadata: &EntryMetadata,
+    reference: SystemTime,
+) -> Option<Ordering> {
+    let reference_secs = reference
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    if metadata.modified != reference_secs {
+        return Some(metadata.modified.cmp(&reference_secs));
+    }
+    let full = if args.dereference_symlinks {
+        fs::metadata(path)
+    } else {
+        fs::symlink_metadata(path)
+    };
+    Some(full.and_then(|full| full.modified()).ok()?.cmp(&reference))
+}
+
+/// `--owner`, `--group` and `--perm`, which content search also applies when
+/// choosing the files to search.
+pub fn matches_ownership_filters(args: &Args, metadata: &EntryMetadata) -> bool {
+    args.owner_filter.is_none_or(|uid| metadata.uid == uid)
+        && args.group_filter.is_none_or(|gid| metadata.gid == gid)
+        && args
+            .perm_filters
+            .iter()
+            .all(|perm| perm.matches(metadata.permissions))
+}
+
+fn apply_refine_filters(entries: Vec<DecoratedEntry>, args: &Args) -> Result<Vec<DecoratedEntry>> {
+    if args.refine_filters.is_empty() {
         return Ok(entries);
     }
 
@@ -1012,8 +1837,8 @@ fn apply_refine_filters(
         .map(|entry| PathBuf::from(&entry.path))
         .collect();
 
-    for expr in refinements {
-        let filter = create_base_filter(expr, !case_sensitive);
+    for expr in &args.refine_filters {
+        let filter = create_base_filter(expr, !args.case_sensitive, args.fuzzy_min_score);
         current_paths = filter.filter_files(&current_paths)?;
         if current_paths.is_empty() {
             return Ok(Vec::new());
@@ -1039,6 +1864,8 @@ struct ListingContext {
     tree_format: bool,
     recursive_format: bool,
     include_dir_sizes: bool,
+    allocated_size: bool,
+    mime: bool,
     dirs_only: bool,
     files_only: bool,
     symlinks_only: bool,
@@ -1053,10 +1880,24 @@ struct ListingContext {
     almost_all: bool,
     dotfiles_only: bool,
     respect_gitignore: bool,
+    one_file_system: bool,
+    llaignore: bool,
     filter: Option<String>,
+    fuzzy_query: Option<String>,
+    fuzzy_min_score: i64,
     size: Option<String>,
     modified: Option<String>,
     created: Option<String>,
+    accessed: Option<String>,
+    newer_than: Option<SystemTime>,
+    older_than: Option<SystemTime>,
+    where_exprs: Vec<String>,
+    field_filters: Vec<String>,
+    content_types: Vec<&'static str>,
+    git_status: Vec<&'static str>,
+    owner: Option<u32>,
+    group: Option<u32>,
+    perms: Vec<String>,
     case_sensitive: bool,
     preset_names: Vec<String>,
     exclude_paths: Vec<String>,
@@ -1070,7 +1911,9 @@ impl ListingContext {
             depth: args.depth,
             tree_format: args.tree_format,
             recursive_format: args.recursive_format,
-            include_dir_sizes: needs_directory_sizes(args, config),
+            include_dir_sizes: upfront_directory_sizes(args, config),
+            allocated_size: args.show_allocated_size,
+            mime: args.show_mime,
             dirs_only: args.dirs_only,
             files_only: args.files_only,
             symlinks_only: args.symlinks_only,
@@ -1088,10 +1931,28 @@ impl ListingContext {
             almost_all: args.almost_all,
             dotfiles_only: args.dotfiles_only,
             respect_gitignore: args.respect_gitignore,
+            one_file_system: args.one_file_system,
+            llaignore: args.llaignore != LlaIgnoreMode::Off,
             filter: args.filter.clone(),
+            fuzzy_query: args.fuzzy_query.clone(),
+            fuzzy_min_score: args.fuzzy_min_score,
             size: args.size_filter_raw.clone(),
             modified: args.modified_filter_raw.clone(),
             created: args.created_filter_raw.clone(),
+            accessed: args.accessed_filter_raw.clone(),
+            newer_than: args.newer_than,
+            older_than: args.older_than,
+            where_exprs: args
+                .where_filters
+                .iter()
+                .map(|expr| expr.source().to_string())
+                .collect(),
+            field_filters: args.field_filters.iter().map(ToString::to_string).collect(),
+            content_types: args.content_types.iter().map(|t| t.name()).collect(),
+            git_status: args.git_status.iter().map(|s| s.name()).collect(),
+            owner: args.owner_filter,
+            group: args.group_filter,
+            perms: args.perm_filters.iter().map(ToString::to_string).collect(),
             case_sensitive: args.case_sensitive,
             preset_names: args.presets.clone(),
             exclude_paths: config
@@ -1123,11 +1984,14 @@ fn canonicalize_path_for_cache(path: &str) -> Option<String> {
 #[cfg(all(test, unix))]
 mod tests {
     use super::*;
+    use crate::sorter::{Collation, EntryLimit};
+    use crate::utils::content_type::ContentType;
     use std::os::unix::fs::{symlink, MetadataExt};
 
     fn args_with_include_dirs() -> Args {
         Args {
             directory: ".".to_string(),
+            operands: vec![".".to_string()],
             depth: None,
             long_format: false,
             tree_format: false,
@@ -1139,6 +2003,7 @@ mod tests {
             git_format: false,
             fuzzy_format: false,
             recursive_format: false,
+            format_template: None,
             show_icons: false,
             hyperlinks: false,
             no_color: true,
@@ -1147,7 +2012,11 @@ mod tests {
             sort_dirs_first: false,
             sort_case_sensitive: false,
             sort_natural: false,
+            sort_collation: Collation::Bytes,
+            limit: None,
             filter: None,
+            fuzzy_query: None,
+            fuzzy_min_score: 0,
             presets: Vec::new(),
             size_filter: None,
             size_filter_raw: None,
@@ -1155,6 +2024,17 @@ mod tests {
             modified_filter_raw: None,
             created_filter: None,
             created_filter_raw: None,
+            accessed_filter: None,
+            accessed_filter_raw: None,
+            newer_than: None,
+            older_than: None,
+            where_filters: Vec::new(),
+            field_filters: Vec::new(),
+            content_types: Vec::new(),
+            git_status: Vec::new(),
+            owner_filter: None,
+            group_filter: None,
+            perm_filters: Vec::new(),
             case_sensitive: false,
             refine_filters: Vec::new(),
             enable_plugin: Vec::new(),
@@ -1174,6 +2054,8 @@ mod tests {
             almost_all: false,
             dotfiles_only: false,
             respect_gitignore: false,
+            one_file_system: true,
+            llaignore: LlaIgnoreMode::On,
             permission_format: "symbolic".to_string(),
             hide_group: false,
             relative_dates: false,
@@ -1184,7 +2066,12 @@ mod tests {
             show_xattrs: false,
             show_context: false,
             show_mounts: false,
+            show_mime: false,
+            classify: false,
+            plain_output: None,
             output_mode: OutputMode::Human,
+            path_list: None,
+            watch: None,
             command: None,
             search: None,
             search_context: 2,
@@ -1200,6 +2087,16 @@ mod tests {
 
         assert!(!needs_directory_sizes(&args, &config));
 
+        // Plain tree output prints paths only.
+        let mut tree_args = args_with_include_dirs();
+        tree_args.tree_format = true;
+        assert!(needs_directory_sizes(&tree_args, &config));
+        tree_args.plain_output = Some(crate::formatter::quoting::PlainOutput {
+            quoting: crate::formatter::quoting::QuotingStyle::Literal,
+            print0: true,
+        });
+        assert!(!needs_directory_sizes(&tree_args, &config));
+
         let context = ListingContext::from_args(&args, &config);
         assert!(!context.include_dir_sizes);
     }
@@ -1236,6 +2133,14 @@ mod tests {
 
         let context = ListingContext::from_args(&long_args, &config);
         assert!(context.include_dir_sizes);
+
+        long_args.show_allocated_size = true;
+        let allocated = ListingContext::from_args(&long_args, &config);
+        assert_ne!(context.cache_key(), allocated.cache_key());
+
+        long_args.one_file_system = !long_args.one_file_system;
+        let crossing = ListingContext::from_args(&long_args, &config);
+        assert_ne!(allocated.cache_key(), crossing.cache_key());
     }
 
     #[test]
@@ -1248,20 +2153,6 @@ mod tests {
         assert!(!needs_directory_sizes(&args, &config));
     }
 
-    #[test]
-    fn iterative_directory_size_counts_files_and_skips_symlinks() {
-        let root = tempfile::tempdir().unwrap();
-        let nested = root.path().join("nested");
-        fs::create_dir(&nested).unwrap();
-        fs::write(root.path().join("one"), [0u8; 7]).unwrap();
-        fs::write(nested.join("two"), [0u8; 11]).unwrap();
-
-        #[cfg(unix)]
-        std::os::unix::fs::symlink(root.path().join("one"), nested.join("link")).unwrap();
-
-        assert_eq!(calculate_dir_size(root.path()).unwrap(), 18);
-    }
-
     #[test]
     fn dereferenced_symlink_keeps_link_identity_and_uses_target_metadata() {
         let directory = tempfile::tempdir().unwrap();
@@ -1284,4 +2175,365 @@ mod tests {
         assert_eq!(dereferenced.size, 1024);
         assert_eq!(dereferenced.inode, fs::metadata(target).unwrap().ino());
     }
+
+    #[test]
+    fn streams_only_recursive_listings_with_streaming_views() {
+        let config = Config::default();
+        let mut args = args_with_include_dirs();
+        assert!(!should_stream(
+            &args,
+            create_formatter(&args, &config).as_ref()
+        ));
+
+        args.recursive_format = true;
+        assert!(should_stream(
+            &args,
+            create_formatter(&args, &config).as_ref()
+        ));
+
+        args.refine_filters = vec!["foo".to_string()];
+        assert!(!should_stream(
+            &args,
+            create_formatter(&args, &config).as_ref()
+        ));
+
+        let mut tree_args = args_with_include_dirs();
+        tree_args.tree_format = true;
+        assert!(!should_stream(
+            &tree_args,
+            create_formatter(&tree_args, &config).as_ref()
+        ));
+
+        tree_args.output_mode = OutputMode::Ndjson;
+        assert!(should_stream(
+            &tree_args,
+            create_formatter(&tree_args, &config).as_ref()
+        ));
+    }
+
+    #[test]
+    fn recursive_lister_emits_one_batch_per_directory() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("a/b")).unwrap();
+        fs::write(root.path().join("top"), b"").unwrap();
+        fs::write(root.path().join("a/mid"), b"").unwrap();
+        fs::write(root.path().join("a/b/leaf"), b"").unwrap();
+
+        let lister = RecursiveLister::new(Config::default(), true, LlaIgnoreMode::On);
+        let mut batches = Vec::new();
+        lister
+            .list_files_batched(root.path().to_str().unwrap(), true, None, &mut |batch| {
+                let mut names: Vec<String> = batch
+                    .iter()
+                    .map(|p| {
+                        p.strip_prefix(root.path())
+                            .unwrap()
+                            .to_string_lossy()
+                            .into_owned()
+                    })
+                    .collect();
+                names.sort();
+                batches.push(names);
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(
+            batches,
+            vec![
+                // The root leads its own children, then each directory's.
+                vec!["".to_string(), "a".to_string(), "top".to_string()],
+                vec!["a/b".to_string(), "a/mid".to_string()],
+                vec!["a/b/leaf".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn gitignore_stream_never_descends_into_ignored_directories() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("ignored/deep")).unwrap();
+        fs::create_dir(root.path().join("kept")).unwrap();
+        fs::write(root.path().join(".gitignore"), b"ignored/\n").unwrap();
+        fs::write(root.path().join("ignored/deep/file"), b"").unwrap();
+        fs::write(root.path().join("kept/file"), b"").unwrap();
+
+        let mut args = args_with_include_dirs();
+        args.directory = root.path().to_string_lossy().into_owned();
+        args.recursive_format = true;
+        args.respect_gitignore = true;
+
+        let mut seen = Vec::new();
+        stream_files_with_gitignore(&args, &Config::default(), &mut |batch| {
+            seen.extend(batch);
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(seen.contains(&root.path().join("kept/file")));
+        assert!(!seen
+            .iter()
+            .any(|p| p.starts_with(root.path().join("ignored"))));
+    }
+
+    #[test]
+    fn walkers_prune_excluded_directories() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("node_modules/pkg")).unwrap();
+        fs::create_dir(root.path().join("src")).unwrap();
+        fs::write(root.path().join("node_modules/pkg/index.js"), b"").unwrap();
+        fs::write(root.path().join("src/main.rs"), b"").unwrap();
+
+        let config = Config {
+            exclude_paths: vec![root.path().join("node_modules")],
+            ..Config::default()
+        };
+        let directory = root.path().to_string_lossy().into_owned();
+        let excluded = root.path().join("node_modules");
+
+        let walked = RecursiveLister::new(config.clone(), true, LlaIgnoreMode::On)
+            .list_files(&directory, true, None)
+            .unwrap();
+        assert!(walked.contains(&root.path().join("src/main.rs")));
+        assert!(!walked.iter().any(|p| p.starts_with(&excluded)));
+
+        let mut args = args_with_include_dirs();
+        args.directory = directory;
+        args.recursive_format = true;
+        args.respect_gitignore = true;
+        let walked = list_files_with_gitignore(&args, &config).unwrap();
+        assert!(walked.contains(&root.path().join("src/main.rs")));
+        assert!(!walked.iter().any(|p| p.starts_with(&excluded)));
+    }
+
+    #[test]
+    fn classifies_operands_and_tags_machine_entries() {
+        let root = tempfile::tempdir().unwrap();
+        let file = root.path().join("notes.txt");
+        let archive = root.path().join("bundle.zip");
+        fs::write(&file, b"notes").unwrap();
+        fs::write(&archive, b"").unwrap();
+
+        assert_eq!(
+            operand_kind(root.path().to_str().unwrap()),
+            OperandKind::Directory
+        );
+        assert_eq!(operand_kind(file.to_str().unwrap()), OperandKind::File);
+        assert_eq!(
+            operand_kind(archive.to_str().unwrap()),
+            OperandKind::Archive
+        );
+
+        let mut entries = vec![DecoratedEntry {
+            path: file.to_string_lossy().into_owned(),
+            metadata: None,
+            custom_fields: HashMap::new(),
+            typed_fields: Default::default(),
+        }];
+        let untagged = crate::formatter::serializable::to_serializable(&entries[0], None);
+        assert!(!serde_json::to_string(&untagged)
+            .unwrap()
+            .contains("\"operand\""));
+
+        // A plugin field named `operand` is reported as such.
+        entries[0]
+            .custom_fields
+            .insert("operand".to_string(), "plugin".to_string());
+        tag_operand(&mut entries, "notes.txt");
+        let serial = crate::formatter::serializable::to_serializable(&entries[0], None);
+        assert_eq!(serial.operand.as_deref(), Some("notes.txt"));
+        assert!(!serial.plugin.contains_key(OPERAND_FIELD));
+        assert_eq!(serial.plugin["operand"], "plugin");
+    }
+
+    #[test]
+    fn sniffs_mime_only_when_requested() {
+        let root = tempfile::tempdir().unwrap();
+        let file = root.path().join("notes.txt");
+        fs::write(&file, b"notes").unwrap();
+        let metadata = convert_metadata(&fs::metadata(&file).unwrap());
+
+        let mut args = args_with_include_dirs();
+        args.output_mode = OutputMode::Json { pretty: false };
+        let mut fields = HashMap::new();
+        assert!(matches_metadata_filters(
+            &args,
+            &file,
+            &metadata,
+            &mut fields
+        ));
+        assert!(!fields.contains_key(MIME_FIELD));
+
+        args.show_mime = true;
+        assert!(matches_metadata_filters(
+            &args,
+            &file,
+            &metadata,
+            &mut fields
+        ));
+        assert_eq!(fields[MIME_FIELD], "text/plain");
+
+        // The type sniffed for `--type` is kept whatever the output.
+        args.show_mime = false;
+        args.output_mode = OutputMode::Human;
+        args.content_types = vec![ContentType::Text];
+        let mut fields = HashMap::new();
+        assert!(matches_metadata_filters(
+            &args,
+            &file,
+            &metadata,
+            &mut fields
+        ));
+        assert_eq!(fields[MIME_FIELD], "text/plain");
+    }
+
+    #[test]
+    fn newer_and_older_compare_within_the_same_second() {
+        let root = tempfile::tempdir().unwrap();
+        let base = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        let stamp = |name: &str, nanos: u64| {
+            let path = root.path().join(name);
+            fs::File::create(&path)
+                .unwrap()
+                .set_modified(base + std::time::Duration::from_nanos(nanos))
+                .unwrap();
+            path
+        };
+        let early = stamp("early", 100);
+        let late = stamp("late", 900);
+        let reference = base + std::time::Duration::from_nanos(500);
+        let metadata = |path: &Path| convert_metadata(&fs::metadata(path).unwrap());
+
+        let mut args = args_with_include_dirs();
+        args.newer_than = Some(reference);
+        let mut fields = HashMap::new();
+        assert!(matches_metadata_filters(
+            &args,
+            &late,
+            &metadata(&late),
+            &mut fields
+        ));
+        assert!(!matches_metadata_filters(
+            &args,
+            &early,
+            &metadata(&early),
+            &mut fields
+        ));
+
+        args.newer_than = None;
+        args.older_than = Some(reference);
+        assert!(matches_metadata_filters(
+            &args,
+            &early,
+            &metadata(&early),
+            &mut fields
+        ));
+        assert!(!matches_metadata_filters(
+            &args,
+            &late,
+            &metadata(&late),
+            &mut fields
+        ));
+    }
+
+    #[test]
+    fn path_list_trees_are_sorted_within_each_directory() {
+        let root = tempfile::tempdir().unwrap();
+        for dir in ["a", "b"] {
+            fs::create_dir(root.path().join(dir)).unwrap();
+        }
+        for file in ["a/y", "a/x", "b/z"] {
+            fs::write(root.path().join(file), b"").unwrap();
+        }
+        let list = root.path().join("paths");
+        fs::write(&list, "b/z\nb\na/y\na\na/x\n").unwrap();
+        let paths = path_list::read_path_list(&PathListSource::File(list))
+            .unwrap()
+            .into_iter()
+            .map(|path| root.path().join(path))
+            .collect();
+
+        let mut args = args_with_include_dirs();
+        args.tree_format = true;
+        let config = Config::default();
+        let mut plugin_manager = PluginManager::new(config.clone());
+        let entries = collect_path_list(&args, &config, &mut plugin_manager, paths).unwrap();
+        let order: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                Path::new(&entry.path)
+                    .strip_prefix(root.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(order, ["a", "b", "a/x", "a/y", "b/z"]);
+    }
+
+    #[test]
+    fn rewalked_subtrees_keep_their_own_directory() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir(root.path().join("sub")).unwrap();
+        fs::write(root.path().join("sub/file"), b"").unwrap();
+
+        let mut args = args_with_include_dirs();
+        args.include_dirs = false;
+        args.recursive_format = true;
+        args.respect_gitignore = true;
+        args.directory = root.path().to_string_lossy().into_owned();
+        let config = Config::default();
+        let lister = create_lister(&args, &config);
+        let filter = create_filter(&args);
+        let mut plugin_manager = PluginManager::new(config.clone());
+        let dir = root.path().join("sub");
+        let entries = rewalk_subtree(
+            &args,
+            &config,
+            &lister,
+            &filter,
+            &mut plugin_manager,
+            "default",
+            &dir,
+        )
+        .unwrap();
+        let mut paths: Vec<_> = entries
+            .iter()
+            .map(|entry| PathBuf::from(&entry.path))
+            .collect();
+        paths.sort();
+        assert_eq!(paths, [dir.clone(), dir.join("file")]);
+    }
+
+    #[test]
+    fn limited_listings_total_only_kept_directories() {
+        let mut args = args_with_include_dirs();
+        args.limit = Some(EntryLimit::Head(2));
+        assert!(sizes_after_limit(&args));
+        args.sort_by = "size".to_string();
+        assert!(!sizes_after_limit(&args));
+        args.sort_by = "name".to_string();
+
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("kept/nested")).unwrap();
+        fs::write(root.path().join("kept/one"), b"abc").unwrap();
+        fs::write(root.path().join("kept/nested/two"), b"de").unwrap();
+        args.directory = root.path().to_string_lossy().into_owned();
+        let entry = |path: PathBuf| DecoratedEntry {
+            path: path.to_string_lossy().into_owned(),
+            metadata: Some(convert_metadata(&fs::metadata(&path).unwrap())),
+            ..Default::default()
+        };
+        let mut kept = vec![
+            entry(root.path().join("kept")),
+            entry(root.path().join("kept/nested")),
+            entry(root.path().join("kept/one")),
+        ];
+        apply_kept_directory_sizes(&args, &mut kept);
+        let sizes: Vec<u64> = kept
+            .iter()
+            .map(|e| e.metadata.as_ref().unwrap().size)
+            .collect();
+        assert_eq!(sizes, [5, 2, 3]);
+    }
 }
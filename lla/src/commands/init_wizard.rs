@@ -142,10 +142,19 @@ pub fn run_wizard() -> Result<()> {
     );
     step += 1;
 
-    const SORT_CHOICES: [(&str, &str); 3] = [
+    const SORT_CHOICES: [(&str, &str); 12] = [
         ("Name (A→Z)", "name"),
         ("Size (small → large)", "size"),
-        ("Date (newest first)", "date"),
+        ("Date (oldest first)", "date"),
+        ("Created (oldest first)", "created"),
+        ("Accessed (oldest first)", "accessed"),
+        ("Extension (A→Z)", "extension"),
+        ("Kind (directories first)", "kind"),
+        ("Owner (A→Z)", "owner"),
+        ("Group (A→Z)", "group"),
+        ("Inode (low → high)", "inode"),
+        ("Hard links (few → many)", "links"),
+        ("Version (v1.9 before v1.10)", "version"),
     ];
     let sort_labels: Vec<&str> = SORT_CHOICES.iter().map(|(label, _)| *label).collect();
     let default_sort_index = SORT_CHOICES
@@ -1,8 +1,12 @@
 pub mod args;
+pub mod cache;
 pub mod command_handler;
 pub mod diff;
 pub mod file_utils;
 pub mod init_wizard;
 pub mod jump;
+pub mod path_list;
 pub mod plugin_utils;
+pub mod preset;
 pub mod search;
+pub mod watch;
@@ -0,0 +1,127 @@
+use crate::commands::args::PathListSource;
+use crate::error::{LlaError, Result};
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+/// A path from the input list that could not be stat'ed.
+#[derive(Debug, Serialize)]
+pub struct MissingPath {
+    pub path: String,
+    pub error: String,
+}
+
+/// Reads the path list named by `--stdin` or `--from-file`.
+pub fn read_path_list(source: &PathListSource) -> Result<Vec<PathBuf>> {
+    let bytes = match source {
+        PathListSource::Stdin => {
+            let mut buffer = Vec::new();
+            io::stdin().lock().read_to_end(&mut buffer)?;
+            buffer
+        }
+        PathListSource::File(path) => fs::read(path).map_err(|e| {
+            LlaError::Other(format!(
+                "Failed to read path list '{}': {}",
+                path.display(),
+                e
+            ))
+        })?,
+    };
+    Ok(parse_path_list(&bytes))
+}
+
+/// Splits on NUL when the input contains any, as produced by `git ls-files -z`
+/// or `fd -0`, and on newlines otherwise. Empty records are skipped.
+pub fn parse_path_list(bytes: &[u8]) -> Vec<PathBuf> {
+    let nul_separated = bytes.contains(&0);
+    let separator = if nul_separated { 0 } else { b'\n' };
+    bytes
+        .split(|byte| *byte == separator)
+        .filter_map(|record| {
+            let record = if nul_separated {
+                record
+            } else {
+                record.strip_suffix(b"\r").unwrap_or(record)
+            };
+            (!record.is_empty()).then(|| bytes_to_path(record))
+        })
+        .collect()
+}
+
+/// Separates paths that exist (including broken symlinks) from those that
+/// cannot be stat'ed at all.
+pub fn split_missing(paths: Vec<PathBuf>) -> (Vec<PathBuf>, Vec<MissingPath>) {
+    let mut present = Vec::with_capacity(paths.len());
+    let mut missing = Vec::new();
+    for path in paths {
+        match path.symlink_metadata() {
+            Ok(_) => present.push(path),
+            Err(err) => missing.push(MissingPath {
+                path: path.to_string_lossy().into_owned(),
+                error: err.to_string(),
+            }),
+        }
+    }
+    (present, missing)
+}
+
+/// Reports missing paths on stderr: one JSON object per line for machine
+/// output, plain messages otherwise.
+pub fn report_missing(missing: &[MissingPath], machine_output: bool) -> Result<()> {
+    let mut stderr = io::stderr().lock();
+    for entry in missing {
+        if machine_output {
+            serde_json::to_writer(&mut stderr, entry)?;
+            writeln!(stderr)?;
+        } else {
+            writeln!(
+                stderr,
+                "lla: cannot access '{}': {}",
+                entry.path, entry.error
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn bytes_to_path(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(windows)]
+fn bytes_to_path(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_newline_and_nul_delimited_input() {
+        assert_eq!(
+            parse_path_list(b"src/main.rs\r\nCargo.toml\n\n"),
+            vec![PathBuf::from("src/main.rs"), PathBuf::from("Cargo.toml")]
+        );
+        assert_eq!(
+            parse_path_list(b"with\nnewline\0plain\0"),
+            vec![PathBuf::from("with\nnewline"), PathBuf::from("plain")]
+        );
+    }
+
+    #[test]
+    fn separates_missing_paths() {
+        let root = tempfile::tempdir().unwrap();
+        let present = root.path().join("present");
+        fs::write(&present, b"").unwrap();
+        let absent = root.path().join("absent");
+
+        let (found, missing) = split_missing(vec![present.clone(), absent.clone()]);
+        assert_eq!(found, vec![present]);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].path, absent.to_string_lossy());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::commands::args::{Args, PresetAction};
+use crate::commands::file_utils::collect_listing;
+use crate::config::{load_config_layers, Config, ResolvedPreset};
+use crate::error::Result;
+use crate::plugin::PluginManager;
+use colored::*;
+use std::fs;
+use toml::Value as TomlValue;
+
+pub fn handle_preset(
+    action: &PresetAction,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+) -> Result<()> {
+    match action {
+        PresetAction::List => list_presets(config),
+        PresetAction::Show(spec) => print_preset(&config.resolve_preset(spec)?),
+        PresetAction::Test { preset, directory } => {
+            let resolved = config.resolve_preset(preset)?;
+            print_preset(&resolved)?;
+            let args = Args::with_preset(config, preset, directory)?;
+            let matched = collect_listing(&args, config, plugin_manager)?.len();
+            println!(
+                "{} {} {} in {}",
+                "Matches:    ".bold(),
+                matched,
+                if matched == 1 { "entry" } else { "entries" },
+                directory
+            );
+            Ok(())
+        }
+    }
+}
+
+fn list_presets(config: &Config) -> Result<()> {
+    if config.filter.presets.is_empty() {
+        println!("No presets configured");
+        return Ok(());
+    }
+    let sources = PresetSources::load()?;
+    let mut names: Vec<&String> = config.filter.presets.keys().collect();
+    names.sort();
+    let width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+    for name in names {
+        let preset = &config.filter.presets[name];
+        let mut details = Vec::new();
+        if !preset.extends.is_empty() {
+            details.push(format!("extends {}", preset.extends.join(", ")));
+        }
+        if !preset.params.is_empty() {
+            let mut params: Vec<String> = preset
+                .params
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            params.sort();
+            details.push(params.join(","));
+        }
+        details.push(sources.describe(name));
+        println!(
+            "{}  {}  {}",
+            format!("{:width$}", name, width = width).bold(),
+            preset.description.as_deref().unwrap_or("-"),
+            format!("({})", details.join("; ")).dimmed()
+        );
+    }
+    Ok(())
+}
+
+fn print_preset(preset: &ResolvedPreset) -> Result<()> {
+    let sources = PresetSources::load()?;
+    println!("{} {}", "Preset:     ".bold(), preset.name);
+    println!(
+        "{} {}",
+        "Source:     ".bold(),
+        sources.describe(&preset.name)
+    );
+    if let Some(description) = &preset.description {
+        println!("{} {}", "Description:".bold(), description);
+    }
+    if preset.chain.len() > 1 {
+        println!("{} {}", "Extends:    ".bold(), preset.chain.join(" -> "));
+    }
+    if !preset.params.is_empty() {
+        let params: Vec<String> = preset
+            .params
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        println!("{} {}", "Parameters: ".bold(), params.join(","));
+    }
+    let flags = preset.to_flags();
+    if flags.is_empty() {
+        println!("{} (matches everything)", "Filter:     ".bold());
+    }
+    for (index, (flag, value)) in flags.iter().enumerate() {
+        let label = if index == 0 {
+            "Filter:     "
+        } else {
+            "            "
+        };
+        if value.is_empty() {
+            println!("{} {}", label.bold(), flag);
+        } else {
+            println!("{} {} {}", label.bold(), flag, shell_quote(value));
+        }
+    }
+    Ok(())
+}
+
+/// Where presets come from: the global config, the nearest `.lla.toml`, or
+/// both when a project profile overrides a global preset.
+struct PresetSources {
+    global: Config,
+    profile: Option<(String, TomlValue)>,
+}
+
+impl PresetSources {
+    fn load() -> Result<Self> {
+        let (layers, _) = load_config_layers(None)?;
+        let profile = match &layers.profile_path {
+            Some(path) => Some((
+                path.display().to_string(),
+                toml::from_str(&fs::read_to_string(path)?)?,
+            )),
+            None => None,
+        };
+        Ok(Self {
+            global: layers.global,
+            profile,
+        })
+    }
+
+    fn describe(&self, name: &str) -> String {
+        let in_global = self.global.filter.presets.contains_key(name);
+        let in_profile = self.profile.as_ref().and_then(|(path, value)| {
+            value
+                .get("filter")
+                .and_then(|filter| filter.get("presets"))
+                .and_then(|presets| presets.get(name))
+                .map(|_| path.as_str())
+        });
+        match (in_profile, in_global) {
+            (Some(path), true) => format!("{} (over global config)", path),
+            (Some(path), false) => path.to_string(),
+            (None, _) => "global config".to_string(),
+        }
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_.,:/=+".contains(c))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
@@ -1,9 +1,12 @@
 use crate::commands::args::{Args, OutputMode, SearchPipelineSpec};
+use crate::commands::file_utils::matches_ownership_filters;
 use crate::config::Config;
 use crate::error::{LlaError, Result};
 use crate::plugin::PluginManager;
 use crate::theme::is_no_color;
 use crate::utils::color::colorize_file_name;
+use crate::utils::exclude::ExcludeSet;
+use crate::utils::fs_metadata;
 use colored::*;
 use ignore::WalkBuilder;
 use lla_plugin_utils::syntax::CodeHighlighter;
@@ -133,24 +136,39 @@ pub fn run_search(args: &Args, config: &Config, plugin_manager: &mut PluginManag
     // Scope search paths: walk honoring .gitignore and config.filter.no_dotfiles with ignore crate
     // Collect eligible paths to pass to ripgrep to avoid traversing excluded prefixes
     let mut paths: Vec<PathBuf> = Vec::new();
+    let excludes = ExcludeSet::new(config, root, args.llaignore);
     let mut walker = WalkBuilder::new(root);
     walker.hidden(!args.almost_all && (args.no_dotfiles || config.filter.no_dotfiles));
     walker.git_ignore(true).git_exclude(true).parents(true);
+    // Prune excluded directories instead of walking them and dropping the results
+    walker.filter_entry(move |entry| {
+        !excludes.contains_entry(
+            entry.path(),
+            entry.file_type().is_some_and(|ft| ft.is_dir()),
+        )
+    });
     let walker = walker.build();
     for d in walker.flatten() {
         let p = d.path();
-        // Skip directories under excluded prefixes
-        if !config.exclude_paths.is_empty() {
-            let abs = p;
-            if config.exclude_paths.iter().any(|ex| abs.starts_with(ex)) {
-                continue;
-            }
-        }
         if p.is_file() && (args.files_only || (!args.no_files)) {
             paths.push(p.to_path_buf());
         }
     }
 
+    // Ownership and permission filters narrow the candidates up front.
+    let restricted =
+        args.owner_filter.is_some() || args.group_filter.is_some() || !args.perm_filters.is_empty();
+    if restricted {
+        paths.retain(|p| {
+            std::fs::metadata(p)
+                .is_ok_and(|md| matches_ownership_filters(args, &fs_metadata::from_metadata(&md)))
+        });
+        if paths.is_empty() {
+            println!("No results (no files match the ownership and permission filters)");
+            return Ok(());
+        }
+    }
+
     if paths.is_empty() {
         // Still run rg on root to allow it to handle includes if any
         cmd.arg(".");
@@ -251,7 +269,7 @@ fn render_pretty(matches: &[RgMatch], args: &Args) -> Result<()> {
             println!(
                 "\n{} {} {}",
                 "File:".bright_black().bold(),
-                colorize_file_name(path).bold(),
+                colorize_file_name(path, None).bold(),
                 format!("[{}]", full_path).bright_black()
             );
         }
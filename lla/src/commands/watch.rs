@@ -0,0 +1,246 @@
+use crate::commands::args::{Args, OutputMode};
+use crate::commands::file_utils::{collect_listing, render_listing};
+use crate::config::Config;
+use crate::error::{LlaError, Result};
+use crate::formatter::json::NdjsonWriter;
+use crate::formatter::FileFormatter;
+use crate::plugin::PluginManager;
+use crossterm::{cursor, execute, terminal};
+use lla_plugin_interface::proto::DecoratedEntry;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::io::{self, Write};
+use std::path::{Component, Path};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// Re-runs the listing whenever a watched operand changes. Human views are
+/// redrawn in place; NDJSON reports added, removed and modified entries.
+pub fn run(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+    formatter: &dyn FileFormatter,
+    interval: Duration,
+) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = sender.send(event);
+    })
+    .map_err(|e| LlaError::Other(format!("Failed to start file watcher: {}", e)))?;
+    watch_operands(&mut watcher, args)?;
+
+    match args.output_mode {
+        OutputMode::Ndjson => watch_ndjson(args, config, plugin_manager, &receiver, interval),
+        _ => watch_human(args, config, plugin_manager, formatter, &receiver, interval),
+    }
+}
+
+fn watch_operands(watcher: &mut RecommendedWatcher, args: &Args) -> Result<()> {
+    let mode = if args.tree_format || args.recursive_format {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    for operand in &args.operands {
+        watcher
+            .watch(Path::new(operand), mode)
+            .map_err(|e| LlaError::Other(format!("Failed to watch '{}': {}", operand, e)))?;
+    }
+    Ok(())
+}
+
+fn watch_human(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+    formatter: &dyn FileFormatter,
+    receiver: &Receiver<notify::Result<Event>>,
+    interval: Duration,
+) -> Result<()> {
+    // Redrawing in place only makes sense on a terminal; when piped, each
+    // listing is simply written after the previous one.
+    let redraw = atty::is(atty::Stream::Stdout);
+    loop {
+        if redraw {
+            execute!(
+                io::stdout(),
+                cursor::MoveTo(0, 0),
+                terminal::Clear(terminal::ClearType::All)
+            )?;
+        }
+        // A listing can fail transiently, for example while a directory is
+        // being replaced, so report the error and keep watching.
+        if let Err(err) = render_listing(args, config, plugin_manager, formatter) {
+            eprintln!("lla: {}", err);
+        }
+        io::stdout().flush()?;
+
+        if !wait_for_change(receiver, interval) {
+            return Ok(());
+        }
+    }
+}
+
+fn watch_ndjson(
+    args: &Args,
+    config: &Config,
+    plugin_manager: &mut PluginManager,
+    receiver: &Receiver<notify::Result<Event>>,
+    interval: Duration,
+) -> Result<()> {
+    // The initial listing is reported as additions so consumers can build
+    // their state from the event stream alone.
+    let mut previous = Snapshot::default();
+    loop {
+        let current = Snapshot::new(collect_listing(args, config, plugin_manager)?);
+        let mut writer = NdjsonWriter::new(args.git_format);
+        for (event, entry) in previous.changes(&current) {
+            writer.write_event(event, entry)?;
+        }
+        writer.flush()?;
+        previous = current;
+
+        if !wait_for_change(receiver, interval) {
+            return Ok(());
+        }
+    }
+}
+
+/// Blocks until a relevant change arrives, then waits `interval` so a burst
+/// of events (a build, a checkout) produces a single update. Returns false
+/// once the watcher has shut down.
+fn wait_for_change(receiver: &Receiver<notify::Result<Event>>, interval: Duration) -> bool {
+    loop {
+        let Ok(first) = receiver.recv() else {
+            return false;
+        };
+        let mut relevant = is_relevant(&first);
+        thread::sleep(interval);
+        while let Ok(event) = receiver.try_recv() {
+            relevant |= is_relevant(&event);
+        }
+        if relevant {
+            return true;
+        }
+    }
+}
+
+/// Ignores reads and changes inside `.git`, which the git view itself causes
+/// when it refreshes the index.
+fn is_relevant(event: &notify::Result<Event>) -> bool {
+    let Ok(event) = event else {
+        return true;
+    };
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+    event.paths.is_empty()
+        || event.paths.iter().any(|path| {
+            !path
+                .components()
+                .any(|component| component == Component::Normal(OsStr::new(".git")))
+        })
+}
+
+/// Listing state used to derive NDJSON change events, keyed by operand and
+/// path.
+#[derive(Default)]
+struct Snapshot {
+    entries: BTreeMap<(String, String), DecoratedEntry>,
+}
+
+impl Snapshot {
+    fn new(entries: Vec<DecoratedEntry>) -> Self {
+        let entries = entries
+            .into_iter()
+            .map(|entry| {
+                let operand = entry
+                    .custom_fields
+                    .get(crate::formatter::serializable::OPERAND_FIELD)
+                    .cloned()
+                    .unwrap_or_default();
+                ((operand, entry.path.clone()), entry)
+            })
+            .collect();
+        Self { entries }
+    }
+
+    fn changes<'a>(&'a self, current: &'a Snapshot) -> Vec<(&'static str, &'a DecoratedEntry)> {
+        let mut changes = Vec::new();
+        for (key, entry) in &self.entries {
+            if !current.entries.contains_key(key) {
+                changes.push(("removed", entry));
+            }
+        }
+        for (key, entry) in &current.entries {
+            match self.entries.get(key) {
+                None => changes.push(("added", entry)),
+                Some(old) if is_modified(old, entry) => changes.push(("modified", entry)),
+                Some(_) => {}
+            }
+        }
+        changes
+    }
+}
+
+fn is_modified(old: &DecoratedEntry, new: &DecoratedEntry) -> bool {
+    match (&old.metadata, &new.metadata) {
+        (Some(old), Some(new)) => {
+            old.size != new.size
+                || old.modified != new.modified
+                || old.permissions != new.permissions
+                || old.uid != new.uid
+                || old.gid != new.gid
+        }
+        (old, new) => old.is_some() != new.is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lla_plugin_interface::proto::EntryMetadata;
+
+    fn entry(path: &str, size: u64) -> DecoratedEntry {
+        DecoratedEntry {
+            path: path.to_string(),
+            metadata: Some(EntryMetadata {
+                size,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_added_removed_and_modified_entries() {
+        let before = Snapshot::new(vec![entry("a", 1), entry("b", 1), entry("c", 1)]);
+        let after = Snapshot::new(vec![entry("a", 1), entry("b", 2), entry("d", 1)]);
+
+        let changes: Vec<_> = before
+            .changes(&after)
+            .into_iter()
+            .map(|(event, entry)| (event, entry.path.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![("removed", "c"), ("modified", "b"), ("added", "d")]
+        );
+    }
+
+    #[test]
+    fn ignores_git_internals_and_reads() {
+        let git = Event::new(EventKind::Any).add_path(".git/index".into());
+        assert!(!is_relevant(&Ok(git)));
+
+        let read = Event::new(EventKind::Access(notify::event::AccessKind::Any))
+            .add_path("src/main.rs".into());
+        assert!(!is_relevant(&Ok(read)));
+
+        let write = Event::new(EventKind::Any).add_path("src/main.rs".into());
+        assert!(is_relevant(&Ok(write)));
+    }
+}
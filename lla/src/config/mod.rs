@@ -1,5 +1,7 @@
 use crate::commands::args::ConfigAction;
 use crate::error::{ConfigErrorKind, LlaError, Result};
+use crate::formatter::template::Template;
+use crate::sorter::{is_valid_sort_spec, Collation, SortKey, SORT_KEYS};
 use crate::theme::{load_theme, Theme};
 use chrono::format::{Item, StrftimeItems};
 use colored::*;
@@ -12,6 +14,10 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use toml::Value as TomlValue;
 
+mod preset;
+
+pub use preset::ResolvedPreset;
+
 pub const DEFAULT_LONG_DATE_FORMAT: &str = "%b %d %H:%M";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -87,6 +93,10 @@ pub struct FormatterConfig {
     pub table: TableFormatterConfig,
     #[serde(default)]
     pub sizemap: SizeMapConfig,
+    /// Named `--format-template` line formats, selected with `--format <name>`
+    /// or `default_format`.
+    #[serde(default)]
+    pub templates: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -198,20 +208,29 @@ pub struct ListerConfig {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SortConfig {
+    /// Sort keys used instead of `default_sort`, each breaking the ties of
+    /// the one before; `-` flips a key's direction.
+    #[serde(default)]
+    pub keys: Vec<String>,
     #[serde(default)]
     pub dirs_first: bool,
     #[serde(default)]
     pub case_sensitive: bool,
     #[serde(default)]
     pub natural: bool,
+    /// How names are compared: `bytes` or `unicode`.
+    #[serde(default = "default_sort_collation")]
+    pub collation: String,
 }
 
 impl Default for SortConfig {
     fn default() -> Self {
         Self {
+            keys: Vec::new(),
             dirs_first: false,
             case_sensitive: false,
             natural: true,
+            collation: default_sort_collation(),
         }
     }
 }
@@ -231,10 +250,28 @@ pub struct FilterConfig {
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct FilterPreset {
     pub description: Option<String>,
+    /// Presets whose settings this one builds on, applied in order.
+    #[serde(default)]
+    pub extends: Vec<String>,
+    /// Parameter defaults, substituted for `${name}` in the other fields.
+    #[serde(default)]
+    pub params: HashMap<String, String>,
     pub filter: Option<String>,
     pub size: Option<String>,
     pub modified: Option<String>,
     pub created: Option<String>,
+    pub accessed: Option<String>,
+    /// A `--where` expression, combined with any given on the command line.
+    #[serde(default, rename = "where")]
+    pub where_expr: Option<String>,
+    pub owner: Option<String>,
+    pub group: Option<String>,
+    /// A `--perm` mode, combined with any given on the command line.
+    pub perm: Option<String>,
+    #[serde(default)]
+    pub setuid: bool,
+    #[serde(default)]
+    pub world_writable: bool,
     #[serde(default)]
     pub refine: Vec<String>,
 }
@@ -257,6 +294,9 @@ pub struct Config {
     pub show_icons: bool,
     #[serde(default)]
     pub include_dirs: bool,
+    /// Keep recursive walks on the file system they start on.
+    #[serde(default)]
+    pub one_file_system: bool,
     #[serde(default)]
     pub sort: SortConfig,
     #[serde(default)]
@@ -317,6 +357,10 @@ fn default_permission_format() -> String {
     "symbolic".to_string()
 }
 
+fn default_sort_collation() -> String {
+    "bytes".to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShortcutCommand {
     pub plugin_name: String,
@@ -418,6 +462,9 @@ impl Config {
         };
 
         let format_string = |value: &str| TomlValue::String(value.to_string()).to_string();
+        let string_array = |values: &[String]| {
+            TomlValue::Array(values.iter().cloned().map(TomlValue::String).collect()).to_string()
+        };
         let long_columns = TomlValue::Array(
             self.formatters
                 .long
@@ -444,7 +491,13 @@ impl Config {
 # Possible values:
 #   - "name": Sort alphabetically by filename (default)
 #   - "size": Sort by file size, largest first
-#   - "date": Sort by modification time, newest first
+#   - "date": Sort by modification time, oldest first ("modified" is an alias)
+#   - "created", "accessed": Sort by creation or access time, oldest first
+#   - "extension": Sort by file extension
+#   - "kind": Sort by file type, directories first
+#   - "owner", "group": Sort by owning user or group name
+#   - "inode", "links": Sort by inode number or hard-link count
+#   - "version": Sort version-numbered names, so v1.9.2 comes before v1.10.0
 default_sort = "{}"
 
 # Default format for displaying files
@@ -458,6 +511,7 @@ default_sort = "{}"
 #   - "timeline": Group files by time periods
 #   - "sizemap": Visual representation of file sizes
 #   - "table": Structured data display
+#   - the name of a template saved under [formatters.templates]
 default_format = "{}"
 
 # Whether to show icons by default
@@ -471,6 +525,12 @@ show_icons = {}
 # Default: false
 include_dirs = {}
 
+# Whether recursive walks stay on the file system they start on
+# When true, recursive, tree, fuzzy and directory-size walks do not descend
+# into mount points such as /proc, network shares or bind mounts
+# Default: false
+one_file_system = {}
+
 # Format for displaying file permissions
 # Possible values:
 #   - "symbolic": Traditional Unix-style (e.g., -rw-r--r--)
@@ -517,6 +577,12 @@ default_depth = {}
 
 # Sorting configuration
 [sort]
+# Sort keys used instead of default_sort, each breaking ties left by the one
+# before it. Prefix a key with "-" to flip its direction.
+# Example: ["size", "-date", "name"]
+# Default: [] (use default_sort)
+keys = {}
+
 # List directories before files
 # Default: false
 dirs_first = {}
@@ -529,6 +595,13 @@ case_sensitive = {}
 # Default: true
 natural = {}
 
+# How names are compared
+# Possible values:
+#   - "bytes": By code point, so "Éclair" sorts after "zebra"
+#   - "unicode": Normalized, with accents and case only breaking ties
+# Default: "bytes"
+collation = "{}"
+
 # Filtering configuration
 [filter]
 # Enable case-sensitive filtering by default
@@ -550,6 +623,16 @@ respect_gitignore = {}
 # filter = "glob:*.{{rs,toml}}"
 # size = "<2M"
 # modified = "<30d"
+# where = 'not path ~ "target/"'
+# owner = "alice"
+# perm = "-g+w"
+#
+# Presets can build on others and take parameters, used as `${{name}}` and
+# given with --preset stale_sources:days=90
+# [filter.presets.stale_sources]
+# extends = ["rust_sources"]
+# params = {{ days = "30" }}
+# modified = "<${{days}}d"
 
 # Formatter-specific configurations
 [formatters.tree]
@@ -594,6 +677,14 @@ columns = {}
 # Columns rendered in table view (same keys as long view; include plugin fields via field:<name>)
 columns = {}
 
+# Named line templates, used with --format <name> or as default_format
+# Placeholders: {{name}}, {{path}}, {{icon}}, {{size}}, {{modified}}, {{permissions}}, any
+# long-view column key or field:<name>, and {{plugins}}. Add :[<>^][width][style]
+# to align and pad; sizes take h, dates a strftime format or relative.
+# {{?key}}...{{/}} renders only when the key has a value, {{!key}}...{{/}} only when not.
+# [formatters.templates]
+# compact = "{{icon}} {{name:<40}} {{size:>8h}} {{modified:%Y-%m-%d}}"
+
 # Lister-specific configurations
 [listers.recursive]
 # Maximum number of entries to process in recursive listing
@@ -621,6 +712,7 @@ editor = {}"#,
             self.default_format,
             self.show_icons,
             self.include_dirs,
+            self.one_file_system,
             self.permission_format,
             self.theme,
             serde_json::to_string(&self.enabled_plugins).unwrap_or_else(|_| "[]".to_string()),
@@ -648,9 +740,11 @@ editor = {}"#,
                 Some(depth) => depth.to_string(),
                 None => "null".to_string(),
             },
+            string_array(&self.sort.keys),
             self.sort.dirs_first,
             self.sort.case_sensitive,
             self.sort.natural,
+            self.sort.collation,
             self.filter.case_sensitive,
             self.filter.no_dotfiles,
             self.filter.respect_gitignore,
@@ -673,6 +767,14 @@ editor = {}"#,
                 .unwrap_or_else(|| TomlValue::String(String::new()).to_string()),
         );
 
+        if !self.formatters.templates.is_empty() {
+            content.push_str("\n\n# Saved format templates\n");
+            content.push_str("[formatters.templates]\n");
+            for (name, source) in &self.formatters.templates {
+                content.push_str(&format!("{} = {}\n", name, format_string(source)));
+            }
+        }
+
         if !self.filter.presets.is_empty() {
             content.push('\n');
             content.push_str("# Saved filter presets\n");
@@ -681,6 +783,19 @@ editor = {}"#,
                 if let Some(desc) = &preset.description {
                     content.push_str(&format!("description = {}\n", format_string(desc)));
                 }
+                if !preset.extends.is_empty() {
+                    content.push_str(&format!("extends = {}\n", string_array(&preset.extends)));
+                }
+                if !preset.params.is_empty() {
+                    let mut params: Vec<_> = preset.params.iter().collect();
+                    params.sort();
+                    let params = params
+                        .into_iter()
+                        .map(|(key, value)| format!("{} = {}", key, format_string(value)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    content.push_str(&format!("params = {{ {} }}\n", params));
+                }
                 if let Some(pattern) = &preset.filter {
                     content.push_str(&format!("filter = {}\n", format_string(pattern)));
                 }
@@ -693,16 +808,29 @@ editor = {}"#,
                 if let Some(created) = &preset.created {
                     content.push_str(&format!("created = {}\n", format_string(created)));
                 }
+                if let Some(accessed) = &preset.accessed {
+                    content.push_str(&format!("accessed = {}\n", format_string(accessed)));
+                }
+                if let Some(expr) = &preset.where_expr {
+                    content.push_str(&format!("where = {}\n", format_string(expr)));
+                }
+                if let Some(owner) = &preset.owner {
+                    content.push_str(&format!("owner = {}\n", format_string(owner)));
+                }
+                if let Some(group) = &preset.group {
+                    content.push_str(&format!("group = {}\n", format_string(group)));
+                }
+                if let Some(perm) = &preset.perm {
+                    content.push_str(&format!("perm = {}\n", format_string(perm)));
+                }
+                if preset.setuid {
+                    content.push_str("setuid = true\n");
+                }
+                if preset.world_writable {
+                    content.push_str("world_writable = true\n");
+                }
                 if !preset.refine.is_empty() {
-                    let arr = TomlValue::Array(
-                        preset
-                            .refine
-                            .iter()
-                            .map(|v| TomlValue::String(v.clone()))
-                            .collect(),
-                    )
-                    .to_string();
-                    content.push_str(&format!("refine = {}\n", arr));
+                    content.push_str(&format!("refine = {}\n", string_array(&preset.refine)));
                 }
                 content.push('\n');
             }
@@ -861,6 +989,21 @@ editor = {}"#,
         self.shortcuts.get(name)
     }
 
+    /// The name collation listings use unless `--sort-collation` is given.
+    pub fn sort_collation(&self) -> Collation {
+        Collation::parse(&self.sort.collation).unwrap_or_default()
+    }
+
+    /// The sort list listings use unless `--sort` is given: `[sort] keys`
+    /// when set, otherwise `default_sort`.
+    pub fn default_sort_keys(&self) -> String {
+        if self.sort.keys.is_empty() {
+            self.default_sort.clone()
+        } else {
+            self.sort.keys.join(",")
+        }
+    }
+
     pub fn resolve_plugin_alias(&self, name: &str) -> String {
         self.plugin_aliases
             .get(name)
@@ -869,24 +1012,51 @@ editor = {}"#,
     }
 
     pub fn validate(&self) -> Result<()> {
-        if !["name", "size", "date"].contains(&self.default_sort.as_str()) {
+        if !is_valid_sort_spec(&self.default_sort) {
             return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
                 "default_sort".to_string(),
                 format!(
-                    "Invalid sort value: {}. Must be one of: name, size, date",
-                    self.default_sort
+                    "Invalid sort value: {}. Must be one of: {}, field:<name>",
+                    self.default_sort,
+                    SORT_KEYS.join(", ")
+                ),
+            )));
+        }
+        if let Some(key) = self.sort.keys.iter().find(|key| !is_valid_sort_spec(key)) {
+            return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                "sort.keys".to_string(),
+                format!(
+                    "Invalid sort key: {}. Must be one of: {}, field:<name>, optionally prefixed with -",
+                    key,
+                    SORT_KEYS.join(", ")
+                ),
+            )));
+        }
+        if Collation::parse(&self.sort.collation).is_none() {
+            return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                "sort.collation".to_string(),
+                format!(
+                    "Invalid collation: {}. Must be one of: {}",
+                    self.sort.collation,
+                    Collation::NAMES.join(", ")
                 ),
             )));
         }
 
+        for (name, source) in &self.formatters.templates {
+            validate_format_template(&format!("formatters.templates.{}", name), source)?;
+        }
+
         let valid_formats = [
             "default", "long", "tree", "grid", "git", "timeline", "sizemap", "table", "fuzzy",
         ];
-        if !valid_formats.contains(&self.default_format.as_str()) {
+        if !valid_formats.contains(&self.default_format.as_str())
+            && !self.formatters.templates.contains_key(&self.default_format)
+        {
             return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
                 "default_format".to_string(),
                 format!(
-                    "Invalid format value: {}. Must be one of: {}",
+                    "Invalid format value: {}. Must be one of: {} or a template name",
                     self.default_format,
                     valid_formats.join(", ")
                 ),
@@ -1057,10 +1227,10 @@ editor = {}"#,
                 self.exclude_paths = resolved;
             }
             ["default_sort"] => {
-                if !["name", "size", "date"].contains(&value) {
+                if !is_valid_sort_spec(value) {
                     return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
                         key.to_string(),
-                        "must be one of: name, size, date".to_string(),
+                        format!("must be one of: {}, field:<name>", SORT_KEYS.join(", ")),
                     )));
                 }
                 self.default_sort = value.to_string();
@@ -1070,10 +1240,14 @@ editor = {}"#,
                     "default", "long", "tree", "grid", "git", "timeline", "sizemap", "table",
                     "fuzzy",
                 ];
-                if !valid_formats.contains(&value) {
+                if !valid_formats.contains(&value) && !self.formatters.templates.contains_key(value)
+                {
                     return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
                         key.to_string(),
-                        format!("must be one of: {}", valid_formats.join(", ")),
+                        format!(
+                            "must be one of: {} or a template name",
+                            valid_formats.join(", ")
+                        ),
                     )));
                 }
                 self.default_format = value.to_string();
@@ -1094,6 +1268,14 @@ editor = {}"#,
                     ))
                 })?;
             }
+            ["one_file_system"] => {
+                self.one_file_system = value.parse().map_err(|_| {
+                    LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        "must be true or false".to_string(),
+                    ))
+                })?;
+            }
             ["default_depth"] => {
                 if value.to_lowercase() == "null" {
                     self.default_depth = None;
@@ -1113,6 +1295,23 @@ editor = {}"#,
                     self.default_depth = Some(depth);
                 }
             }
+            ["sort", "keys"] => {
+                let keys: Vec<String> = value
+                    .split(',')
+                    .map(|key| key.trim().to_string())
+                    .filter(|key| !key.is_empty())
+                    .collect();
+                if !keys.iter().all(|key| is_valid_sort_spec(key)) {
+                    return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        format!(
+                            "must be a comma-separated list of {}, field:<name>, each optionally prefixed with -",
+                            SORT_KEYS.join(", ")
+                        ),
+                    )));
+                }
+                self.sort.keys = keys;
+            }
             ["sort", "dirs_first"] => {
                 self.sort.dirs_first = value.parse().map_err(|_| {
                     LlaError::Config(ConfigErrorKind::InvalidValue(
@@ -1137,6 +1336,15 @@ editor = {}"#,
                     ))
                 })?;
             }
+            ["sort", "collation"] => {
+                if Collation::parse(value).is_none() {
+                    return Err(LlaError::Config(ConfigErrorKind::InvalidValue(
+                        key.to_string(),
+                        format!("must be one of: {}", Collation::NAMES.join(", ")),
+                    )));
+                }
+                self.sort.collation = value.to_string();
+            }
             ["filter", "case_sensitive"] => {
                 self.filter.case_sensitive = value.parse().map_err(|_| {
                     LlaError::Config(ConfigErrorKind::InvalidValue(
@@ -1200,6 +1408,12 @@ editor = {}"#,
                 })?;
                 self.formatters.table.columns = columns;
             }
+            ["formatters", "templates", name] => {
+                validate_format_template(key, value)?;
+                self.formatters
+                    .templates
+                    .insert(name.to_string(), value.to_string());
+            }
 
             ["listers", "recursive", "max_entries"] => {
                 let max_entries = value.parse().map_err(|_| {
@@ -1280,6 +1494,7 @@ impl Default for Config {
             default_depth: Some(3),
             show_icons: false,
             include_dirs: false,
+            one_file_system: false,
             sort: SortConfig::default(),
             filter: FilterConfig::default(),
             formatters: FormatterConfig::default(),
@@ -1451,13 +1666,20 @@ fn print_config_summary(config_path: &Path, config: &Config) {
         "Include dirs",
         format_toggle(config.include_dirs, "include", "files only"),
     );
+    print_row(
+        "File systems",
+        format_toggle(config.one_file_system, "stay on one", "cross mounts"),
+    );
     print_row(
         "Depth limit",
         format_optional_limit(config.default_depth, "levels"),
     );
 
     print_section("Sorting & filters");
-    print_row("Sort order", describe_sort(&config.default_sort).cyan());
+    print_row(
+        "Sort order",
+        describe_sort(&config.default_sort_keys()).cyan(),
+    );
     print_row(
         "Dirs first",
         format_toggle(config.sort.dirs_first, "yes", "no"),
@@ -1470,6 +1692,7 @@ fn print_config_summary(config_path: &Path, config: &Config) {
         "Natural sort",
         format_toggle(config.sort.natural, "natural", "lexical"),
     );
+    print_row("Collation", config.sort.collation.as_str().cyan());
     print_row(
         "Filter casing",
         format_toggle(
@@ -1558,6 +1781,16 @@ fn print_config_summary(config_path: &Path, config: &Config) {
     );
 }
 
+fn validate_format_template(key: &str, source: &str) -> Result<()> {
+    Template::parse(source).map(|_| ()).map_err(|err| {
+        let message = match err {
+            LlaError::Parse(message) => message,
+            other => other.to_string(),
+        };
+        LlaError::Config(ConfigErrorKind::InvalidValue(key.to_string(), message))
+    })
+}
+
 fn describe_format(format: &str) -> &str {
     match format {
         "tree" => "Tree (hierarchical)",
@@ -1568,16 +1801,41 @@ fn describe_format(format: &str) -> &str {
         "git" => "Git status",
         "sizemap" => "Size map",
         "fuzzy" => "Fuzzy finder",
-        _ => "Recommended default",
+        "default" => "Recommended default",
+        _ => "Template",
     }
 }
 
-fn describe_sort(sort: &str) -> &str {
-    match sort {
-        "size" => "Size (small → large)",
-        "date" => "Date (newest first)",
-        _ => "Name (A→Z)",
-    }
+fn describe_sort(sort: &str) -> String {
+    SortKey::parse_list(sort)
+        .iter()
+        .map(|key| {
+            let description = match key.name.strip_prefix("field:") {
+                Some(field) => format!("Plugin field {}", field),
+                None => match key.name.as_str() {
+                    "size" => "Size (small → large)",
+                    "date" => "Date (oldest first)",
+                    "created" => "Created (oldest first)",
+                    "accessed" => "Accessed (oldest first)",
+                    "extension" => "Extension (A→Z)",
+                    "kind" => "Kind (directories first)",
+                    "owner" => "Owner (A→Z)",
+                    "group" => "Group (A→Z)",
+                    "inode" => "Inode (low → high)",
+                    "links" => "Hard links (few → many)",
+                    "version" => "Version (oldest first)",
+                    _ => "Name (A→Z)",
+                }
+                .to_string(),
+            };
+            if key.reverse {
+                format!("{} reversed", description)
+            } else {
+                description
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", then ")
 }
 
 fn print_section(title: &str) {
@@ -0,0 +1,332 @@
+use super::{Config, FilterPreset};
+use crate::error::{LlaError, Result};
+use std::collections::BTreeMap;
+
+/// A preset with everything it extends folded in and its parameters
+/// substituted, ready to apply to a listing.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResolvedPreset {
+    pub name: String,
+    pub description: Option<String>,
+    /// Every preset that contributed, parents before children.
+    pub chain: Vec<String>,
+    pub params: BTreeMap<String, String>,
+    pub filters: Vec<String>,
+    pub size: Option<String>,
+    pub modified: Option<String>,
+    pub created: Option<String>,
+    pub accessed: Option<String>,
+    pub where_exprs: Vec<String>,
+    pub owner: Option<String>,
+    pub group: Option<String>,
+    pub perms: Vec<String>,
+    pub setuid: bool,
+    pub world_writable: bool,
+    pub refine: Vec<String>,
+}
+
+impl ResolvedPreset {
+    /// The command-line flags this preset stands for.
+    pub fn to_flags(&self) -> Vec<(&'static str, String)> {
+        let mut flags = Vec::new();
+        flags.extend(self.filters.iter().map(|f| ("--filter", f.clone())));
+        let scalars = [
+            ("--size", &self.size),
+            ("--modified", &self.modified),
+            ("--created", &self.created),
+            ("--accessed", &self.accessed),
+            ("--owner", &self.owner),
+            ("--group", &self.group),
+        ];
+        for (flag, value) in scalars {
+            flags.extend(value.iter().map(|v| (flag, v.clone())));
+        }
+        flags.extend(self.where_exprs.iter().map(|w| ("--where", w.clone())));
+        flags.extend(self.perms.iter().map(|p| ("--perm", p.clone())));
+        if self.setuid {
+            flags.push(("--setuid", String::new()));
+        }
+        if self.world_writable {
+            flags.push(("--world-writable", String::new()));
+        }
+        flags.extend(self.refine.iter().map(|r| ("--refine", r.clone())));
+        flags
+    }
+
+    /// Folds `preset` on top of what its parents contributed. Filters,
+    /// `where`, `perm` and `refine` add up; other values replace the
+    /// parents'.
+    fn apply(&mut self, preset: &FilterPreset) {
+        self.filters.extend(preset.filter.clone());
+        for (value, own) in [
+            (&mut self.size, &preset.size),
+            (&mut self.modified, &preset.modified),
+            (&mut self.created, &preset.created),
+            (&mut self.accessed, &preset.accessed),
+            (&mut self.owner, &preset.owner),
+            (&mut self.group, &preset.group),
+        ] {
+            if own.is_some() {
+                value.clone_from(own);
+            }
+        }
+        self.where_exprs.extend(preset.where_expr.clone());
+        self.perms.extend(preset.perm.clone());
+        self.setuid |= preset.setuid;
+        self.world_writable |= preset.world_writable;
+        self.refine.extend(preset.refine.iter().cloned());
+        for (key, value) in &preset.params {
+            self.params.insert(key.clone(), value.clone());
+        }
+    }
+
+    fn substitute(&mut self, given: &BTreeMap<String, String>) -> Result<()> {
+        for (key, value) in given {
+            if !self.params.contains_key(key) && !self.mentions(key) {
+                return Err(LlaError::Filter(format!(
+                    "Preset '{}' has no parameter '{}'",
+                    self.name, key
+                )));
+            }
+            self.params.insert(key.clone(), value.clone());
+        }
+
+        let name = self.name.clone();
+        let params = self.params.clone();
+        let fill = |text: &mut String| -> Result<()> {
+            *text = substitute(text, &params).map_err(|missing| {
+                LlaError::Filter(format!(
+                    "Preset '{}' needs a value for '{}' (use --preset {}:{}=<value>)",
+                    name, missing, name, missing
+                ))
+            })?;
+            Ok(())
+        };
+        for text in self
+            .filters
+            .iter_mut()
+            .chain(self.where_exprs.iter_mut())
+            .chain(self.perms.iter_mut())
+            .chain(self.refine.iter_mut())
+        {
+            fill(text)?;
+        }
+        for text in [
+            &mut self.size,
+            &mut self.modified,
+            &mut self.created,
+            &mut self.accessed,
+            &mut self.owner,
+            &mut self.group,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            fill(text)?;
+        }
+        Ok(())
+    }
+
+    fn mentions(&self, key: &str) -> bool {
+        let placeholder = format!("${{{}}}", key);
+        self.to_flags()
+            .iter()
+            .any(|(_, value)| value.contains(&placeholder))
+    }
+}
+
+impl Config {
+    /// Resolves a `--preset` value: a preset name, optionally followed by
+    /// parameters as in `stale:days=90,min=1M`.
+    pub fn resolve_preset(&self, spec: &str) -> Result<ResolvedPreset> {
+        let (name, given) = parse_preset_spec(spec)?;
+        let mut resolved = ResolvedPreset {
+            name: name.to_string(),
+            ..ResolvedPreset::default()
+        };
+        self.fold_preset(name, &mut Vec::new(), &mut resolved)?;
+        resolved.description = self.filter.presets[name].description.clone();
+        resolved.substitute(&given)?;
+        Ok(resolved)
+    }
+
+    fn fold_preset(
+        &self,
+        name: &str,
+        stack: &mut Vec<String>,
+        resolved: &mut ResolvedPreset,
+    ) -> Result<()> {
+        if stack.iter().any(|entry| entry == name) {
+            stack.push(name.to_string());
+            return Err(LlaError::Filter(format!(
+                "Preset '{}' extends itself: {}",
+                name,
+                stack.join(" -> ")
+            )));
+        }
+        // A preset reached twice through different parents applies once.
+        if resolved.chain.iter().any(|entry| entry == name) {
+            return Ok(());
+        }
+        let preset = self
+            .filter
+            .presets
+            .get(name)
+            .ok_or_else(|| LlaError::Filter(format!("Unknown preset '{}'", name)))?;
+
+        stack.push(name.to_string());
+        for parent in &preset.extends {
+            self.fold_preset(parent, stack, resolved)?;
+        }
+        stack.pop();
+
+        resolved.apply(preset);
+        resolved.chain.push(name.to_string());
+        Ok(())
+    }
+}
+
+fn parse_preset_spec(spec: &str) -> Result<(&str, BTreeMap<String, String>)> {
+    let (name, params) = match spec.split_once(':') {
+        Some((name, params)) => (name.trim(), params),
+        None => return Ok((spec.trim(), BTreeMap::new())),
+    };
+    let mut given = BTreeMap::new();
+    for param in params.split(',').filter(|p| !p.trim().is_empty()) {
+        let (key, value) = param.split_once('=').ok_or_else(|| {
+            LlaError::Filter(format!(
+                "Invalid preset parameter '{}' in '{}'. Use name:key=value[,key=value...]",
+                param, spec
+            ))
+        })?;
+        given.insert(key.trim().to_string(), value.trim().to_string());
+    }
+    Ok((name, given))
+}
+
+/// Replaces `${key}` placeholders, returning the first key without a value.
+/// Anything else, including glob braces, is left alone.
+fn substitute(
+    text: &str,
+    params: &BTreeMap<String, String>,
+) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}').filter(|&end| {
+            let key = &after[..end];
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        });
+        match end {
+            Some(end) => {
+                let key = &after[..end];
+                out.push_str(params.get(key).ok_or_else(|| key.to_string())?);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str("${");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(presets: &[(&str, FilterPreset)]) -> Config {
+        let mut config = Config::default();
+        config.filter.presets = presets
+            .iter()
+            .map(|(name, preset)| (name.to_string(), preset.clone()))
+            .collect::<HashMap<_, _>>();
+        config
+    }
+
+    #[test]
+    fn presets_extend_and_take_parameters() {
+        let config = config(&[
+            (
+                "source",
+                FilterPreset {
+                    filter: Some("glob:*.{rs,toml}".to_string()),
+                    size: Some("<2M".to_string()),
+                    where_expr: Some("not path ~ \"target/\"".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "stale",
+                FilterPreset {
+                    extends: vec!["source".to_string()],
+                    modified: Some("<${days}d".to_string()),
+                    size: Some("<${max}".to_string()),
+                    params: HashMap::from([
+                        ("days".to_string(), "30".to_string()),
+                        ("max".to_string(), "1M".to_string()),
+                    ]),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let stale = config.resolve_preset("stale").unwrap();
+        assert_eq!(stale.chain, ["source", "stale"]);
+        assert_eq!(stale.filters, ["glob:*.{rs,toml}"]);
+        assert_eq!(stale.modified.as_deref(), Some("<30d"));
+        assert_eq!(stale.size.as_deref(), Some("<1M"));
+        assert_eq!(stale.where_exprs.len(), 1);
+
+        let older = config.resolve_preset("stale:days=90").unwrap();
+        assert_eq!(older.modified.as_deref(), Some("<90d"));
+        assert_eq!(older.params["days"], "90");
+
+        assert!(config.resolve_preset("stale:weeks=2").is_err());
+        assert!(config.resolve_preset("stale:days").is_err());
+        assert!(config.resolve_preset("missing").is_err());
+    }
+
+    #[test]
+    fn cycles_and_missing_parameters_are_errors() {
+        let looped = config(&[
+            (
+                "a",
+                FilterPreset {
+                    extends: vec!["b".to_string()],
+                    ..Default::default()
+                },
+            ),
+            (
+                "b",
+                FilterPreset {
+                    extends: vec!["a".to_string()],
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let error = looped.resolve_preset("a").unwrap_err().to_string();
+        assert!(error.contains("a -> b -> a"), "{}", error);
+
+        let needs = config(&[(
+            "owned",
+            FilterPreset {
+                owner: Some("${user}".to_string()),
+                ..Default::default()
+            },
+        )]);
+        assert!(needs.resolve_preset("owned").is_err());
+        assert_eq!(
+            needs.resolve_preset("owned:user=alice").unwrap().owner,
+            Some("alice".to_string())
+        );
+    }
+}
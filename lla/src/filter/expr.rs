@@ -0,0 +1,665 @@
+use super::{
+    parse_size_range, parse_time_range, FilterOperation, GlobFilter, NumericRange, RegexFilter,
+    TimeRange,
+};
+use crate::error::{LlaError, Result};
+use crate::utils::fs_metadata::entry_kind;
+use lla_plugin_interface::proto::{EntryMetadata, FileKind};
+use std::fmt::Display;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// A boolean filter over names, metadata and entry types, as given to
+/// `--where`:
+///
+/// ```text
+/// ext in (rs, toml) and size > 10M and not (path ~ "target/" or modified < 30d)
+/// ```
+///
+/// Size and time values use the `--size` and `--modified` syntax, and `~`
+/// takes the same `glob:` and `regex:` prefixes as `--filter`.
+#[derive(Clone)]
+pub struct WhereExpr {
+    source: String,
+    root: Node,
+}
+
+impl WhereExpr {
+    /// Parses an expression. Relative times such as `30d` are resolved
+    /// against `now`. Errors point at the offending token with a caret.
+    pub fn parse(source: &str, now: SystemTime) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            source,
+            tokens,
+            position: 0,
+            now,
+        };
+        if parser.peek().kind == TokenKind::End {
+            return Err(parser.error_at(0, "expression is empty"));
+        }
+        let root = parser.parse_or()?;
+        let trailing = parser.peek();
+        if trailing.kind != TokenKind::End {
+            return Err(parser.error_at(trailing.column, "expected 'and', 'or' or ')'"));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn matches(&self, path: &Path, metadata: &EntryMetadata) -> bool {
+        self.root.matches(path, metadata)
+    }
+}
+
+#[derive(Clone)]
+enum Node {
+    Predicate(Predicate),
+    Compound(FilterOperation, Vec<Node>),
+}
+
+impl Node {
+    fn compound(operation: FilterOperation, mut nodes: Vec<Node>) -> Node {
+        if nodes.len() == 1 && !matches!(operation, FilterOperation::Not) {
+            nodes.remove(0)
+        } else {
+            Node::Compound(operation, nodes)
+        }
+    }
+
+    fn negated(self) -> Node {
+        Node::Compound(FilterOperation::Not, vec![self])
+    }
+
+    fn matches(&self, path: &Path, metadata: &EntryMetadata) -> bool {
+        match self {
+            Node::Predicate(predicate) => predicate.matches(path, metadata),
+            Node::Compound(FilterOperation::And, nodes) => {
+                nodes.iter().all(|node| node.matches(path, metadata))
+            }
+            Node::Compound(FilterOperation::Or, nodes) => {
+                nodes.iter().any(|node| node.matches(path, metadata))
+            }
+            Node::Compound(FilterOperation::Not, nodes) => {
+                !nodes.iter().all(|node| node.matches(path, metadata))
+            }
+            Node::Compound(FilterOperation::Xor, nodes) => {
+                nodes
+                    .iter()
+                    .filter(|node| node.matches(path, metadata))
+                    .count()
+                    % 2
+                    == 1
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Predicate {
+    Name(TextMatch),
+    Path(TextMatch),
+    Extension(Vec<String>),
+    Size(NumericRange),
+    Modified(TimeRange),
+    Created(TimeRange),
+    Accessed(TimeRange),
+    Kind(Vec<FileKind>),
+}
+
+impl Predicate {
+    fn matches(&self, path: &Path, metadata: &EntryMetadata) -> bool {
+        match self {
+            Predicate::Name(text) => {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_else(|| path.to_string_lossy());
+                text.matches(&name)
+            }
+            Predicate::Path(text) => text.matches(&path.to_string_lossy()),
+            Predicate::Extension(extensions) => path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .map(|ext| extensions.contains(&ext))
+                .unwrap_or(false),
+            Predicate::Size(range) => range.matches(metadata.size),
+            // Entries without a timestamp never match, as with --modified.
+            Predicate::Modified(range) => {
+                metadata.modified != 0 && range.matches_epoch_secs(metadata.modified)
+            }
+            Predicate::Created(range) => {
+                metadata.created != 0 && range.matches_epoch_secs(metadata.created)
+            }
+            Predicate::Accessed(range) => {
+                metadata.accessed != 0 && range.matches_epoch_secs(metadata.accessed)
+            }
+            Predicate::Kind(kinds) => kinds.contains(&entry_kind(metadata)),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum TextMatch {
+    Equals(String),
+    Contains(String),
+    Glob(GlobFilter),
+    Regex(RegexFilter),
+}
+
+impl TextMatch {
+    fn parse(value: &str) -> Result<Self> {
+        if let Some(pattern) = value.strip_prefix("glob:") {
+            Ok(TextMatch::Glob(GlobFilter::parse(pattern)?))
+        } else if let Some(pattern) = value.strip_prefix("regex:") {
+            Ok(TextMatch::Regex(RegexFilter::parse(pattern)?))
+        } else {
+            Ok(TextMatch::Contains(value.to_string()))
+        }
+    }
+
+    fn matches(&self, text: &str) -> bool {
+        match self {
+            TextMatch::Equals(expected) => text == expected,
+            TextMatch::Contains(needle) => text.contains(needle.as_str()),
+            TextMatch::Glob(glob) => glob.matches(text),
+            TextMatch::Regex(regex) => regex.matches(text),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Path,
+    Extension,
+    Size,
+    Modified,
+    Created,
+    Accessed,
+    Kind,
+}
+
+impl Field {
+    fn parse(word: &str) -> Option<Self> {
+        match word.to_ascii_lowercase().as_str() {
+            "name" => Some(Field::Name),
+            "path" => Some(Field::Path),
+            "ext" | "extension" => Some(Field::Extension),
+            "size" => Some(Field::Size),
+            "modified" | "mtime" => Some(Field::Modified),
+            "created" | "ctime" => Some(Field::Created),
+            "accessed" | "atime" => Some(Field::Accessed),
+            "type" | "kind" => Some(Field::Kind),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::Path => "path",
+            Field::Extension => "ext",
+            Field::Size => "size",
+            Field::Modified => "modified",
+            Field::Created => "created",
+            Field::Accessed => "accessed",
+            Field::Kind => "type",
+        }
+    }
+
+    fn operators(self) -> &'static [&'static str] {
+        match self {
+            Field::Name | Field::Path => &["=", "!=", "~", "!~", "in"],
+            Field::Extension | Field::Kind => &["=", "!=", "in"],
+            Field::Size => &["=", "!=", "<", "<=", ">", ">="],
+            Field::Modified | Field::Created | Field::Accessed => &["<", "<=", ">", ">=", "="],
+        }
+    }
+}
+
+fn parse_kind(value: &str) -> Option<FileKind> {
+    match value.to_ascii_lowercase().as_str() {
+        "file" | "regular" => Some(FileKind::Regular),
+        "dir" | "directory" => Some(FileKind::Directory),
+        "symlink" | "link" => Some(FileKind::Symlink),
+        "fifo" | "pipe" => Some(FileKind::Fifo),
+        "socket" => Some(FileKind::Socket),
+        "block" | "block_device" => Some(FileKind::BlockDevice),
+        "char" | "char_device" => Some(FileKind::CharDevice),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TokenKind {
+    Word(String),
+    Quoted(String),
+    Operator(&'static str),
+    OpenParen,
+    CloseParen,
+    Comma,
+    End,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    /// Character offset into the source, used to place the error caret.
+    column: usize,
+}
+
+const OPERATORS: [&str; 9] = ["==", "!=", "!~", "<=", ">=", "=", "~", "<", ">"];
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let column = index;
+        if c.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        let kind = match c {
+            '(' => {
+                index += 1;
+                TokenKind::OpenParen
+            }
+            ')' => {
+                index += 1;
+                TokenKind::CloseParen
+            }
+            ',' => {
+                index += 1;
+                TokenKind::Comma
+            }
+            '"' | '\'' => {
+                let mut value = String::new();
+                index += 1;
+                loop {
+                    match chars.get(index) {
+                        None => return Err(parse_error(source, column, "unterminated string")),
+                        Some(&quote) if quote == c => break,
+                        Some('\\') if chars.get(index + 1).is_some() => {
+                            value.push(chars[index + 1]);
+                            index += 2;
+                        }
+                        Some(&other) => {
+                            value.push(other);
+                            index += 1;
+                        }
+                    }
+                }
+                index += 1;
+                TokenKind::Quoted(value)
+            }
+            _ => {
+                let rest: String = chars[index..chars.len().min(index + 2)].iter().collect();
+                if let Some(operator) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+                    index += operator.chars().count();
+                    TokenKind::Operator(if *operator == "==" { "=" } else { operator })
+                } else if c == '!' {
+                    return Err(parse_error(source, column, "expected '!=' or '!~'"));
+                } else {
+                    let start = index;
+                    while index < chars.len() && is_word_char(chars[index]) {
+                        index += 1;
+                    }
+                    TokenKind::Word(chars[start..index].iter().collect())
+                }
+            }
+        };
+        tokens.push(Token { kind, column });
+    }
+    tokens.push(Token {
+        kind: TokenKind::End,
+        column: chars.len(),
+    });
+    Ok(tokens)
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !"()\"',=!~<>".contains(c)
+}
+
+fn parse_error(source: &str, column: usize, message: impl Display) -> LlaError {
+    LlaError::Parse(format!(
+        "Invalid --where expression: {}\n  {}\n  {}^",
+        message,
+        source,
+        " ".repeat(column)
+    ))
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+    now: SystemTime,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+        if token.kind != TokenKind::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn error_at(&self, column: usize, message: impl Display) -> LlaError {
+        parse_error(self.source, column, message)
+    }
+
+    fn parse_or(&mut self) -> Result<Node> {
+        let mut nodes = vec![self.parse_xor()?];
+        while self.at_keyword("or") {
+            self.advance();
+            nodes.push(self.parse_xor()?);
+        }
+        Ok(Node::compound(FilterOperation::Or, nodes))
+    }
+
+    fn parse_xor(&mut self) -> Result<Node> {
+        let mut node = self.parse_and()?;
+        while self.at_keyword("xor") {
+            self.advance();
+            let right = self.parse_and()?;
+            node = Node::Compound(FilterOperation::Xor, vec![node, right]);
+        }
+        Ok(node)
+    }
+
+    fn parse_and(&mut self) -> Result<Node> {
+        let mut nodes = vec![self.parse_unary()?];
+        while self.at_keyword("and") {
+            self.advance();
+            nodes.push(self.parse_unary()?);
+        }
+        Ok(Node::compound(FilterOperation::And, nodes))
+    }
+
+    fn parse_unary(&mut self) -> Result<Node> {
+        if self.at_keyword("not") {
+            self.advance();
+            return Ok(self.parse_unary()?.negated());
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Node> {
+        let token = self.advance();
+        match token.kind {
+            TokenKind::OpenParen => {
+                let node = self.parse_or()?;
+                let close = self.advance();
+                if close.kind != TokenKind::CloseParen {
+                    return Err(self.error_at(close.column, "expected ')'"));
+                }
+                Ok(node)
+            }
+            TokenKind::Word(word) => match Field::parse(&word) {
+                Some(field) => self.parse_predicate(field),
+                None => Err(self.error_at(
+                    token.column,
+                    format!(
+                        "unknown field '{}' (expected name, path, ext, size, modified, created, accessed or type)",
+                        word
+                    ),
+                )),
+            },
+            _ => Err(self.error_at(
+                token.column,
+                "expected a field such as name, ext, size or modified",
+            )),
+        }
+    }
+
+    fn parse_predicate(&mut self, field: Field) -> Result<Node> {
+        let operator_token = self.advance();
+        let operator = match &operator_token.kind {
+            TokenKind::Operator(operator) => *operator,
+            TokenKind::Word(word) if word.eq_ignore_ascii_case("in") => "in",
+            _ => {
+                return Err(self.error_at(
+                    operator_token.column,
+                    format!("expected an operator after '{}'", field.name()),
+                ))
+            }
+        };
+        if !field.operators().contains(&operator) {
+            return Err(self.error_at(
+                operator_token.column,
+                format!(
+                    "'{}' cannot be used with {} (expected {})",
+                    operator,
+                    field.name(),
+                    field.operators().join(", ")
+                ),
+            ));
+        }
+
+        let values = if operator == "in" {
+            self.parse_list()?
+        } else {
+            vec![self.parse_value()?]
+        };
+        let negated = operator.starts_with('!');
+        let node = self.build_predicate(field, operator, values)?;
+        Ok(if negated { node.negated() } else { node })
+    }
+
+    fn parse_value(&mut self) -> Result<(String, usize)> {
+        let token = self.advance();
+        match token.kind {
+            TokenKind::Word(value) | TokenKind::Quoted(value) => Ok((value, token.column)),
+            _ => Err(self.error_at(token.column, "expected a value")),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<(String, usize)>> {
+        let open = self.advance();
+        if open.kind != TokenKind::OpenParen {
+            return Err(self.error_at(open.column, "expected '(' after 'in'"));
+        }
+        let mut values = vec![self.parse_value()?];
+        loop {
+            let token = self.advance();
+            match token.kind {
+                TokenKind::Comma => values.push(self.parse_value()?),
+                TokenKind::CloseParen => return Ok(values),
+                _ => return Err(self.error_at(token.column, "expected ',' or ')'")),
+            }
+        }
+    }
+
+    fn build_predicate(
+        &self,
+        field: Field,
+        operator: &str,
+        values: Vec<(String, usize)>,
+    ) -> Result<Node> {
+        let at = |column: usize| move |e: LlaError| self.error_at(column, e);
+        let predicate = match field {
+            Field::Name | Field::Path => {
+                let mut nodes = Vec::with_capacity(values.len());
+                for (value, column) in values {
+                    let text = if operator.ends_with('~') {
+                        TextMatch::parse(&value).map_err(at(column))?
+                    } else {
+                        TextMatch::Equals(value)
+                    };
+                    nodes.push(Node::Predicate(if field == Field::Name {
+                        Predicate::Name(text)
+                    } else {
+                        Predicate::Path(text)
+                    }));
+                }
+                return Ok(Node::compound(FilterOperation::Or, nodes));
+            }
+            Field::Extension => Predicate::Extension(
+                values
+                    .into_iter()
+                    .map(|(value, _)| value.trim_start_matches('.').to_lowercase())
+                    .collect(),
+            ),
+            Field::Kind => Predicate::Kind(
+                values
+                    .into_iter()
+                    .map(|(value, column)| {
+                        parse_kind(&value).ok_or_else(|| {
+                            self.error_at(
+                                column,
+                                format!(
+                                    "unknown type '{}' (expected file, dir, symlink, fifo, socket, block or char)",
+                                    value
+                                ),
+                            )
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            Field::Size => {
+                let (value, column) = &values[0];
+                let range = if matches!(operator, "=" | "!=") && value.contains("..") {
+                    parse_size_range(value)
+                } else {
+                    // `!=` is parsed as `=` and negated by the caller.
+                    parse_size_range(&format!("{}{}", operator.trim_start_matches('!'), value))
+                };
+                Predicate::Size(range.map_err(at(*column))?)
+            }
+            Field::Modified | Field::Created | Field::Accessed => {
+                let (value, column) = &values[0];
+                let expression = if operator == "=" {
+                    if !value.contains("..") {
+                        return Err(self.error_at(
+                            *column,
+                            "'=' needs a range such as 2024-01-01..2024-02-01",
+                        ));
+                    }
+                    value.clone()
+                } else {
+                    format!("{}{}", operator, value)
+                };
+                let range = parse_time_range(&expression, self.now).map_err(at(*column))?;
+                match field {
+                    Field::Modified => Predicate::Modified(range),
+                    Field::Created => Predicate::Created(range),
+                    _ => Predicate::Accessed(range),
+                }
+            }
+        };
+        Ok(Node::Predicate(predicate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_000 * DAY)
+    }
+
+    fn file(size: u64, age_days: u64) -> EntryMetadata {
+        EntryMetadata {
+            size,
+            modified: 1_000 * DAY - age_days * DAY,
+            is_file: true,
+            kind: FileKind::Regular as i32,
+            ..Default::default()
+        }
+    }
+
+    fn matches(expr: &str, path: &str, metadata: &EntryMetadata) -> bool {
+        WhereExpr::parse(expr, now())
+            .unwrap()
+            .matches(Path::new(path), metadata)
+    }
+
+    #[test]
+    fn evaluates_nested_boolean_expressions() {
+        let expr =
+            r#"ext in (rs, toml) and size > 10M and not (path ~ "target/" or modified < 30d)"#;
+        let big = 20 * 1024 * 1024;
+        assert!(matches(expr, "src/main.rs", &file(big, 1)));
+        assert!(matches(expr, "Cargo.TOML", &file(big, 1)));
+        assert!(!matches(expr, "src/main.rs", &file(1024, 1)));
+        assert!(!matches(expr, "README.md", &file(big, 1)));
+        assert!(!matches(expr, "target/debug/build.rs", &file(big, 1)));
+        assert!(!matches(expr, "src/old.rs", &file(big, 90)));
+    }
+
+    #[test]
+    fn matches_names_types_and_patterns() {
+        let md = file(0, 0);
+        assert!(matches("name = main.rs", "src/main.rs", &md));
+        assert!(!matches("name != main.rs", "src/main.rs", &md));
+        assert!(matches(r#"name ~ "glob:*.rs""#, "src/main.rs", &md));
+        assert!(matches(
+            r#"path ~ "regex:^src/.*\.rs$""#,
+            "src/main.rs",
+            &md
+        ));
+        assert!(matches("type = file", "src/main.rs", &md));
+        assert!(!matches("type in (dir, symlink)", "src/main.rs", &md));
+        assert!(matches("size = 0 xor name ~ main", "src/lib.rs", &md));
+        assert!(!matches("size = 0 xor name ~ main", "src/main.rs", &md));
+        assert!(matches("NOT ext = md AND size <= 1K", "a.rs", &md));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let md = file(0, 0);
+        assert!(matches("ext = md or ext = rs and size > 1", "a.md", &md));
+        assert!(!matches("(ext = md or ext = rs) and size > 1", "a.md", &md));
+    }
+
+    fn parse_error(expr: &str) -> String {
+        match WhereExpr::parse(expr, now()) {
+            Err(LlaError::Parse(message)) => message,
+            Err(other) => panic!("unexpected error: {}", other),
+            Ok(_) => panic!("'{}' should not parse", expr),
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        assert_eq!(
+            parse_error("size > 10M and ext ~ rs"),
+            "Invalid --where expression: '~' cannot be used with ext (expected =, !=, in)\n  \
+             size > 10M and ext ~ rs\n                     ^"
+        );
+
+        let message = parse_error("size > 10Q");
+        assert!(message.ends_with("size > 10Q\n         ^"), "{}", message);
+
+        let message = parse_error("(ext = rs or ext = md");
+        assert!(message.contains("expected ')'"), "{}", message);
+        assert!(message.ends_with("                       ^"), "{}", message);
+
+        assert!(parse_error("colour = red").contains("unknown field 'colour'"));
+        assert!(parse_error("name ~ \"unterminated").contains("unterminated string"));
+        assert!(parse_error("ext = rs rs").contains("expected 'and', 'or' or ')'"));
+    }
+}
@@ -0,0 +1,290 @@
+use super::{parse_size_range, parse_time_range, GlobFilter, NumericRange, TimeRange};
+use crate::error::{LlaError, Result};
+use lla_plugin_interface::manifest::FieldType;
+use lla_plugin_interface::proto::{typed_value::Value, DecoratedEntry};
+use std::fmt;
+use std::time::SystemTime;
+
+/// A `--field-filter` as written, such as `complexity>20`. It is only
+/// compiled once the plugin declaring the field is known, because the
+/// comparison depends on the field's declared type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldFilter {
+    pub field: String,
+    operator: &'static str,
+    value: String,
+}
+
+const OPERATORS: [&str; 7] = [">=", "<=", "!=", "=", ">", "<", "~"];
+
+impl FieldFilter {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let split = spec.find(|c: char| "<>=!~".contains(c)).ok_or_else(|| {
+            LlaError::Parse(format!(
+                "Invalid field filter '{}' (expected <field><op><value>, e.g. complexity>20)",
+                spec
+            ))
+        })?;
+        let field = spec[..split].trim();
+        let rest = &spec[split..];
+        let operator = OPERATORS
+            .iter()
+            .find(|op| rest.starts_with(**op))
+            .ok_or_else(|| {
+                LlaError::Parse(format!(
+                    "Invalid operator in field filter '{}' (expected =, !=, <, <=, >, >= or ~)",
+                    spec
+                ))
+            })?;
+        if field.is_empty() {
+            return Err(LlaError::Parse(format!(
+                "Field filter '{}' is missing a field name",
+                spec
+            )));
+        }
+        Ok(Self {
+            field: field.to_string(),
+            operator,
+            value: rest[operator.len()..].trim().to_string(),
+        })
+    }
+
+    /// Builds the comparison for a field of the given type.
+    pub fn compile(&self, field_type: FieldType, now: SystemTime) -> Result<FieldMatcher> {
+        let invalid = |e: LlaError| {
+            LlaError::Filter(format!(
+                "Invalid value for field '{}' ({:?}): {}",
+                self.field, field_type, e
+            ))
+        };
+        let unsupported = || {
+            LlaError::Filter(format!(
+                "Operator '{}' cannot be used with {:?} field '{}'",
+                self.operator, field_type, self.field
+            ))
+        };
+        let negated = self.operator == "!=";
+        let comparison = match field_type {
+            FieldType::Integer | FieldType::Bytes => {
+                if self.operator == "~" {
+                    return Err(unsupported());
+                }
+                let range = if matches!(self.operator, "=" | "!=") {
+                    parse_size_range(&self.value)
+                } else {
+                    parse_size_range(&format!("{}{}", self.operator, self.value))
+                };
+                Comparison::Numeric(range.map_err(invalid)?)
+            }
+            FieldType::Float => {
+                if self.operator == "~" {
+                    return Err(unsupported());
+                }
+                let value = self
+                    .value
+                    .parse::<f64>()
+                    .map_err(|e| invalid(LlaError::Parse(format!("'{}': {}", self.value, e))))?;
+                Comparison::Float(if negated { "=" } else { self.operator }, value)
+            }
+            FieldType::Boolean => {
+                if !matches!(self.operator, "=" | "!=") {
+                    return Err(unsupported());
+                }
+                let value = self.value.parse::<bool>().map_err(|_| {
+                    invalid(LlaError::Parse(format!(
+                        "'{}' is not true or false",
+                        self.value
+                    )))
+                })?;
+                Comparison::Boolean(value)
+            }
+            FieldType::Timestamp => {
+                let expression = match self.operator {
+                    "=" | "!=" => self.value.clone(),
+                    "~" => return Err(unsupported()),
+                    operator => format!("{}{}", operator, self.value),
+                };
+                Comparison::Time(parse_time_range(&expression, now).map_err(invalid)?)
+            }
+            FieldType::String | FieldType::Path => match self.operator {
+                "=" | "!=" => Comparison::Equals(self.value.clone()),
+                "~" => {
+                    // Without wildcards `~` is a substring match.
+                    let pattern = if self.value.contains(['*', '?', '[']) {
+                        self.value.clone()
+                    } else {
+                        format!("*{}*", self.value)
+                    };
+                    Comparison::Glob(GlobFilter::parse(&pattern).map_err(invalid)?)
+                }
+                _ => return Err(unsupported()),
+            },
+        };
+        Ok(FieldMatcher {
+            field: self.field.clone(),
+            comparison,
+            negated,
+        })
+    }
+}
+
+impl fmt::Display for FieldFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.field, self.operator, self.value)
+    }
+}
+
+#[derive(Clone)]
+enum Comparison {
+    Numeric(NumericRange),
+    Float(&'static str, f64),
+    Boolean(bool),
+    Time(TimeRange),
+    Equals(String),
+    Glob(GlobFilter),
+}
+
+/// A field filter compiled for the field's declared type.
+#[derive(Clone)]
+pub struct FieldMatcher {
+    field: String,
+    comparison: Comparison,
+    negated: bool,
+}
+
+impl FieldMatcher {
+    /// Entries without the field, or with a value of another type, never
+    /// match, even for `!=`.
+    pub fn matches(&self, entry: &DecoratedEntry) -> bool {
+        let typed = entry
+            .typed_fields
+            .get(&self.field)
+            .and_then(|value| value.value.as_ref());
+        let text = entry.custom_fields.get(&self.field);
+        let result = match &self.comparison {
+            Comparison::Numeric(range) => match typed {
+                Some(Value::IntegerValue(value)) => u64::try_from(*value).ok(),
+                Some(Value::BytesValue(value) | Value::TimestampValue(value)) => Some(*value),
+                _ => text.and_then(|text| text.trim().parse::<u64>().ok()),
+            }
+            .map(|value| range.matches(value)),
+            Comparison::Float(operator, expected) => match typed {
+                Some(Value::FloatValue(value)) => Some(*value),
+                Some(Value::IntegerValue(value)) => Some(*value as f64),
+                _ => text.and_then(|text| text.trim().parse::<f64>().ok()),
+            }
+            .map(|value| match *operator {
+                "<" => value < *expected,
+                "<=" => value <= *expected,
+                ">" => value > *expected,
+                ">=" => value >= *expected,
+                _ => value == *expected,
+            }),
+            Comparison::Boolean(expected) => match typed {
+                Some(Value::BooleanValue(value)) => Some(*value),
+                _ => text.and_then(|text| text.trim().parse::<bool>().ok()),
+            }
+            .map(|value| value == *expected),
+            Comparison::Time(range) => match typed {
+                Some(Value::TimestampValue(value)) => Some(*value),
+                _ => text.and_then(|text| text.trim().parse::<u64>().ok()),
+            }
+            .map(|seconds| range.matches_epoch_secs(seconds)),
+            Comparison::Equals(expected) => field_text(typed, text).map(|value| value == *expected),
+            Comparison::Glob(glob) => field_text(typed, text).map(|value| glob.matches(&value)),
+        };
+        match result {
+            Some(matched) => matched != self.negated,
+            None => false,
+        }
+    }
+}
+
+fn field_text(typed: Option<&Value>, text: Option<&String>) -> Option<String> {
+    match typed {
+        Some(Value::StringValue(value) | Value::PathValue(value)) => Some(value.clone()),
+        _ => text.cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lla_plugin_interface::proto::TypedValue;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn entry(field: &str, text: &str, typed: Option<Value>) -> DecoratedEntry {
+        let mut entry = DecoratedEntry::default();
+        entry
+            .custom_fields
+            .insert(field.to_string(), text.to_string());
+        if let Some(value) = typed {
+            entry
+                .typed_fields
+                .insert(field.to_string(), TypedValue { value: Some(value) });
+        }
+        entry
+    }
+
+    fn matcher(spec: &str, field_type: FieldType) -> FieldMatcher {
+        FieldFilter::parse(spec)
+            .unwrap()
+            .compile(field_type, UNIX_EPOCH + Duration::from_secs(100 * 86_400))
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_field_operator_and_value() {
+        let filter = FieldFilter::parse("complexity>=20").unwrap();
+        assert_eq!(filter.field, "complexity");
+        assert_eq!(filter.operator, ">=");
+        assert_eq!(filter.value, "20");
+        assert!(FieldFilter::parse("complexity").is_err());
+        assert!(FieldFilter::parse("=modified").is_err());
+    }
+
+    #[test]
+    fn compares_by_declared_type() {
+        let complexity = matcher("complexity>20", FieldType::Integer);
+        assert!(complexity.matches(&entry("complexity", "25", Some(Value::IntegerValue(25)))));
+        assert!(!complexity.matches(&entry("complexity", "5", Some(Value::IntegerValue(5)))));
+        assert!(!complexity.matches(&DecoratedEntry::default()));
+
+        let size = matcher("blob_size>=1K", FieldType::Bytes);
+        assert!(size.matches(&entry("blob_size", "2048", None)));
+
+        let status = matcher("git_status=modified", FieldType::String);
+        assert!(status.matches(&entry("git_status", "modified", None)));
+        assert!(
+            !matcher("git_status!=modified", FieldType::String).matches(&entry(
+                "git_status",
+                "modified",
+                None
+            ))
+        );
+
+        let tags = matcher("tags~release", FieldType::String);
+        assert!(tags.matches(&entry("tags", "beta,release", None)));
+        assert!(matcher("tags~rel*", FieldType::String).matches(&entry("tags", "release", None)));
+
+        let recent = matcher("reviewed>30d", FieldType::Timestamp);
+        assert!(recent.matches(&entry(
+            "reviewed",
+            "",
+            Some(Value::TimestampValue(90 * 86_400))
+        )));
+        assert!(!recent.matches(&entry("reviewed", "", Some(Value::TimestampValue(86_400)))));
+    }
+
+    #[test]
+    fn rejects_operators_the_type_does_not_support() {
+        let filter = FieldFilter::parse("complexity~2").unwrap();
+        assert!(filter
+            .compile(FieldType::Integer, SystemTime::now())
+            .is_err());
+        let filter = FieldFilter::parse("complexity>lots").unwrap();
+        assert!(filter
+            .compile(FieldType::Integer, SystemTime::now())
+            .is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use super::FileFilter;
+use crate::error::Result;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::path::{Path, PathBuf};
+
+/// A fuzzy match of a query against a file name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Character (not byte) offsets into the name of the matched characters.
+    pub positions: Vec<usize>,
+}
+
+/// Scores file names against a query with the fuzzy finder's engine,
+/// ignoring case. Names scoring below `min_score` do not match.
+pub struct FuzzyQuery {
+    query: String,
+    min_score: i64,
+    matcher: SkimMatcherV2,
+}
+
+impl FuzzyQuery {
+    pub fn new(query: &str, min_score: i64) -> Self {
+        FuzzyQuery {
+            query: query.to_string(),
+            min_score,
+            matcher: SkimMatcherV2::default().ignore_case(),
+        }
+    }
+
+    pub fn score(&self, path: &Path) -> Option<FuzzyMatch> {
+        let name = path.file_name()?.to_string_lossy();
+        let (score, positions) = self.matcher.fuzzy_indices(&name, &self.query)?;
+        (score >= self.min_score).then_some(FuzzyMatch { score, positions })
+    }
+}
+
+/// `fuzzy:<query>` as a plain name filter, for expressions and refinements.
+pub struct FuzzyFilter {
+    query: FuzzyQuery,
+}
+
+impl FuzzyFilter {
+    pub fn new(query: &str, min_score: i64) -> Self {
+        FuzzyFilter {
+            query: FuzzyQuery::new(query, min_score),
+        }
+    }
+}
+
+impl FileFilter for FuzzyFilter {
+    fn filter_files(&self, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        Ok(files
+            .iter()
+            .filter(|file| self.query.score(file).is_some())
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_names_with_char_positions() {
+        let query = FuzzyQuery::new("fu", 0);
+        let matched = query.score(Path::new("src/lister/Fuzzy.rs")).unwrap();
+        assert_eq!(matched.positions, [0, 1]);
+        assert!(query.score(Path::new("fuzzy/main.rs")).is_none());
+
+        let unicode = FuzzyQuery::new("ab", 0)
+            .score(Path::new("ü-a-b.txt"))
+            .unwrap();
+        assert_eq!(unicode.positions, [2, 4]);
+
+        let strict = FuzzyQuery::new("fu", matched.score + 1);
+        assert!(strict.score(Path::new("Fuzzy.rs")).is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use super::FileFilter;
+use crate::error::Result;
+use crate::formatter::serializable::{find_git_root, get_git_status_map};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GitStatusKind {
+    /// Changed in the working tree but not staged.
+    Modified,
+    Untracked,
+    /// Changed in the index.
+    Staged,
+    Conflicted,
+    Ignored,
+}
+
+impl GitStatusKind {
+    pub const NAMES: &'static [&'static str] =
+        &["modified", "untracked", "staged", "conflicted", "ignored"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "modified" => Some(Self::Modified),
+            "untracked" => Some(Self::Untracked),
+            "staged" => Some(Self::Staged),
+            "conflicted" => Some(Self::Conflicted),
+            "ignored" => Some(Self::Ignored),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Modified => "modified",
+            Self::Untracked => "untracked",
+            Self::Staged => "staged",
+            Self::Conflicted => "conflicted",
+            Self::Ignored => "ignored",
+        }
+    }
+
+    /// Whether a porcelain `XY` code has this status. A file can be both
+    /// staged and modified.
+    fn matches(self, code: &str) -> bool {
+        let conflicted = matches!(code, "DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU");
+        let mut flags = code.chars();
+        let changed = |flag: Option<char>| flag.is_some_and(|c| !matches!(c, '.' | '?' | '!'));
+        match self {
+            Self::Conflicted => conflicted,
+            Self::Untracked => code == "??",
+            Self::Ignored => code == "!!",
+            Self::Staged => !conflicted && changed(flags.next()),
+            Self::Modified => !conflicted && changed(flags.nth(1)),
+        }
+    }
+}
+
+/// Keeps entries whose Git working-tree status is one of `kinds`; directories
+/// pass when any descendant does. Each repository's status is read once, the
+/// first time an entry inside it is filtered, and entries outside a
+/// repository never match.
+pub struct GitStatusFilter {
+    kinds: Vec<GitStatusKind>,
+    directories: RwLock<HashMap<PathBuf, Option<Location>>>,
+    repositories: RwLock<HashMap<PathBuf, Arc<RepositoryMatches>>>,
+}
+
+#[derive(Clone)]
+struct Location {
+    repository: Arc<RepositoryMatches>,
+    relative: PathBuf,
+}
+
+/// The repository-relative paths that match, with all their parents, and
+/// the directories git reported as a whole, such as ignored build output.
+#[derive(Default)]
+struct RepositoryMatches {
+    paths: HashSet<PathBuf>,
+    subtrees: Vec<PathBuf>,
+}
+
+impl RepositoryMatches {
+    fn new(root: &Path, kinds: &[GitStatusKind]) -> Self {
+        let mut matches = Self::default();
+        let statuses = get_git_status_map(root, kinds.contains(&GitStatusKind::Ignored));
+        for (path, code) in statuses {
+            if !kinds.iter().any(|kind| kind.matches(&code)) {
+                continue;
+            }
+            let relative = PathBuf::from(path.trim_end_matches('/'));
+            if path.ends_with('/') {
+                matches.subtrees.push(relative.clone());
+            }
+            for ancestor in relative.ancestors() {
+                if !matches.paths.insert(ancestor.to_path_buf()) {
+                    break;
+                }
+            }
+        }
+        matches
+    }
+
+    fn contains(&self, relative: &Path) -> bool {
+        self.paths.contains(relative)
+            || self
+                .subtrees
+                .iter()
+                .any(|subtree| relative.starts_with(subtree))
+    }
+}
+
+impl GitStatusFilter {
+    pub fn new(kinds: Vec<GitStatusKind>) -> Self {
+        GitStatusFilter {
+            kinds,
+            directories: RwLock::new(HashMap::new()),
+            repositories: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        let location = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => self.locate_directory(parent).map(|mut location| {
+                location.relative.push(name);
+                location
+            }),
+            _ => self.locate_directory(path),
+        };
+        location.is_some_and(|location| location.repository.contains(&location.relative))
+    }
+
+    /// Finds the repository holding `directory`. Entries are filtered one
+    /// at a time, so lookups are cached by parent directory.
+    fn locate_directory(&self, directory: &Path) -> Option<Location> {
+        if let Some(cached) = self.directories.read().unwrap().get(directory) {
+            return cached.clone();
+        }
+
+        let location = (|| {
+            let canonical = if directory.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                directory
+            }
+            .canonicalize()
+            .ok()?;
+            let root = find_git_root(&canonical)?;
+            Some(Location {
+                relative: canonical.strip_prefix(&root).ok()?.to_path_buf(),
+                repository: self.repository(&root),
+            })
+        })();
+        self.directories
+            .write()
+            .unwrap()
+            .insert(directory.to_path_buf(), location.clone());
+        location
+    }
+
+    fn repository(&self, root: &Path) -> Arc<RepositoryMatches> {
+        if let Some(repository) = self.repositories.read().unwrap().get(root) {
+            return Arc::clone(repository);
+        }
+        // Holding the write lock while git runs keeps parallel callers from
+        // reading the same repository twice.
+        let mut repositories = self.repositories.write().unwrap();
+        Arc::clone(
+            repositories
+                .entry(root.to_path_buf())
+                .or_insert_with(|| Arc::new(RepositoryMatches::new(root, &self.kinds))),
+        )
+    }
+}
+
+impl FileFilter for GitStatusFilter {
+    fn filter_files(&self, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        Ok(files
+            .iter()
+            .filter(|file| self.matches(file))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn porcelain_codes_map_to_kinds() {
+        use GitStatusKind::*;
+        assert!(Modified.matches(".M"));
+        assert!(!Modified.matches("M."));
+        assert!(Staged.matches("M."));
+        assert!(Staged.matches("A."));
+        assert!(Staged.matches("RM") && Modified.matches("RM"));
+        assert!(Conflicted.matches("UU") && !Staged.matches("UU"));
+        assert!(Untracked.matches("??") && !Modified.matches("??"));
+        assert!(Ignored.matches("!!") && !Staged.matches("!!"));
+        assert!(!Modified.matches(".") && !Staged.matches("."));
+    }
+
+    #[test]
+    fn directories_pass_when_a_descendant_matches() {
+        let matches = RepositoryMatches {
+            paths: ["src/deep/new.rs", "src/deep", "src", ""]
+                .into_iter()
+                .map(PathBuf::from)
+                .collect(),
+            subtrees: vec![PathBuf::from("target")],
+        };
+        assert!(matches.contains(Path::new("src")));
+        assert!(matches.contains(Path::new("src/deep/new.rs")));
+        assert!(!matches.contains(Path::new("src/old.rs")));
+        assert!(matches.contains(Path::new("target/debug/lla")));
+        assert!(!matches.contains(Path::new("targets")));
+    }
+}
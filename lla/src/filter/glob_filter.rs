@@ -1,8 +1,9 @@
 use super::FileFilter;
-use crate::error::Result;
+use crate::error::{LlaError, Result};
 use glob::Pattern;
 use std::path::PathBuf;
 
+#[derive(Clone)]
 pub struct GlobFilter {
     pattern: Pattern,
 }
@@ -13,6 +14,17 @@ impl GlobFilter {
             pattern: Pattern::new(&pattern).unwrap_or_else(|_| Pattern::new("*").unwrap()),
         }
     }
+
+    /// Like `new`, but reports an invalid pattern instead of matching everything.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        Pattern::new(pattern)
+            .map(|pattern| GlobFilter { pattern })
+            .map_err(|e| LlaError::Parse(format!("Invalid glob '{}': {}", pattern, e.msg)))
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        self.pattern.matches(text)
+    }
 }
 
 impl FileFilter for GlobFilter {
@@ -22,7 +34,7 @@ impl FileFilter for GlobFilter {
             .filter(|file| {
                 file.file_name()
                     .and_then(|name| name.to_str())
-                    .map(|name| self.pattern.matches(name))
+                    .map(|name| self.matches(name))
                     .unwrap_or(false)
             })
             .cloned()
@@ -7,16 +7,28 @@ pub trait FileFilter: Send + Sync {
 
 mod case_insensitive;
 mod composite;
+mod expr;
 mod extension;
+mod field;
+mod fuzzy;
+mod git_status;
 mod glob_filter;
+mod ownership;
 mod pattern;
 mod range;
 mod regex_filter;
 
 pub use case_insensitive::CaseInsensitiveFilter;
 pub use composite::{CompositeFilter, FilterOperation};
+pub use expr::WhereExpr;
 pub use extension::ExtensionFilter;
+pub use field::{FieldFilter, FieldMatcher};
+pub use fuzzy::{FuzzyFilter, FuzzyQuery};
+pub use git_status::{GitStatusFilter, GitStatusKind};
 pub use glob_filter::GlobFilter;
+pub use ownership::{resolve_group, resolve_user, PermFilter};
 pub use pattern::PatternFilter;
-pub use range::{parse_size_range, parse_time_range, NumericRange, TimeRange};
+pub use range::{
+    parse_age, parse_size, parse_size_range, parse_time_range, NumericRange, TimeRange,
+};
 pub use regex_filter::RegexFilter;
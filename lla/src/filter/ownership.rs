@@ -0,0 +1,203 @@
+use crate::error::{LlaError, Result};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PermMatch {
+    /// `644`: the permission bits are exactly these.
+    Exact,
+    /// `-644`: all of these bits are set.
+    All,
+    /// `/644`: any of these bits is set.
+    Any,
+}
+
+/// A `--perm` mode with find(1) semantics: octal or symbolic, optionally
+/// prefixed with `-` (all bits set) or `/` (any bit set).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermFilter {
+    source: String,
+    bits: u32,
+    kind: PermMatch,
+}
+
+impl PermFilter {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let trimmed = spec.trim();
+        let (kind, mode) = if let Some(mode) = trimmed.strip_prefix('-') {
+            (PermMatch::All, mode)
+        } else if let Some(mode) = trimmed.strip_prefix('/') {
+            (PermMatch::Any, mode)
+        } else {
+            (PermMatch::Exact, trimmed)
+        };
+        let bits = if !mode.is_empty() && mode.chars().all(|c| c.is_digit(8)) {
+            u32::from_str_radix(mode, 8)
+                .ok()
+                .filter(|bits| *bits <= 0o7777)
+                .ok_or_else(|| invalid(spec, "octal modes go up to 7777"))?
+        } else {
+            parse_symbolic(mode).map_err(|reason| invalid(spec, &reason))?
+        };
+        Ok(Self {
+            source: trimmed.to_string(),
+            bits,
+            kind,
+        })
+    }
+
+    pub fn matches(&self, permissions: u32) -> bool {
+        let mode = permissions & 0o7777;
+        match self.kind {
+            PermMatch::Exact => mode == self.bits,
+            PermMatch::All => mode & self.bits == self.bits,
+            // As in find, `/000` matches everything.
+            PermMatch::Any => self.bits == 0 || mode & self.bits != 0,
+        }
+    }
+}
+
+impl fmt::Display for PermFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn invalid(spec: &str, reason: &str) -> LlaError {
+    LlaError::Parse(format!("Invalid permission mode '{}': {}", spec, reason))
+}
+
+/// Applies a chmod-style symbolic mode such as `u+x,go=r` to an empty mode,
+/// as find does.
+fn parse_symbolic(mode: &str) -> std::result::Result<u32, String> {
+    if mode.is_empty() {
+        return Err("expected an octal or symbolic mode such as 644 or u+x".to_string());
+    }
+    let mut bits = 0;
+    for clause in mode.split(',') {
+        let ops_at = clause
+            .find(['+', '-', '='])
+            .ok_or_else(|| format!("'{}' has no +, - or = operator", clause))?;
+        // Each class owns its rwx bits and one special bit: setuid for u,
+        // setgid for g and the sticky bit for o.
+        let (mut user, mut group, mut other) = (false, false, false);
+        for c in clause[..ops_at].chars() {
+            match c {
+                'u' => user = true,
+                'g' => group = true,
+                'o' => other = true,
+                'a' => (user, group, other) = (true, true, true),
+                _ => return Err(format!("unknown class '{}' (expected u, g, o or a)", c)),
+            }
+        }
+        if !(user || group || other) {
+            (user, group, other) = (true, true, true);
+        }
+        let class_bits = |u: u32, g: u32, o: u32| {
+            (if user { u } else { 0 }) | (if group { g } else { 0 }) | (if other { o } else { 0 })
+        };
+        let who = class_bits(0o4700, 0o2070, 0o1007);
+
+        let mut rest = clause[ops_at..].chars().peekable();
+        while let Some(op) = rest.next() {
+            let mut perms = 0;
+            while let Some(&c) = rest.peek() {
+                if matches!(c, '+' | '-' | '=') {
+                    break;
+                }
+                perms |= match c {
+                    'r' => class_bits(0o400, 0o040, 0o004),
+                    'w' => class_bits(0o200, 0o020, 0o002),
+                    'x' => class_bits(0o100, 0o010, 0o001),
+                    // `o+s` sets nothing, as with chmod.
+                    's' => class_bits(0o4000, 0o2000, 0),
+                    't' => class_bits(0, 0, 0o1000),
+                    _ => {
+                        return Err(format!(
+                            "unknown permission '{}' (expected r, w, x, s or t)",
+                            c
+                        ))
+                    }
+                };
+                rest.next();
+            }
+            match op {
+                '+' => bits |= perms,
+                '-' => bits &= !perms,
+                _ => bits = (bits & !who) | perms,
+            }
+        }
+    }
+    Ok(bits)
+}
+
+/// Resolves `--owner` to a uid, by number or through the user database.
+pub fn resolve_user(spec: &str) -> Result<u32> {
+    if let Ok(uid) = spec.trim().parse() {
+        return Ok(uid);
+    }
+    #[cfg(unix)]
+    if let Some(user) = users::get_user_by_name(spec.trim()) {
+        return Ok(user.uid());
+    }
+    Err(LlaError::Filter(format!("Unknown user '{}'", spec)))
+}
+
+/// Resolves `--group` to a gid, by number or through the group database.
+pub fn resolve_group(spec: &str) -> Result<u32> {
+    if let Ok(gid) = spec.trim().parse() {
+        return Ok(gid);
+    }
+    #[cfg(unix)]
+    if let Some(group) = users::get_group_by_name(spec.trim()) {
+        return Ok(group.gid());
+    }
+    Err(LlaError::Filter(format!("Unknown group '{}'", spec)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn perm(spec: &str) -> PermFilter {
+        PermFilter::parse(spec).unwrap()
+    }
+
+    #[test]
+    fn octal_modes_follow_find() {
+        assert!(perm("644").matches(0o100644));
+        assert!(!perm("644").matches(0o100664));
+        assert!(perm("-220").matches(0o100664));
+        assert!(!perm("-220").matches(0o100644));
+        assert!(perm("/111").matches(0o100744));
+        assert!(!perm("/111").matches(0o100644));
+        assert!(perm("/000").matches(0o100600));
+        assert!(PermFilter::parse("8").is_err());
+        assert!(PermFilter::parse("17777").is_err());
+    }
+
+    #[test]
+    fn symbolic_modes_apply_to_an_empty_mode() {
+        assert_eq!(perm("u=rwx,go=rx").bits, 0o755);
+        assert_eq!(perm("-g+w").bits, 0o020);
+        assert_eq!(perm("/u+x,g+x").bits, 0o110);
+        assert_eq!(perm("u+s").bits, 0o4000);
+        assert_eq!(perm("+t").bits, 0o1000);
+        assert_eq!(perm("a+rw,o-w").bits, 0o664);
+        assert_eq!(perm("g+s").bits, 0o2000);
+        assert_eq!(perm("o+s").bits, 0);
+        assert_eq!(perm("a+s").bits, 0o6000);
+        assert_eq!(perm("o+t").bits, 0o1000);
+        assert_eq!(perm("u+t").bits, 0);
+        assert!(perm("-o+w").matches(0o40777));
+        assert!(!perm("-o+w").matches(0o40755));
+        assert!(PermFilter::parse("u+q").is_err());
+        assert!(PermFilter::parse("ux").is_err());
+    }
+
+    #[test]
+    fn numeric_owners_need_no_lookup() {
+        assert_eq!(resolve_user("1000").unwrap(), 1000);
+        assert_eq!(resolve_group("0").unwrap(), 0);
+        assert!(resolve_user("no-such-user-for-lla-tests").is_err());
+    }
+}
@@ -1,5 +1,8 @@
 use crate::error::{LlaError, Result};
-use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono::{
+    DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
 use serde::Serialize;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -184,6 +187,10 @@ pub fn parse_size_range(expr: &str) -> Result<NumericRange> {
     })
 }
 
+/// Parses a time filter. Relative durations and dates are instants, while
+/// phrases such as `yesterday` or `this-week` name a period: on their own
+/// they match anything inside it, and in comparisons they compare as an
+/// interval, so `>yesterday` means after yesterday ended.
 pub fn parse_time_range(expr: &str, now: SystemTime) -> Result<TimeRange> {
     let trimmed = expr.trim();
     if trimmed.is_empty() {
@@ -192,14 +199,14 @@ pub fn parse_time_range(expr: &str, now: SystemTime) -> Result<TimeRange> {
 
     if let Some(rest) = trimmed.strip_prefix(">=") {
         return Ok(TimeRange {
-            earliest: Some(parse_time_point(rest.trim(), now)?),
+            earliest: Some(parse_time_point(rest.trim(), now)?.start),
             latest: None,
         });
     }
 
     if let Some(rest) = trimmed.strip_prefix('>') {
         return Ok(TimeRange {
-            earliest: Some(parse_time_point(rest.trim(), now)?),
+            earliest: Some(parse_time_point(rest.trim(), now)?.end),
             latest: None,
         });
     }
@@ -207,14 +214,14 @@ pub fn parse_time_range(expr: &str, now: SystemTime) -> Result<TimeRange> {
     if let Some(rest) = trimmed.strip_prefix("<=") {
         return Ok(TimeRange {
             earliest: None,
-            latest: Some(parse_time_point(rest.trim(), now)?),
+            latest: Some(parse_time_point(rest.trim(), now)?.end),
         });
     }
 
     if let Some(rest) = trimmed.strip_prefix('<') {
         return Ok(TimeRange {
             earliest: None,
-            latest: Some(parse_time_point(rest.trim(), now)?),
+            latest: Some(parse_time_point(rest.trim(), now)?.start),
         });
     }
 
@@ -225,13 +232,13 @@ pub fn parse_time_range(expr: &str, now: SystemTime) -> Result<TimeRange> {
         let earliest = if start.is_empty() {
             None
         } else {
-            Some(parse_time_point(start, now)?)
+            Some(parse_time_point(start, now)?.start)
         };
 
         let latest = if end.is_empty() {
             None
         } else {
-            Some(parse_time_point(end, now)?)
+            Some(parse_time_point(end, now)?.end)
         };
 
         let mut range = TimeRange { earliest, latest };
@@ -239,9 +246,25 @@ pub fn parse_time_range(expr: &str, now: SystemTime) -> Result<TimeRange> {
         return Ok(range);
     }
 
+    let point = parse_time_point(trimmed, now)?;
     Ok(TimeRange {
-        earliest: Some(parse_time_point(trimmed, now)?),
-        latest: None,
+        earliest: Some(point.start),
+        latest: point.is_period.then_some(point.end),
+    })
+}
+
+/// Parses a single size such as `512K` or `1.5GiB`.
+pub fn parse_size(expr: &str) -> Result<u64> {
+    parse_size_value(expr.trim())
+}
+
+/// Parses a length of time such as `30d` or `12h`.
+pub fn parse_age(expr: &str) -> Result<Duration> {
+    parse_duration(expr.trim())?.ok_or_else(|| {
+        LlaError::Parse(format!(
+            "Invalid duration '{}' (expected a value such as 30d or 12h)",
+            expr
+        ))
     })
 }
 
@@ -337,16 +360,36 @@ fn size_multiplier(unit: &str) -> Result<u64> {
     Ok(multiplier)
 }
 
-fn parse_time_point(input: &str, now: SystemTime) -> Result<SystemTime> {
+/// A time bound: an instant, or a period such as a whole day whose `end` is
+/// the last instant inside it.
+struct TimePoint {
+    start: SystemTime,
+    end: SystemTime,
+    is_period: bool,
+}
+
+impl TimePoint {
+    fn instant(at: SystemTime) -> Self {
+        TimePoint {
+            start: at,
+            end: at,
+            is_period: false,
+        }
+    }
+}
+
+fn parse_time_point(input: &str, now: SystemTime) -> Result<TimePoint> {
     let trimmed = input.trim();
     if let Ok(abs) = parse_absolute_datetime(trimmed) {
-        return Ok(abs);
+        return Ok(TimePoint::instant(abs));
+    }
+
+    if let Some(point) = parse_phrase(trimmed, now)? {
+        return Ok(point);
     }
 
     if let Some(duration) = parse_duration(trimmed)? {
-        return now.checked_sub(duration).ok_or_else(|| {
-            LlaError::Parse(format!("Relative time '{}' exceeds supported range", input))
-        });
+        return relative_to(now, duration, input).map(TimePoint::instant);
     }
 
     Err(LlaError::Parse(format!(
@@ -355,6 +398,98 @@ fn parse_time_point(input: &str, now: SystemTime) -> Result<SystemTime> {
     )))
 }
 
+fn relative_to(now: SystemTime, duration: Duration, input: &str) -> Result<SystemTime> {
+    now.checked_sub(duration).ok_or_else(|| {
+        LlaError::Parse(format!("Relative time '{}' exceeds supported range", input))
+    })
+}
+
+/// Natural phrases, read in local time: `now`, `today`, `yesterday`,
+/// `this-week`/`last-week` (weeks start on Monday), the same for `month` and
+/// `year`, weekday names such as `monday` or `last monday` (the most recent
+/// one before today), and `<duration> ago`. Words may be separated by spaces,
+/// `-` or `_`.
+fn parse_phrase(input: &str, now: SystemTime) -> Result<Option<TimePoint>> {
+    let normalized = input.to_ascii_lowercase().replace(['-', '_'], " ");
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+
+    if let [amount @ .., "ago"] = words.as_slice() {
+        let Some(duration) = parse_duration(&amount.concat())? else {
+            return Ok(None);
+        };
+        return relative_to(now, duration, input)
+            .map(TimePoint::instant)
+            .map(Some);
+    }
+
+    let today = DateTime::<Local>::from(now).date_naive();
+    let days = |n: u64| Days::new(n);
+    let (first, after) = match words.as_slice() {
+        ["now"] => return Ok(Some(TimePoint::instant(now))),
+        ["today"] => (today, today + days(1)),
+        ["yesterday"] => (today - days(1), today),
+        [which @ ("this" | "last"), unit] => {
+            let back = *which == "last";
+            match *unit {
+                "week" => {
+                    let monday = today - days(today.weekday().num_days_from_monday().into());
+                    let monday = if back { monday - days(7) } else { monday };
+                    (monday, monday + days(7))
+                }
+                "month" => {
+                    let first = today.with_day(1).unwrap_or(today);
+                    let first = if back { first - Months::new(1) } else { first };
+                    (first, first + Months::new(1))
+                }
+                "year" => {
+                    let year = today.year() - i32::from(back);
+                    match (
+                        NaiveDate::from_ymd_opt(year, 1, 1),
+                        NaiveDate::from_ymd_opt(year + 1, 1, 1),
+                    ) {
+                        (Some(first), Some(after)) => (first, after),
+                        _ => return Ok(None),
+                    }
+                }
+                day if back => match day.parse::<Weekday>() {
+                    Ok(weekday) => last_weekday(today, weekday),
+                    Err(_) => return Ok(None),
+                },
+                _ => return Ok(None),
+            }
+        }
+        [day] => match day.parse::<Weekday>() {
+            Ok(weekday) => last_weekday(today, weekday),
+            Err(_) => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(Some(TimePoint {
+        start: local_midnight(first)?,
+        end: local_midnight(after)? - Duration::from_nanos(1),
+        is_period: true,
+    }))
+}
+
+/// The most recent `weekday` strictly before `today`, as a one-day period.
+fn last_weekday(today: NaiveDate, weekday: Weekday) -> (NaiveDate, NaiveDate) {
+    let back = (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    let day = today - Days::new(if back == 0 { 7 } else { back.into() });
+    (day, day + Days::new(1))
+}
+
+fn local_midnight(date: NaiveDate) -> Result<SystemTime> {
+    let naive = date.and_time(NaiveTime::MIN);
+    // Midnight can fall in a daylight-saving gap; use the first valid instant.
+    let local = Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc));
+    datetime_to_system_time(local)
+}
+
 fn parse_duration(token: &str) -> Result<Option<Duration>> {
     if token.is_empty() {
         return Ok(None);
@@ -459,3 +594,48 @@ fn datetime_to_system_time(dt: DateTime<Utc>) -> Result<SystemTime> {
     let nanos = dt.timestamp_subsec_nanos() as u64;
     Ok(UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_nanos(nanos))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, m: u32, d: u32, h: u32) -> SystemTime {
+        Local.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap().into()
+    }
+
+    // Wednesday 2024-05-08, 15:00 local time.
+    fn now() -> SystemTime {
+        local(2024, 5, 8, 15)
+    }
+
+    fn matches(expr: &str, at: SystemTime) -> bool {
+        parse_time_range(expr, now()).unwrap().matches_timestamp(at)
+    }
+
+    #[test]
+    fn phrases_name_whole_periods() {
+        assert!(matches("yesterday", local(2024, 5, 7, 12)));
+        assert!(!matches("yesterday", local(2024, 5, 8, 0)));
+        assert!(matches("this-week", local(2024, 5, 6, 0)));
+        assert!(!matches("this week", local(2024, 5, 5, 23)));
+        assert!(matches("last_week", local(2024, 4, 29, 9)));
+        assert!(matches("last-month", local(2024, 4, 30, 23)));
+        assert!(matches("last monday", local(2024, 5, 6, 10)));
+        assert!(matches("wednesday", local(2024, 5, 1, 10)));
+        assert!(!matches("wednesday", local(2024, 5, 8, 10)));
+    }
+
+    #[test]
+    fn periods_compare_as_intervals() {
+        assert!(matches(">yesterday", local(2024, 5, 8, 1)));
+        assert!(!matches(">yesterday", local(2024, 5, 7, 23)));
+        assert!(matches(">=yesterday", local(2024, 5, 7, 1)));
+        assert!(matches("<yesterday", local(2024, 5, 6, 23)));
+        assert!(!matches("<yesterday", local(2024, 5, 7, 1)));
+        assert!(matches("last monday..yesterday", local(2024, 5, 7, 23)));
+        assert!(matches("2024-05-01T09:00..now", local(2024, 5, 8, 14)));
+        assert!(!matches("2024-05-01T09:00..now", local(2024, 5, 8, 16)));
+        assert!(matches(">2 days ago", local(2024, 5, 7, 0)));
+        assert!(parse_time_range("next tuesday", now()).is_err());
+    }
+}
@@ -1,8 +1,9 @@
 use super::FileFilter;
-use crate::error::Result;
+use crate::error::{LlaError, Result};
 use regex::Regex;
 use std::path::PathBuf;
 
+#[derive(Clone)]
 pub struct RegexFilter {
     regex: Regex,
 }
@@ -13,6 +14,17 @@ impl RegexFilter {
             regex: Regex::new(&pattern).unwrap_or_else(|_| Regex::new(".*").unwrap()),
         }
     }
+
+    /// Like `new`, but reports an invalid pattern instead of matching everything.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        Regex::new(pattern)
+            .map(|regex| RegexFilter { regex })
+            .map_err(|e| LlaError::Parse(format!("Invalid regex '{}': {}", pattern, e)))
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
 }
 
 impl FileFilter for RegexFilter {
@@ -22,7 +34,7 @@ impl FileFilter for RegexFilter {
             .filter(|file| {
                 file.file_name()
                     .and_then(|name| name.to_str())
-                    .map(|name| self.regex.is_match(name))
+                    .map(|name| self.matches(name))
                     .unwrap_or(false)
             })
             .cloned()
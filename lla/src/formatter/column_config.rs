@@ -13,6 +13,7 @@ pub enum ColumnKey {
     Xattrs,
     Context,
     Mount,
+    Mime,
     Name,
     Path,
     Plugins,
@@ -56,6 +57,7 @@ impl ColumnKey {
             "xattrs" | "extended" => ColumnKey::Xattrs,
             "context" | "security_context" | "security-context" | "acl" => ColumnKey::Context,
             "mount" | "mounts" => ColumnKey::Mount,
+            "mime" | "mime_type" | "mime-type" | "content_type" => ColumnKey::Mime,
             "name" => ColumnKey::Name,
             "path" => ColumnKey::Path,
             "plugins" | "plugin" => ColumnKey::Plugins,
@@ -85,6 +87,7 @@ impl ColumnKey {
             ColumnKey::Xattrs => "Xattrs".to_string(),
             ColumnKey::Context => "Context".to_string(),
             ColumnKey::Mount => "Mount".to_string(),
+            ColumnKey::Mime => "MIME".to_string(),
             ColumnKey::Name => "Name".to_string(),
             ColumnKey::Path => "Path".to_string(),
             ColumnKey::Plugins => "Plugins".to_string(),
@@ -116,5 +119,6 @@ mod tests {
             ColumnKey::Context
         );
         assert_eq!(ColumnKey::from_config("mounts"), ColumnKey::Mount);
+        assert_eq!(ColumnKey::from_config("mime-type"), ColumnKey::Mime);
     }
 }
@@ -40,6 +40,7 @@ where
         "symlink_target",
         "is_hidden",
         "git_status",
+        "operand",
     ])?;
 
     let mut git_status_map = None;
@@ -51,7 +52,7 @@ where
                 if let Some(parent) = std::path::Path::new(&entry.path).parent() {
                     if let Some(root) = find_git_root(parent) {
                         git_root = Some(root.clone());
-                        git_status_map = Some(get_git_status_map(&root));
+                        git_status_map = Some(get_git_status_map(&root, false));
                     }
                 }
             }
@@ -99,6 +100,7 @@ where
             serial.symlink_target.unwrap_or_default(),
             serial.is_hidden.to_string(),
             serial.git_status.unwrap_or_default(),
+            serial.operand.unwrap_or_default(),
         ])?;
     }
 
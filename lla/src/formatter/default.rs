@@ -1,18 +1,25 @@
+use super::quoting::PlainOutput;
 use super::FileFormatter;
 use crate::error::Result;
 use crate::plugin::PluginManager;
-use crate::utils::color::{colorize_file_name, colorize_file_name_with_icon};
+use crate::utils::color::{classify_indicator, colorize_file_name, colorize_file_name_with_icon};
 use crate::utils::hyperlink;
 use crate::utils::icons::format_with_icon;
 use lla_plugin_interface::proto::DecoratedEntry;
 use std::path::Path;
 pub struct DefaultFormatter {
     pub show_icons: bool,
+    pub plain: Option<PlainOutput>,
+    pub classify: bool,
 }
 
 impl DefaultFormatter {
-    pub fn new(show_icons: bool) -> Self {
-        Self { show_icons }
+    pub fn new(show_icons: bool, plain: Option<PlainOutput>, classify: bool) -> Self {
+        Self {
+            show_icons,
+            plain,
+            classify,
+        }
     }
 }
 impl FileFormatter for DefaultFormatter {
@@ -22,18 +29,31 @@ impl FileFormatter for DefaultFormatter {
         plugin_manager: &mut PluginManager,
         _depth: Option<usize>,
     ) -> Result<String> {
+        if let Some(plain) = self.plain {
+            return Ok(plain.records(files.iter().map(|file| {
+                Path::new(&file.path)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or(&file.path)
+            })));
+        }
+
         plugin_manager.prepare_format_fields(files, "default");
         Ok(files
             .iter()
             .map(|file| {
                 let path = Path::new(&file.path);
-                let colored_name = colorize_file_name(path).to_string();
+                let colored_name = colorize_file_name(path, file.metadata.as_ref()).to_string();
                 let name_with_icon = colorize_file_name_with_icon(
                     path,
-                    format_with_icon(path, colored_name, self.show_icons),
+                    file.metadata.as_ref(),
+                    format_with_icon(path, file.metadata.as_ref(), colored_name, self.show_icons),
                 )
                 .to_string();
-                let name_with_icon = hyperlink::link_path(path, name_with_icon);
+                let mut name_with_icon = hyperlink::link_path(path, name_with_icon);
+                if self.classify {
+                    name_with_icon.push_str(classify_indicator(path, file.metadata.as_ref()));
+                }
                 let plugin_fields = plugin_manager.format_fields(file, "default").join(" ");
                 if plugin_fields.is_empty() {
                     name_with_icon
@@ -44,4 +64,8 @@ impl FileFormatter for DefaultFormatter {
             .collect::<Vec<_>>()
             .join("\n"))
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
 }
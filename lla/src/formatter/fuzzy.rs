@@ -32,11 +32,12 @@ impl FuzzyFormatter {
         let metadata = entry.metadata.as_ref().cloned().unwrap_or_default();
         let path = Path::new(&entry.path);
 
-        let colored_name = colorize_file_name(path).to_string();
+        let colored_name = colorize_file_name(path, Some(&metadata)).to_string();
         let name_display = if self.show_icons {
             colorize_file_name_with_icon(
                 path,
-                format_with_icon(path, colored_name, self.show_icons),
+                Some(&metadata),
+                format_with_icon(path, Some(&metadata), colored_name, self.show_icons),
             )
             .to_string()
         } else {
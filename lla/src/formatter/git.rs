@@ -492,10 +492,12 @@ impl GitFormatter {
 
         for file in files {
             let path = Path::new(&file.path);
-            let name = colorize_file_name(path);
+            let metadata = file.metadata.as_ref();
+            let name = colorize_file_name(path, metadata);
             let name_with_icon = colorize_file_name_with_icon(
                 path,
-                format_with_icon(path, name.to_string(), show_icons),
+                metadata,
+                format_with_icon(path, metadata, name.to_string(), show_icons),
             )
             .to_string();
             let name_with_icon = hyperlink::link_path(path, name_with_icon);
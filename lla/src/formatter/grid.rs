@@ -1,6 +1,6 @@
 use super::FileFormatter;
 use crate::plugin::PluginManager;
-use crate::utils::color::colorize_file_name;
+use crate::utils::color::{classify_indicator, colorize_file_name};
 use crate::utils::hyperlink;
 use crate::utils::icons::format_with_icon;
 use crate::{error::Result, utils::color::colorize_file_name_with_icon};
@@ -13,14 +13,16 @@ pub struct GridFormatter {
     pub show_icons: bool,
     pub grid_ignore: bool,
     pub max_width: usize,
+    pub classify: bool,
 }
 
 impl GridFormatter {
-    pub fn new(show_icons: bool, grid_ignore: bool, max_width: usize) -> Self {
+    pub fn new(show_icons: bool, grid_ignore: bool, max_width: usize, classify: bool) -> Self {
         Self {
             show_icons,
             grid_ignore,
             max_width,
+            classify,
         }
     }
 }
@@ -49,13 +51,17 @@ impl FileFormatter for GridFormatter {
 
         for file in files {
             let path = Path::new(&file.path);
-            let colored_name = colorize_file_name(path).to_string();
+            let colored_name = colorize_file_name(path, file.metadata.as_ref()).to_string();
             let name_with_icon = colorize_file_name_with_icon(
                 path,
-                format_with_icon(path, colored_name, self.show_icons),
+                file.metadata.as_ref(),
+                format_with_icon(path, file.metadata.as_ref(), colored_name, self.show_icons),
             )
             .to_string();
-            let name_with_icon = hyperlink::link_path(path, name_with_icon);
+            let mut name_with_icon = hyperlink::link_path(path, name_with_icon);
+            if self.classify {
+                name_with_icon.push_str(classify_indicator(path, file.metadata.as_ref()));
+            }
             let plugin_fields = plugin_manager.format_fields(file, "grid").join(" ");
             let total_str = if plugin_fields.is_empty() {
                 name_with_icon.clone()
@@ -1,9 +1,48 @@
 use crate::error::Result;
 use crate::plugin::PluginManager;
 use lla_plugin_interface::proto::DecoratedEntry;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-use super::serializable::{find_git_root, get_git_status_map, to_serializable};
+use super::serializable::{find_git_root, get_git_status_map, to_serializable, SerializableEntry};
+
+/// A change reported by `--watch --ndjson`.
+#[derive(Serialize)]
+struct ChangeEvent<'a> {
+    event: &'a str,
+    entry: SerializableEntry,
+}
+
+/// Resolves git status for entries, locating the repository lazily from the
+/// first entry that needs it.
+#[derive(Default)]
+struct GitStatusResolver {
+    git_root: Option<PathBuf>,
+    git_status_map: Option<HashMap<String, String>>,
+}
+
+impl GitStatusResolver {
+    fn status_for(&mut self, entry: &DecoratedEntry) -> Option<String> {
+        if self.git_root.is_none() {
+            if let Some(parent) = Path::new(&entry.path).parent() {
+                if let Some(root) = find_git_root(parent) {
+                    self.git_status_map = Some(get_git_status_map(&root, false));
+                    self.git_root = Some(root);
+                }
+            }
+        }
+        let root = self.git_root.as_ref()?;
+        let full = Path::new(&entry.path);
+        let rel = full.strip_prefix(root).unwrap_or(full);
+        let rel_str = rel.to_string_lossy().to_string();
+        self.git_status_map
+            .as_ref()
+            .and_then(|m| m.get(&rel_str))
+            .cloned()
+    }
+}
 
 pub fn write_json_array_stream<I>(
     entries: I,
@@ -16,13 +55,8 @@ where
 {
     let mut stdout = io::BufWriter::new(io::stdout());
 
-    let mut git_status_map = None;
-    let mut git_root = None;
-
-    // Prepare git status map if any plugin/git format would have been requested
-    // We don't compute git by default; only when git format is active in current run.
-    // Here we infer it from plugin_manager or rely on existing fields. To keep it simple,
-    // we attempt to find git root from first entry lazily.
+    // Git status is only computed when git format is active in the current run.
+    let mut git = GitStatusResolver::default();
 
     stdout.write_all(b"[")?;
     let mut first = true;
@@ -33,27 +67,8 @@ where
             stdout.write_all(b",")?;
         }
 
-        // Determine git status lazily
         let git_status = if include_git_status {
-            if git_root.is_none() {
-                if let Some(parent) = std::path::Path::new(&entry.path).parent() {
-                    if let Some(root) = find_git_root(parent) {
-                        git_root = Some(root.clone());
-                        git_status_map = Some(get_git_status_map(&root));
-                    }
-                }
-            }
-            if let Some(root) = &git_root {
-                let full = std::path::Path::new(&entry.path);
-                let rel = full.strip_prefix(root).unwrap_or(full);
-                let rel_str = rel.to_string_lossy().to_string();
-                git_status_map
-                    .as_ref()
-                    .and_then(|m| m.get(&rel_str))
-                    .cloned()
-            } else {
-                None
-            }
+            git.status_for(&entry)
         } else {
             None
         };
@@ -80,41 +95,70 @@ pub fn write_ndjson_stream<I>(
 where
     I: IntoIterator<Item = DecoratedEntry>,
 {
-    let mut stdout = io::BufWriter::new(io::stdout());
+    let mut writer = NdjsonWriter::new(include_git_status);
+    for entry in entries {
+        writer.write_entry(&entry)?;
+    }
+    writer.flush()
+}
 
-    let mut git_status_map = None;
-    let mut git_root = None;
+/// NDJSON writer that can be fed incrementally, one batch of a streamed listing
+/// at a time. Git status state is kept across batches.
+pub struct NdjsonWriter {
+    stdout: io::BufWriter<io::Stdout>,
+    include_git_status: bool,
+    git: GitStatusResolver,
+}
 
-    for entry in entries {
-        let git_status = if include_git_status {
-            if git_root.is_none() {
-                if let Some(parent) = std::path::Path::new(&entry.path).parent() {
-                    if let Some(root) = find_git_root(parent) {
-                        git_root = Some(root.clone());
-                        git_status_map = Some(get_git_status_map(&root));
-                    }
-                }
-            }
-            if let Some(root) = &git_root {
-                let full = std::path::Path::new(&entry.path);
-                let rel = full.strip_prefix(root).unwrap_or(full);
-                let rel_str = rel.to_string_lossy().to_string();
-                git_status_map
-                    .as_ref()
-                    .and_then(|m| m.get(&rel_str))
-                    .cloned()
-            } else {
-                None
-            }
+impl NdjsonWriter {
+    pub fn new(include_git_status: bool) -> Self {
+        Self {
+            stdout: io::BufWriter::new(io::stdout()),
+            include_git_status,
+            git: GitStatusResolver::default(),
+        }
+    }
+
+    pub fn write_entry(&mut self, entry: &DecoratedEntry) -> Result<()> {
+        let git_status = if self.include_git_status {
+            self.git.status_for(entry)
         } else {
             None
         };
 
-        let serial = to_serializable(&entry, git_status);
+        let serial = to_serializable(entry, git_status);
         let json = serde_json::to_string(&serial)?;
-        stdout.write_all(json.as_bytes())?;
-        stdout.write_all(b"\n")?;
+        self.stdout.write_all(json.as_bytes())?;
+        self.stdout.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Writes `{"event": ..., "entry": {...}}` for a watched change.
+    pub fn write_event(&mut self, event: &str, entry: &DecoratedEntry) -> Result<()> {
+        let git_status = if self.include_git_status {
+            self.git.status_for(entry)
+        } else {
+            None
+        };
+
+        let change = ChangeEvent {
+            event,
+            entry: to_serializable(entry, git_status),
+        };
+        serde_json::to_writer(&mut self.stdout, &change)?;
+        self.stdout.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn write_batch(&mut self, entries: &[DecoratedEntry]) -> Result<()> {
+        for entry in entries {
+            self.write_entry(entry)?;
+        }
+        self.flush()
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.stdout.flush()?;
+        Ok(())
     }
-    stdout.flush()?;
-    Ok(())
 }
@@ -1,4 +1,5 @@
 use super::column_config::ColumnKey;
+use super::serializable::entry_mime;
 use super::FileFormatter;
 use crate::config::DEFAULT_LONG_DATE_FORMAT;
 use crate::error::Result;
@@ -32,6 +33,7 @@ pub struct LongFormatter {
     pub permission_format: String,
     pub hide_group: bool,
     pub relative_dates: bool,
+    pub classify: bool,
     date_format_items: Vec<Item<'static>>,
     columns: Vec<ColumnKey>,
     has_plugins_column: bool,
@@ -45,6 +47,7 @@ impl LongFormatter {
         relative_dates: bool,
         date_format: String,
         columns: Vec<ColumnKey>,
+        classify: bool,
     ) -> Self {
         let date_format_items = compile_date_format(&date_format);
         let filtered_columns: Vec<ColumnKey> = columns
@@ -64,6 +67,7 @@ impl LongFormatter {
             permission_format,
             hide_group,
             relative_dates,
+            classify,
             date_format_items,
             columns: final_columns,
             has_plugins_column,
@@ -123,6 +127,10 @@ impl FileFormatter for LongFormatter {
         }
         Ok(output)
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
 }
 
 impl LongFormatter {
@@ -162,6 +170,7 @@ impl LongFormatter {
             ColumnKey::Xattrs => fs_metadata::format_xattrs(metadata),
             ColumnKey::Context => fs_metadata::format_context(metadata),
             ColumnKey::Mount => fs_metadata::format_mount(metadata),
+            ColumnKey::Mime => entry_mime(entry, metadata).unwrap_or_else(|| "-".to_string()),
             ColumnKey::Name => self.render_name(entry, metadata, plugin_text),
             ColumnKey::Path => entry.path.clone(),
             ColumnKey::Plugins => plugin_text.to_string(),
@@ -180,13 +189,23 @@ impl LongFormatter {
         plugin_text: &str,
     ) -> String {
         let path = Path::new(&entry.path);
-        let colored_name = colorize_file_name(path).to_string();
+        let colored_name = colorize_file_name(path, Some(metadata)).to_string();
         let base_name = colorize_file_name_with_icon(
             path,
-            format_with_icon(path, colored_name, self.show_icons),
+            Some(metadata),
+            format_with_icon(path, Some(metadata), colored_name, self.show_icons),
         )
         .to_string();
-        let base_name = hyperlink::link_path(path, base_name);
+        let mut base_name = hyperlink::link_path(path, base_name);
+
+        let shows_target = metadata.is_symlink
+            && !entry.custom_fields.contains_key("hide_symlink_target")
+            && (entry.custom_fields.contains_key("symlink_target")
+                || entry.custom_fields.contains_key("invalid_symlink"));
+        // Like `ls -lF`, a symlink whose target is shown gets no `@`.
+        if self.classify && !shows_target {
+            base_name.push_str(classify_indicator(path, Some(metadata)));
+        }
 
         let with_target =
             if metadata.is_symlink && !entry.custom_fields.contains_key("hide_symlink_target") {
@@ -246,7 +265,7 @@ impl LongFormatter {
     }
 }
 
-fn compile_date_format(format: &str) -> Vec<Item<'static>> {
+pub(super) fn compile_date_format(format: &str) -> Vec<Item<'static>> {
     StrftimeItems::new(format)
         .parse_to_owned()
         .unwrap_or_else(|_| {
@@ -265,6 +284,7 @@ impl Default for LongFormatter {
             false,
             DEFAULT_LONG_DATE_FORMAT.to_string(),
             vec![ColumnKey::Name],
+            false,
         )
     }
 }
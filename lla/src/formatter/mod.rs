@@ -9,6 +9,24 @@ pub trait FileFormatter {
         plugin_manager: &mut PluginManager,
         depth: Option<usize>,
     ) -> Result<String>;
+
+    /// Whether this view can render a listing batch by batch. Views that need
+    /// every entry up front (grid column widths, sizemap percentages, tree
+    /// connectors) keep the default and are always handed the full listing.
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    /// Renders one batch of a streamed listing. Batches arrive one directory at
+    /// a time, already filtered, decorated and sorted.
+    fn format_batch(
+        &self,
+        files: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+        depth: Option<usize>,
+    ) -> Result<String> {
+        self.format_files(files, plugin_manager, depth)
+    }
 }
 
 pub mod column_config;
@@ -19,10 +37,12 @@ mod git;
 mod grid;
 pub mod json;
 mod long;
+pub mod quoting;
 mod recursive;
 pub mod serializable;
 mod sizemap;
 mod table;
+pub mod template;
 mod timeline;
 mod tree;
 
@@ -34,5 +54,6 @@ pub use long::LongFormatter;
 pub use recursive::RecursiveFormatter;
 pub use sizemap::SizeMapFormatter;
 pub use table::TableFormatter;
+pub use template::TemplateFormatter;
 pub use timeline::TimelineFormatter;
 pub use tree::TreeFormatter;
@@ -0,0 +1,123 @@
+use std::fmt::Write;
+
+/// How names are quoted in script-friendly output, following GNU ls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotingStyle {
+    /// Names are written unchanged.
+    Literal,
+    /// Names are quoted only when a POSIX shell would need it.
+    Shell,
+    /// Names are always double quoted with C escapes.
+    C,
+}
+
+impl QuotingStyle {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "literal" => Some(Self::Literal),
+            "shell" => Some(Self::Shell),
+            "c" => Some(Self::C),
+            _ => None,
+        }
+    }
+
+    pub fn quote(self, name: &str) -> String {
+        match self {
+            Self::Literal => name.to_string(),
+            Self::Shell => shell_quote(name),
+            Self::C => format!("\"{}\"", c_escape(name)),
+        }
+    }
+}
+
+/// Output meant for other programs rather than people: one record per entry,
+/// with no colors, icons, hyperlinks or plugin fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlainOutput {
+    pub quoting: QuotingStyle,
+    pub print0: bool,
+}
+
+impl PlainOutput {
+    /// Quotes each record and terminates it with a newline or, for `--print0`,
+    /// a NUL byte. Every record is terminated, including the last one.
+    pub fn records<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> String {
+        let terminator = if self.print0 { '\0' } else { '\n' };
+        let mut output = String::new();
+        for name in names {
+            output.push_str(&self.quoting.quote(name));
+            output.push(terminator);
+        }
+        output
+    }
+}
+
+fn shell_quote(name: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !name.is_empty() && name.chars().all(is_safe) {
+        return name.to_string();
+    }
+    if name.chars().any(char::is_control) {
+        // Single quotes cannot carry control characters in a readable way, so
+        // fall back to ANSI-C quoting, which bash, zsh and ksh understand.
+        return format!("$'{}'", c_escape(name).replace('\'', "\\'"));
+    }
+    format!("'{}'", name.replace('\'', "'\\''"))
+}
+
+fn c_escape(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\x07' => escaped.push_str("\\a"),
+            '\x08' => escaped.push_str("\\b"),
+            '\x0c' => escaped.push_str("\\f"),
+            '\x0b' => escaped.push_str("\\v"),
+            c if c.is_control() => {
+                let mut buffer = [0u8; 4];
+                for byte in c.encode_utf8(&mut buffer).bytes() {
+                    let _ = write!(escaped, "\\{:03o}", byte);
+                }
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_quoting_only_quotes_when_needed() {
+        assert_eq!(QuotingStyle::Shell.quote("src/main.rs"), "src/main.rs");
+        assert_eq!(QuotingStyle::Shell.quote("my file"), "'my file'");
+        assert_eq!(QuotingStyle::Shell.quote("it's"), "'it'\\''s'");
+        assert_eq!(QuotingStyle::Shell.quote("a\nb"), "$'a\\nb'");
+    }
+
+    #[test]
+    fn c_quoting_escapes_specials() {
+        assert_eq!(QuotingStyle::C.quote("plain"), "\"plain\"");
+        assert_eq!(
+            QuotingStyle::C.quote("say \"hi\"\t"),
+            "\"say \\\"hi\\\"\\t\""
+        );
+        assert_eq!(QuotingStyle::C.quote("bell\x01"), "\"bell\\001\"");
+    }
+
+    #[test]
+    fn print0_terminates_every_record() {
+        let plain = PlainOutput {
+            quoting: QuotingStyle::Literal,
+            print0: true,
+        };
+        assert_eq!(plain.records(["a b", "c\nd"]), "a b\0c\nd\0");
+    }
+}
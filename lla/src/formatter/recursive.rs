@@ -1,3 +1,4 @@
+use super::quoting::PlainOutput;
 use super::FileFormatter;
 use crate::error::Result;
 use crate::plugin::PluginManager;
@@ -9,16 +10,29 @@ use chrono::{DateTime, Local};
 use colored::*;
 use lla_plugin_interface::proto::DecoratedEntry;
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, UNIX_EPOCH};
 
 pub struct RecursiveFormatter {
     pub show_icons: bool,
+    pub plain: Option<PlainOutput>,
+    /// The listed operands. Their own entries are left out, since each
+    /// directory's header already names it.
+    pub roots: Vec<PathBuf>,
 }
 
 impl RecursiveFormatter {
-    pub fn new(show_icons: bool) -> Self {
-        Self { show_icons }
+    pub fn new(show_icons: bool, plain: Option<PlainOutput>, roots: Vec<PathBuf>) -> Self {
+        Self {
+            show_icons,
+            plain,
+            roots,
+        }
+    }
+
+    fn is_root(&self, entry: &DecoratedEntry) -> bool {
+        self.roots.iter().any(|root| root == Path::new(&entry.path))
     }
 
     fn format_date(timestamp: u64) -> ColoredString {
@@ -38,28 +52,47 @@ impl RecursiveFormatter {
         theme::color_value_to_color(&ColorValue::Named("bright black".to_string()))
     }
 
-    fn print_entry(&self, entry: &DecoratedEntry, plugin_manager: &mut PluginManager) {
+    fn write_entry(
+        &self,
+        output: &mut String,
+        entry: &DecoratedEntry,
+        plugin_manager: &mut PluginManager,
+    ) {
         if let Some(metadata) = &entry.metadata {
             let date = Self::format_date(metadata.modified);
             let path = Path::new(&entry.path);
-            let colored_name = colorize_file_name(path).to_string();
+            let colored_name = colorize_file_name(path, Some(metadata)).to_string();
             let name = colorize_file_name_with_icon(
                 path,
-                format_with_icon(path, colored_name, self.show_icons),
+                Some(metadata),
+                format_with_icon(path, Some(metadata), colored_name, self.show_icons),
             )
             .to_string();
             let name = hyperlink::link_path(path, name);
 
-            print!("{:>16} │ {}", date, name);
+            let _ = write!(output, "{:>16} │ {}", date, name);
 
             let plugin_fields = plugin_manager.format_fields(entry, "recursive").join(" ");
             if !plugin_fields.is_empty() {
-                print!(" {}", plugin_fields);
+                let _ = write!(output, " {}", plugin_fields);
             }
-            println!();
+            output.push('\n');
         }
     }
 
+    fn write_header(output: &mut String, parent: &str) {
+        let _ = writeln!(
+            output,
+            "\n{}",
+            parent.color(Self::get_header_color()).bold()
+        );
+        let _ = writeln!(
+            output,
+            "{}",
+            "─".repeat(40).color(Self::get_separator_color())
+        );
+    }
+
     fn get_parent_path(path: &str) -> String {
         Path::new(path)
             .parent()
@@ -79,27 +112,119 @@ impl FileFormatter for RecursiveFormatter {
         if files.is_empty() {
             return Ok(String::new());
         }
+        // Script-friendly output lists full paths without headers or dates.
+        if let Some(plain) = self.plain {
+            return Ok(plain.records(files.iter().map(|file| file.path.as_str())));
+        }
         plugin_manager.prepare_format_fields(files, "recursive");
 
         let mut groups: BTreeMap<String, Vec<&DecoratedEntry>> = BTreeMap::new();
 
-        for file in files {
+        for file in files.iter().filter(|file| !self.is_root(file)) {
             let parent = Self::get_parent_path(&file.path);
             groups.entry(parent).or_default().push(file);
         }
 
         let single_group = groups.len() == 1;
 
+        let mut output = String::new();
         for (parent, entries) in &groups {
             if !(single_group && parent == ".") {
-                println!("\n{}", parent.color(Self::get_header_color()).bold());
-                println!("{}", "─".repeat(40).color(Self::get_separator_color()));
+                Self::write_header(&mut output, parent);
             }
 
             for entry in entries {
-                self.print_entry(entry, plugin_manager);
+                self.write_entry(&mut output, entry, plugin_manager);
             }
         }
-        Ok(String::new())
+        Ok(output)
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn format_batch(
+        &self,
+        files: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+        _depth: Option<usize>,
+    ) -> Result<String> {
+        if files.is_empty() {
+            return Ok(String::new());
+        }
+        if let Some(plain) = self.plain {
+            return Ok(plain.records(files.iter().map(|file| file.path.as_str())));
+        }
+        plugin_manager.prepare_format_fields(files, "recursive");
+
+        // A streamed batch is normally a single directory, but group consecutive
+        // runs anyway so listers that fall back to one batch still get headers.
+        let mut output = String::new();
+        let mut current_parent: Option<String> = None;
+        for file in files.iter().filter(|file| !self.is_root(file)) {
+            let parent = Self::get_parent_path(&file.path);
+            if current_parent.as_deref() != Some(parent.as_str()) {
+                Self::write_header(&mut output, &parent);
+                current_parent = Some(parent);
+            }
+            self.write_entry(&mut output, file, plugin_manager);
+        }
+        // The caller ends each printed batch with a newline.
+        output.pop();
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::lister::{FileLister, RecursiveLister};
+    use crate::utils::fs_metadata;
+    use crate::utils::llaignore::LlaIgnoreMode;
+
+    #[test]
+    fn streamed_listing_opens_with_the_root_header() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("sub")).unwrap();
+        std::fs::write(root.path().join("sub/file"), b"").unwrap();
+        let directory = root.path().to_str().unwrap();
+
+        let lister = RecursiveLister::new(Config::default(), false, LlaIgnoreMode::Off);
+        let mut batches = Vec::new();
+        lister
+            .list_files_batched(directory, true, None, &mut |batch| {
+                batches.push(batch);
+                Ok(())
+            })
+            .unwrap();
+
+        let formatter = RecursiveFormatter::new(false, None, vec![PathBuf::from(directory)]);
+        let mut plugin_manager = PluginManager::new(Config::default());
+        let first: Vec<DecoratedEntry> = batches[0]
+            .iter()
+            .map(|path| DecoratedEntry {
+                path: path.to_string_lossy().into_owned(),
+                metadata: Some(fs_metadata::from_metadata(
+                    &std::fs::symlink_metadata(path).unwrap(),
+                )),
+                ..Default::default()
+            })
+            .collect();
+        let output = formatter
+            .format_batch(&first, &mut plugin_manager, None)
+            .unwrap();
+        let stripped = strip_ansi_escapes::strip(output).unwrap();
+        let lines: Vec<String> = String::from_utf8_lossy(&stripped)
+            .lines()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+
+        assert_eq!(lines.len(), 4, "{:?}", lines);
+        assert_eq!(lines[0], "");
+        assert_eq!(lines[1], directory);
+        assert_eq!(lines[2], "─".repeat(40));
+        assert!(lines[3].ends_with("│ sub/"), "{:?}", lines[3]);
     }
 }
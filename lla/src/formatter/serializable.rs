@@ -1,4 +1,6 @@
-use lla_plugin_interface::proto::DecoratedEntry;
+use crate::utils::content_type;
+use crate::utils::fs_metadata::entry_kind;
+use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata, FileKind};
 use serde::Serialize;
 use std::collections::{BTreeMap, HashMap};
 use std::fs;
@@ -12,17 +14,57 @@ use std::sync::Mutex;
 #[cfg(unix)]
 use users::{get_group_by_gid, get_user_by_uid};
 
+// Ids without a name are cached too, so each id is looked up once.
 #[cfg(unix)]
-static USER_CACHE: Lazy<Mutex<HashMap<u32, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static USER_CACHE: Lazy<Mutex<HashMap<u32, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 #[cfg(unix)]
-static GROUP_CACHE: Lazy<Mutex<HashMap<u32, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static GROUP_CACHE: Lazy<Mutex<HashMap<u32, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Prefix reserved for custom fields lla sets itself, so they cannot collide
+/// with fields a plugin emits. They are never reported as plugin fields.
+pub const INTERNAL_FIELD_PREFIX: &str = "lla:";
+
+/// Custom field carrying the path operand an entry was listed from, lifted
+/// into `SerializableEntry::operand`.
+pub const OPERAND_FIELD: &str = "lla:operand";
+
+/// Custom field carrying the content type sniffed for `--type` or `--mime`,
+/// lifted into `SerializableEntry::mime`.
+pub const MIME_FIELD: &str = "lla:mime";
+
+/// The content type recorded while filtering, or sniffed now when the entry
+/// was never sniffed.
+pub fn entry_mime(entry: &DecoratedEntry, metadata: &EntryMetadata) -> Option<String> {
+    entry
+        .custom_fields
+        .get(MIME_FIELD)
+        .cloned()
+        .or_else(|| content_type::mime(Path::new(&entry.path), metadata).map(String::from))
+}
+
+/// Custom fields carrying a fuzzy query's score and the comma-separated
+/// character positions it matched in the name, lifted into
+/// `SerializableEntry::fuzzy`.
+pub const FUZZY_SCORE_FIELD: &str = "fuzzy_score";
+pub const FUZZY_POSITIONS_FIELD: &str = "fuzzy_positions";
+
+#[derive(Debug, Serialize)]
+pub struct SerializableFuzzyMatch {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
 
 #[derive(Debug, Serialize)]
 pub struct SerializableEntry {
     pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operand: Option<String>,
     pub name: String,
     pub extension: Option<String>,
     pub file_type: String,
+    pub mime: Option<String>,
     pub size_bytes: u64,
     pub modified: String,
     pub created: Option<String>,
@@ -42,6 +84,7 @@ pub struct SerializableEntry {
     pub symlink_target: Option<String>,
     pub is_hidden: bool,
     pub git_status: Option<String>,
+    pub fuzzy: Option<SerializableFuzzyMatch>,
     pub plugin: HashMap<String, serde_json::Value>,
 }
 
@@ -64,48 +107,34 @@ fn mode_to_octal(permissions: u32) -> String {
 }
 
 #[cfg(unix)]
-fn uid_to_name(uid: u32) -> Option<String> {
-    if uid == 0 && get_user_by_uid(uid).is_none() {
-        return None;
-    }
+pub(crate) fn uid_to_name(uid: u32) -> Option<String> {
     let Ok(mut cache) = USER_CACHE.lock() else {
         return get_user_by_uid(uid).map(|u| u.name().to_string_lossy().into_owned());
     };
-    if let Some(name) = cache.get(&uid) {
-        return Some(name.clone());
-    }
-    let name = get_user_by_uid(uid).map(|u| u.name().to_string_lossy().into_owned());
-    if let Some(ref n) = name {
-        cache.insert(uid, n.clone());
-    }
-    name
+    cache
+        .entry(uid)
+        .or_insert_with(|| get_user_by_uid(uid).map(|u| u.name().to_string_lossy().into_owned()))
+        .clone()
 }
 
 #[cfg(windows)]
-fn uid_to_name(_uid: u32) -> Option<String> {
+pub(crate) fn uid_to_name(_uid: u32) -> Option<String> {
     None
 }
 
 #[cfg(unix)]
-fn gid_to_name(gid: u32) -> Option<String> {
-    if gid == 0 && get_group_by_gid(gid).is_none() {
-        return None;
-    }
+pub(crate) fn gid_to_name(gid: u32) -> Option<String> {
     let Ok(mut cache) = GROUP_CACHE.lock() else {
         return get_group_by_gid(gid).map(|g| g.name().to_string_lossy().into_owned());
     };
-    if let Some(name) = cache.get(&gid) {
-        return Some(name.clone());
-    }
-    let name = get_group_by_gid(gid).map(|g| g.name().to_string_lossy().into_owned());
-    if let Some(ref n) = name {
-        cache.insert(gid, n.clone());
-    }
-    name
+    cache
+        .entry(gid)
+        .or_insert_with(|| get_group_by_gid(gid).map(|g| g.name().to_string_lossy().into_owned()))
+        .clone()
 }
 
 #[cfg(windows)]
-fn gid_to_name(_gid: u32) -> Option<String> {
+pub(crate) fn gid_to_name(_gid: u32) -> Option<String> {
     None
 }
 
@@ -125,12 +154,17 @@ pub fn to_serializable(entry: &DecoratedEntry, git_status: Option<String>) -> Se
 
     let file_type = if md.is_symlink {
         "symlink"
-    } else if md.is_dir {
-        "dir"
-    } else if md.is_file {
-        "file"
     } else {
-        "other"
+        match entry_kind(&md) {
+            FileKind::Regular => "file",
+            FileKind::Directory => "dir",
+            FileKind::Symlink => "symlink",
+            FileKind::Fifo => "fifo",
+            FileKind::Socket => "socket",
+            FileKind::BlockDevice => "block_device",
+            FileKind::CharDevice => "char_device",
+            FileKind::Unknown => "other",
+        }
     }
     .to_string();
 
@@ -157,6 +191,11 @@ pub fn to_serializable(entry: &DecoratedEntry, git_status: Option<String>) -> Se
 
     let mut plugin: HashMap<String, serde_json::Value> = HashMap::new();
     for (k, v) in &entry.custom_fields {
+        if k.starts_with(INTERNAL_FIELD_PREFIX)
+            || [FUZZY_SCORE_FIELD, FUZZY_POSITIONS_FIELD].contains(&k.as_str())
+        {
+            continue;
+        }
         plugin.insert(k.clone(), serde_json::Value::String(v.clone()));
     }
     fn typed_value_to_json(value: &lla_plugin_interface::proto::TypedValue) -> serde_json::Value {
@@ -206,9 +245,11 @@ pub fn to_serializable(entry: &DecoratedEntry, git_status: Option<String>) -> Se
 
     SerializableEntry {
         path: entry.path.clone(),
+        operand: entry.custom_fields.get(OPERAND_FIELD).cloned(),
         name,
         extension,
         file_type,
+        mime: entry.custom_fields.get(MIME_FIELD).cloned(),
         size_bytes: md.size,
         modified: fmt_ts_required(md.modified),
         created: fmt_ts_opt(md.created),
@@ -228,10 +269,26 @@ pub fn to_serializable(entry: &DecoratedEntry, git_status: Option<String>) -> Se
         symlink_target,
         is_hidden,
         git_status,
+        fuzzy: fuzzy_match(entry),
         plugin,
     }
 }
 
+fn fuzzy_match(entry: &DecoratedEntry) -> Option<SerializableFuzzyMatch> {
+    let score = entry.custom_fields.get(FUZZY_SCORE_FIELD)?.parse().ok()?;
+    let positions = entry
+        .custom_fields
+        .get(FUZZY_POSITIONS_FIELD)
+        .map(|positions| {
+            positions
+                .split(',')
+                .filter_map(|position| position.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(SerializableFuzzyMatch { score, positions })
+}
+
 pub fn find_git_root(start: &Path) -> Option<PathBuf> {
     let mut dir = start;
     loop {
@@ -242,15 +299,19 @@ pub fn find_git_root(start: &Path) -> Option<PathBuf> {
     }
 }
 
-pub fn get_git_status_map(workspace_root: &Path) -> HashMap<String, String> {
+/// Porcelain `XY` codes by repository-relative path, with `.` for clean
+/// tracked files. Ignored paths get `!!` when `include_ignored` is set; an
+/// ignored directory is reported once, with a trailing `/`.
+pub fn get_git_status_map(workspace_root: &Path, include_ignored: bool) -> HashMap<String, String> {
     use std::process::Command;
     let mut status_map = HashMap::new();
 
-    if let Ok(output) = Command::new("git")
-        .args(["status", "--porcelain=v2", "--untracked-files=all"])
-        .current_dir(workspace_root)
-        .output()
-    {
+    let mut command = Command::new("git");
+    command.args(["status", "--porcelain=v2", "--untracked-files=all"]);
+    if include_ignored {
+        command.arg("--ignored=matching");
+    }
+    if let Ok(output) = command.current_dir(workspace_root).output() {
         for line in String::from_utf8_lossy(&output.stdout).lines() {
             if line.is_empty() || line.starts_with('#') {
                 continue;
@@ -262,10 +323,15 @@ pub fn get_git_status_map(workspace_root: &Path) -> HashMap<String, String> {
             }
 
             match parts[0] {
-                "1" | "2" if parts.len() >= 9 => {
-                    let xy = parts[1];
-                    let path = parts[8];
-                    status_map.insert(path.to_string(), xy.to_string());
+                "1" if parts.len() >= 9 => {
+                    status_map.insert(parts[8].to_string(), parts[1].to_string());
+                }
+                // Renames end with the new path and the original one.
+                "2" if parts.len() >= 11 => {
+                    status_map.insert(parts[9].to_string(), parts[1].to_string());
+                }
+                "u" if parts.len() >= 11 => {
+                    status_map.insert(parts[10].to_string(), parts[1].to_string());
                 }
                 "?" if parts.len() >= 2 => {
                     status_map.insert(parts[1].to_string(), "??".to_string());
@@ -3,6 +3,7 @@ use crate::error::Result;
 use crate::plugin::PluginManager;
 use crate::theme::{self, ColorValue};
 use crate::utils::color::{self, colorize_file_name, colorize_file_name_with_icon};
+use crate::utils::dir_size::SizeBasis;
 use crate::utils::hyperlink;
 use crate::utils::icons::format_with_icon;
 use colored::*;
@@ -13,11 +14,12 @@ use unicode_width::UnicodeWidthStr;
 
 pub struct SizeMapFormatter {
     pub show_icons: bool,
+    pub basis: SizeBasis,
 }
 
 impl SizeMapFormatter {
-    pub fn new(show_icons: bool) -> Self {
-        Self { show_icons }
+    pub fn new(show_icons: bool, basis: SizeBasis) -> Self {
+        Self { show_icons, basis }
     }
 
     fn strip_ansi(s: &str) -> String {
@@ -32,15 +34,20 @@ impl SizeMapFormatter {
         files: &[DecoratedEntry],
         total_size: u64,
         term_width: usize,
+        basis: SizeBasis,
     ) -> (usize, usize, usize) {
         let max_name_width = (term_width as f64 * 0.3) as usize;
         let name_width = files
             .iter()
             .map(|f| {
                 let path = Path::new(&f.path);
-                let colored_name = colorize_file_name(path).to_string();
-                let name_with_icon =
-                    colorize_file_name_with_icon(path, format_with_icon(path, colored_name, true));
+                let metadata = f.metadata.as_ref();
+                let colored_name = colorize_file_name(path, metadata).to_string();
+                let name_with_icon = colorize_file_name_with_icon(
+                    path,
+                    metadata,
+                    format_with_icon(path, metadata, colored_name, true),
+                );
                 Self::visible_width(&name_with_icon)
             })
             .max()
@@ -50,7 +57,7 @@ impl SizeMapFormatter {
         let size_width = files
             .iter()
             .map(|f| {
-                let size = f.metadata.as_ref().map_or(0, |m| m.size);
+                let size = f.metadata.as_ref().map_or(0, |m| basis.of(m));
                 Self::visible_width(&format_size(size))
             })
             .chain(std::iter::once(Self::visible_width(&format_size(
@@ -206,25 +213,28 @@ impl FileFormatter for SizeMapFormatter {
 
         let total_size: u64 = files
             .iter()
-            .map(|f| f.metadata.as_ref().map_or(0, |m| m.size))
+            .map(|f| f.metadata.as_ref().map_or(0, |m| self.basis.of(m)))
             .sum();
 
         let (name_width, size_width, bar_width) =
-            Self::calculate_layout(files, total_size, term_width);
+            Self::calculate_layout(files, total_size, term_width, self.basis);
 
         let mut output = String::new();
         output.push('\n');
 
         let mut files = files.to_vec();
-        files.sort_by_key(|f| std::cmp::Reverse(f.metadata.as_ref().map_or(0, |m| m.size)));
+        files.sort_by_key(|f| {
+            std::cmp::Reverse(f.metadata.as_ref().map_or(0, |m| self.basis.of(m)))
+        });
 
         for file in &files {
             let path = Path::new(&file.path);
-            let colored_name = colorize_file_name(path).to_string();
-            let name = format_with_icon(path, colored_name, self.show_icons);
+            let colored_name = colorize_file_name(path, file.metadata.as_ref()).to_string();
+            let name =
+                format_with_icon(path, file.metadata.as_ref(), colored_name, self.show_icons);
             let name = hyperlink::link_path(path, name);
             let metadata = file.metadata.as_ref().cloned().unwrap_or_default();
-            let size = metadata.size;
+            let size = self.basis.of(&metadata);
             let size_str = format_size(size);
             let percentage = if total_size > 0 {
                 (size as f64 / total_size as f64) * 100.0
@@ -346,7 +356,8 @@ mod tests {
             },
         ];
 
-        let (_, size_width, _) = SizeMapFormatter::calculate_layout(&files, 1800, 80);
+        let (_, size_width, _) =
+            SizeMapFormatter::calculate_layout(&files, 1800, 80, SizeBasis::Apparent);
 
         assert!(size_width >= SizeMapFormatter::visible_width("1.8 KB"));
     }
@@ -1,4 +1,5 @@
 use super::column_config::ColumnKey;
+use super::serializable::entry_mime;
 use super::FileFormatter;
 use crate::error::Result;
 use crate::plugin::PluginManager;
@@ -198,6 +199,7 @@ impl TableFormatter {
             ColumnKey::Xattrs => fs_metadata::format_xattrs(metadata),
             ColumnKey::Context => fs_metadata::format_context(metadata),
             ColumnKey::Mount => fs_metadata::format_mount(metadata),
+            ColumnKey::Mime => entry_mime(entry, metadata).unwrap_or_else(|| "-".to_string()),
             ColumnKey::Name => self.render_name(entry),
             ColumnKey::Path => entry.path.clone(),
             ColumnKey::Plugins => plugin_text.to_string(),
@@ -211,10 +213,12 @@ impl TableFormatter {
 
     fn render_name(&self, entry: &DecoratedEntry) -> String {
         let path = Path::new(&entry.path);
-        let colored_name = colorize_file_name(path).to_string();
+        let metadata = entry.metadata.as_ref();
+        let colored_name = colorize_file_name(path, metadata).to_string();
         let name = colorize_file_name_with_icon(
             path,
-            format_with_icon(path, colored_name, self.show_icons),
+            metadata,
+            format_with_icon(path, metadata, colored_name, self.show_icons),
         )
         .to_string();
         hyperlink::link_path(path, name)
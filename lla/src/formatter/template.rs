@@ -0,0 +1,629 @@
+use super::column_config::ColumnKey;
+use super::long::compile_date_format;
+use super::quoting::PlainOutput;
+use super::serializable::{entry_mime, gid_to_name, uid_to_name};
+use super::FileFormatter;
+use crate::error::{LlaError, Result};
+use crate::plugin::PluginManager;
+use crate::utils::color::*;
+use crate::utils::icons::get_icon;
+use crate::utils::{fs_metadata, hyperlink};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use lla_plugin_interface::proto::typed_value::Value;
+use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata, TypedValue};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use unicode_width::UnicodeWidthStr;
+
+const PERMISSION_FORMATS: [&str; 6] = [
+    "symbolic", "octal", "binary", "numeric", "verbose", "compact",
+];
+
+/// A user-defined line format such as
+/// `{icon} {name:<40} {size:>8h} {modified:%Y-%m-%d} {field:git_status}`.
+///
+/// Placeholders name a long-view column or `icon`, optionally followed by
+/// `:` and an alignment (`<`, `>` or `^`), a width and a style: `h` for
+/// human-readable sizes, a strftime format or `relative` for dates, and a
+/// permission format for `permissions`. `{?key}…{/}` renders its contents
+/// only when `key` has a value and `{!key}…{/}` only when it has none.
+/// `{{` and `}}` stand for literal braces.
+#[derive(Clone, Debug)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug)]
+enum Segment {
+    Text(String),
+    Value(Placeholder),
+    Section {
+        key: Key,
+        negated: bool,
+        body: Vec<Segment>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Key {
+    Icon,
+    Column(ColumnKey),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Clone, Debug)]
+enum Style {
+    Default,
+    HumanSize,
+    RelativeDate,
+    Date(Vec<Item<'static>>),
+    Permissions(String),
+}
+
+#[derive(Clone, Debug)]
+struct Placeholder {
+    key: Key,
+    align: Align,
+    width: usize,
+    style: Style,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        Self::parse_segments(source)
+            .map(|segments| Template { segments })
+            .map_err(|message| {
+                LlaError::Parse(format!("Invalid format template '{}': {}", source, message))
+            })
+    }
+
+    fn parse_segments(source: &str) -> std::result::Result<Vec<Segment>, String> {
+        let mut segments = Vec::new();
+        let mut open: Vec<(Key, bool, Vec<Segment>)> = Vec::new();
+        let mut text = String::new();
+        let mut rest = source;
+
+        while let Some(c) = rest.chars().next() {
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                text.push(c);
+                rest = &rest[2..];
+                continue;
+            }
+            if c == '}' {
+                return Err("unmatched '}' (write '}}' for a literal brace)".to_string());
+            }
+            if c != '{' {
+                text.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+
+            let end = rest
+                .find('}')
+                .ok_or_else(|| format!("unclosed placeholder '{}'", rest))?;
+            let inner = &rest[1..end];
+            rest = &rest[end + 1..];
+            if inner.contains('{') {
+                return Err(format!("unexpected '{{' in placeholder '{{{}}}'", inner));
+            }
+
+            let current = match open.last_mut() {
+                Some((_, _, body)) => body,
+                None => &mut segments,
+            };
+            if !text.is_empty() {
+                current.push(Segment::Text(std::mem::take(&mut text)));
+            }
+
+            if let Some(key) = inner.strip_prefix('?') {
+                open.push((parse_key(key)?, false, Vec::new()));
+            } else if let Some(key) = inner.strip_prefix('!') {
+                open.push((parse_key(key)?, true, Vec::new()));
+            } else if inner.starts_with('/') {
+                let (key, negated, body) = open
+                    .pop()
+                    .ok_or_else(|| format!("'{{{}}}' closes no section", inner))?;
+                let section = Segment::Section { key, negated, body };
+                match open.last_mut() {
+                    Some((_, _, body)) => body.push(section),
+                    None => segments.push(section),
+                }
+            } else {
+                current.push(Segment::Value(Placeholder::parse(inner)?));
+            }
+        }
+
+        if let Some((key, negated, _)) = open.last() {
+            return Err(format!(
+                "section '{{{}{}}}' is never closed with '{{/}}'",
+                if *negated { '!' } else { '?' },
+                key_name(key)
+            ));
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(segments)
+    }
+
+    fn uses_plugins(&self) -> bool {
+        fn any_plugins(segments: &[Segment]) -> bool {
+            segments.iter().any(|segment| match segment {
+                Segment::Text(_) => false,
+                Segment::Value(placeholder) => placeholder.key == Key::Column(ColumnKey::Plugins),
+                Segment::Section { key, body, .. } => {
+                    *key == Key::Column(ColumnKey::Plugins) || any_plugins(body)
+                }
+            })
+        }
+        any_plugins(&self.segments)
+    }
+}
+
+fn parse_key(raw: &str) -> std::result::Result<Key, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err("empty placeholder".to_string());
+    }
+    if raw.eq_ignore_ascii_case("icon") {
+        return Ok(Key::Icon);
+    }
+    match ColumnKey::from_config(raw) {
+        ColumnKey::CustomField(field) if field.is_empty() => {
+            Err(format!("'{}' is missing a field name", raw))
+        }
+        ColumnKey::CustomField(_) if !raw.starts_with("field:") => Err(format!(
+            "unknown placeholder '{}' (use field:{} for a plugin field)",
+            raw, raw
+        )),
+        column => Ok(Key::Column(column)),
+    }
+}
+
+fn key_name(key: &Key) -> String {
+    match key {
+        Key::Icon => "icon".to_string(),
+        Key::Column(ColumnKey::CustomField(field)) => format!("field:{}", field),
+        Key::Column(column) => column.header_label().to_lowercase(),
+    }
+}
+
+impl Placeholder {
+    fn parse(inner: &str) -> std::result::Result<Self, String> {
+        // `field:<name>` keeps its own colon, so the spec starts after it.
+        let (key, spec) = match inner.strip_prefix("field:") {
+            Some(field) => match field.split_once(':') {
+                Some((field, spec)) => (&inner[..6 + field.len()], spec),
+                None => (inner, ""),
+            },
+            None => inner.split_once(':').unwrap_or((inner, "")),
+        };
+        let key = parse_key(key)?;
+
+        let (align, spec) = match spec.chars().next() {
+            Some('<') => (Align::Left, &spec[1..]),
+            Some('>') => (Align::Right, &spec[1..]),
+            Some('^') => (Align::Center, &spec[1..]),
+            _ => match &key {
+                Key::Column(column) if column.align_right() => (Align::Right, spec),
+                _ => (Align::Left, spec),
+            },
+        };
+        let digits = spec
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(spec.len());
+        let width = spec[..digits].parse().unwrap_or(0);
+        let style = parse_style(&key, &spec[digits..])?;
+
+        Ok(Placeholder {
+            key,
+            align,
+            width,
+            style,
+        })
+    }
+}
+
+fn parse_style(key: &Key, style: &str) -> std::result::Result<Style, String> {
+    if style.is_empty() {
+        return Ok(Style::Default);
+    }
+    match key {
+        Key::Column(ColumnKey::Size | ColumnKey::AllocatedSize) if style == "h" => {
+            Ok(Style::HumanSize)
+        }
+        Key::Column(ColumnKey::Size | ColumnKey::AllocatedSize) => Err(format!(
+            "'{}' is not a size style (use h for human-readable sizes)",
+            style
+        )),
+        Key::Column(ColumnKey::Modified | ColumnKey::Created | ColumnKey::Accessed) => {
+            if style == "relative" {
+                return Ok(Style::RelativeDate);
+            }
+            StrftimeItems::new(style)
+                .parse_to_owned()
+                .map(Style::Date)
+                .map_err(|_| format!("'{}' is not a valid date format", style))
+        }
+        Key::Column(ColumnKey::Permissions) if PERMISSION_FORMATS.contains(&style) => {
+            Ok(Style::Permissions(style.to_string()))
+        }
+        Key::Column(ColumnKey::Permissions) => Err(format!(
+            "'{}' is not a permission format (use one of: {})",
+            style,
+            PERMISSION_FORMATS.join(", ")
+        )),
+        key => Err(format!(
+            "'{}' takes no style, got '{}'",
+            key_name(key),
+            style
+        )),
+    }
+}
+
+/// Renders every entry as one line of a `Template`, with theme colors applied
+/// to each placeholder. With `plain`, each line is quoted and terminated as a
+/// record instead.
+pub struct TemplateFormatter {
+    template: Template,
+    plain: Option<PlainOutput>,
+    permission_format: String,
+    date_format_items: Vec<Item<'static>>,
+    relative_dates: bool,
+    classify: bool,
+}
+
+impl TemplateFormatter {
+    pub fn new(
+        template: Template,
+        plain: Option<PlainOutput>,
+        permission_format: String,
+        date_format: &str,
+        relative_dates: bool,
+        classify: bool,
+    ) -> Self {
+        Self {
+            template,
+            plain,
+            permission_format,
+            date_format_items: compile_date_format(date_format),
+            relative_dates,
+            classify,
+        }
+    }
+
+    fn render_entry(&self, entry: &DecoratedEntry, plugin_text: &str) -> String {
+        let metadata = entry.metadata.clone().unwrap_or_default();
+        let mut line = String::new();
+        self.render_segments(
+            &self.template.segments,
+            entry,
+            &metadata,
+            plugin_text,
+            &mut line,
+        );
+        line
+    }
+
+    fn render_segments(
+        &self,
+        segments: &[Segment],
+        entry: &DecoratedEntry,
+        metadata: &EntryMetadata,
+        plugin_text: &str,
+        line: &mut String,
+    ) {
+        for segment in segments {
+            match segment {
+                Segment::Text(text) => line.push_str(text),
+                Segment::Value(placeholder) => {
+                    let value = self
+                        .value(
+                            &placeholder.key,
+                            &placeholder.style,
+                            entry,
+                            metadata,
+                            plugin_text,
+                        )
+                        .unwrap_or_else(|| "-".to_string());
+                    line.push_str(&pad(&value, placeholder.width, placeholder.align));
+                }
+                Segment::Section { key, negated, body } => {
+                    let present = self
+                        .value(key, &Style::Default, entry, metadata, plugin_text)
+                        .is_some();
+                    if present != *negated {
+                        self.render_segments(body, entry, metadata, plugin_text, line);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The colored text for `key`, or `None` when the entry has no value.
+    fn value(
+        &self,
+        key: &Key,
+        style: &Style,
+        entry: &DecoratedEntry,
+        metadata: &EntryMetadata,
+        plugin_text: &str,
+    ) -> Option<String> {
+        let path = Path::new(&entry.path);
+        let column = match key {
+            Key::Icon => {
+                return Some(
+                    colorize_as_file(path, Some(metadata), get_icon(path).to_string()).to_string(),
+                )
+            }
+            Key::Column(column) => column,
+        };
+        let known = |text: String| (!text.is_empty() && text != "-").then_some(text);
+
+        match column {
+            ColumnKey::Permissions => {
+                let format = match style {
+                    Style::Permissions(format) => format,
+                    _ => &self.permission_format,
+                };
+                let mut rendered = colorize_permissions(metadata.permissions, Some(format));
+                if metadata.has_acl {
+                    rendered.push('+');
+                }
+                Some(rendered)
+            }
+            ColumnKey::Inode => known(fs_metadata::format_inode(metadata)),
+            ColumnKey::HardLinks => known(fs_metadata::format_hard_links(metadata)),
+            ColumnKey::Size => Some(format_size(metadata.size, style)),
+            ColumnKey::AllocatedSize => {
+                fs_metadata::allocated_size(metadata).map(|size| format_size(size, style))
+            }
+            ColumnKey::Modified => self.format_timestamp(metadata.modified, style),
+            ColumnKey::Created => self.format_timestamp(metadata.created, style),
+            ColumnKey::Accessed => self.format_timestamp(metadata.accessed, style),
+            ColumnKey::User => {
+                let user = uid_to_name(metadata.uid).unwrap_or_else(|| metadata.uid.to_string());
+                Some(colorize_user(&user).to_string())
+            }
+            ColumnKey::Group => {
+                let group = gid_to_name(metadata.gid).unwrap_or_else(|| metadata.gid.to_string());
+                Some(colorize_group(&group).to_string())
+            }
+            ColumnKey::Xattrs => known(fs_metadata::format_xattrs(metadata)),
+            ColumnKey::Context => known(fs_metadata::format_context(metadata)),
+            ColumnKey::Mount => known(fs_metadata::format_mount(metadata)),
+            ColumnKey::Mime => entry_mime(entry, metadata)
+                .map(|mime| colorize_as_file(path, Some(metadata), mime).to_string()),
+            ColumnKey::Name => {
+                let name = colorize_file_name(path, Some(metadata)).to_string();
+                let mut name = hyperlink::link_path(path, name);
+                if self.classify {
+                    name.push_str(classify_indicator(path, Some(metadata)));
+                }
+                Some(name)
+            }
+            ColumnKey::Path => {
+                Some(colorize_as_file(path, Some(metadata), entry.path.clone()).to_string())
+            }
+            ColumnKey::Plugins => known(plugin_text.to_string()),
+            ColumnKey::CustomField(field) => entry
+                .custom_fields
+                .get(field)
+                .cloned()
+                .or_else(|| entry.typed_fields.get(field).and_then(typed_text))
+                .and_then(known)
+                .map(|text| colorize_as_file(path, Some(metadata), text).to_string()),
+        }
+    }
+
+    fn format_timestamp(&self, seconds: u64, style: &Style) -> Option<String> {
+        if seconds == 0 {
+            return None;
+        }
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+        let items = match style {
+            Style::RelativeDate => return Some(colorize_date_relative(&time).to_string()),
+            Style::Default if self.relative_dates => {
+                return Some(colorize_date_relative(&time).to_string())
+            }
+            Style::Date(items) => items,
+            _ => &self.date_format_items,
+        };
+        let datetime: DateTime<Local> = time.into();
+        let formatted = datetime.format_with_items(items.iter()).to_string();
+        Some(colorize_date_text(formatted).to_string())
+    }
+}
+
+impl FileFormatter for TemplateFormatter {
+    fn format_files(
+        &self,
+        files: &[DecoratedEntry],
+        plugin_manager: &mut PluginManager,
+        _depth: Option<usize>,
+    ) -> Result<String> {
+        if files.is_empty() {
+            return Ok(String::new());
+        }
+        let uses_plugins = self.template.uses_plugins();
+        if uses_plugins {
+            plugin_manager.prepare_format_fields(files, "template");
+        }
+
+        let lines: Vec<String> = files
+            .iter()
+            .map(|entry| {
+                let plugin_text = if uses_plugins {
+                    plugin_manager.format_fields(entry, "template").join(" ")
+                } else {
+                    String::new()
+                };
+                self.render_entry(entry, &plugin_text)
+            })
+            .collect();
+        match self.plain {
+            Some(plain) => Ok(plain.records(lines.iter().map(String::as_str))),
+            None => Ok(lines.join("\n")),
+        }
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+}
+
+fn format_size(size: u64, style: &Style) -> String {
+    match style {
+        Style::HumanSize => colorize_size(size).to_string(),
+        _ => colorize_size_text(size.to_string()).to_string(),
+    }
+}
+
+fn typed_text(value: &TypedValue) -> Option<String> {
+    match value.value.as_ref()? {
+        Value::NullValue(_) => None,
+        Value::StringValue(text) | Value::PathValue(text) => Some(text.clone()),
+        Value::IntegerValue(number) => Some(number.to_string()),
+        Value::FloatValue(number) => Some(number.to_string()),
+        Value::BooleanValue(flag) => Some(flag.to_string()),
+        Value::BytesValue(number) | Value::TimestampValue(number) => Some(number.to_string()),
+        Value::ListValue(list) => Some(
+            list.values
+                .iter()
+                .filter_map(typed_text)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        Value::ObjectValue(_) => None,
+    }
+}
+
+fn visible_width(value: &str) -> usize {
+    let stripped = strip_ansi_escapes::strip(value).unwrap_or_default();
+    String::from_utf8_lossy(&stripped).width()
+}
+
+fn pad(value: &str, width: usize, align: Align) -> String {
+    let missing = width.saturating_sub(visible_width(value));
+    if missing == 0 {
+        return value.to_string();
+    }
+    let (left, right) = match align {
+        Align::Left => (0, missing),
+        Align::Right => (missing, 0),
+        Align::Center => (missing / 2, missing - missing / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), value, " ".repeat(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::quoting::QuotingStyle;
+    use std::collections::HashMap;
+
+    fn plain(value: &str) -> String {
+        let stripped = strip_ansi_escapes::strip(value).unwrap_or_default();
+        String::from_utf8_lossy(&stripped).into_owned()
+    }
+
+    fn render(template: &str, entry: &DecoratedEntry) -> String {
+        let formatter = TemplateFormatter::new(
+            Template::parse(template).unwrap(),
+            None,
+            "symbolic".to_string(),
+            "%Y-%m-%d",
+            false,
+            false,
+        );
+        plain(&formatter.render_entry(entry, ""))
+    }
+
+    fn entry() -> DecoratedEntry {
+        DecoratedEntry {
+            path: "/nonexistent/notes.txt".to_string(),
+            metadata: Some(EntryMetadata {
+                size: 2048,
+                modified: 86_400 * 545,
+                permissions: 0o644,
+                is_file: true,
+                ..Default::default()
+            }),
+            custom_fields: HashMap::from([("git_status".to_string(), "M".to_string())]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_aligned_and_styled_placeholders() {
+        let entry = entry();
+        assert_eq!(
+            render("{name:<12}|{size:>6h}|{size}|{permissions:octal}", &entry),
+            "notes.txt   |  2.0K|2048|-644"
+        );
+        assert_eq!(render("{name:^13}", &entry), "  notes.txt  ");
+        assert_eq!(
+            render("{{{field:git_status}}} {modified:%Y}", &entry),
+            "{M} 1971"
+        );
+        assert_eq!(render("{field:missing}", &entry), "-");
+    }
+
+    #[test]
+    fn sections_depend_on_a_value() {
+        let entry = entry();
+        let template = "{name}{?field:git_status} [{field:git_status}]{/}{!field:owner_note} -{/}";
+        assert_eq!(render(template, &entry), "notes.txt [M] -");
+        assert_eq!(render("{?created}{created}{/}.", &entry), ".");
+    }
+
+    #[test]
+    fn plain_output_terminates_every_record() {
+        let formatter = TemplateFormatter::new(
+            Template::parse("{path}").unwrap(),
+            Some(PlainOutput {
+                quoting: QuotingStyle::Shell,
+                print0: true,
+            }),
+            "symbolic".to_string(),
+            "%Y-%m-%d",
+            false,
+            false,
+        );
+        let mut plugin_manager = PluginManager::new(crate::config::Config::default());
+        let mut spaced = entry();
+        spaced.path = "/nonexistent/my notes.txt".to_string();
+        let output = formatter
+            .format_files(&[entry(), spaced], &mut plugin_manager, None)
+            .unwrap();
+        let records: Vec<String> = output.split('\0').map(plain).collect();
+        assert_eq!(
+            records,
+            ["/nonexistent/notes.txt", "'/nonexistent/my notes.txt'", ""]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        for template in [
+            "{name",
+            "name}",
+            "{colour}",
+            "{size:x}",
+            "{name:h}",
+            "{permissions:fancy}",
+            "{?name}unclosed",
+            "{/}",
+            "{field:}",
+        ] {
+            assert!(Template::parse(template).is_err(), "{}", template);
+        }
+    }
+}
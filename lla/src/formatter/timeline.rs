@@ -144,10 +144,12 @@ impl FileFormatter for TimelineFormatter {
                 let time_str = Self::format_relative_time(dt).color(Self::get_time_color());
 
                 let path = Path::new(&entry.path);
-                let colored_name = colorize_file_name(path).to_string();
+                let metadata = entry.metadata.as_ref();
+                let colored_name = colorize_file_name(path, metadata).to_string();
                 let name = colorize_file_name_with_icon(
                     path,
-                    format_with_icon(path, colored_name, self.show_icons),
+                    metadata,
+                    format_with_icon(path, metadata, colored_name, self.show_icons),
                 )
                 .to_string();
                 let name = hyperlink::link_path(path, name);
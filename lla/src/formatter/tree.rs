@@ -1,11 +1,15 @@
+use super::quoting::PlainOutput;
 use super::FileFormatter;
 use crate::error::Result;
 use crate::plugin::PluginManager;
 use crate::utils::color::*;
+use crate::utils::dir_size::SizeBasis;
+use crate::utils::fs_metadata::mounted_filesystem;
 use crate::utils::hyperlink;
 use crate::utils::icons::format_with_icon;
 use colored::Colorize;
-use lla_plugin_interface::proto::DecoratedEntry;
+use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata};
+use lla_plugin_utils::ui::format_size;
 use std::collections::{HashMap, HashSet};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
@@ -14,57 +18,90 @@ const BUFFER_SIZE: usize = 16384;
 
 pub struct TreeFormatter {
     pub show_icons: bool,
+    pub plain: Option<PlainOutput>,
+    /// Set with `--include-dirs` to print each entry's size, recursive totals
+    /// for directories.
+    pub sizes: Option<SizeBasis>,
+    pub classify: bool,
 }
 
 impl TreeFormatter {
-    pub fn new(show_icons: bool) -> Self {
-        Self { show_icons }
+    pub fn new(
+        show_icons: bool,
+        plain: Option<PlainOutput>,
+        sizes: Option<SizeBasis>,
+        classify: bool,
+    ) -> Self {
+        Self {
+            show_icons,
+            plain,
+            sizes,
+            classify,
+        }
     }
 
-    fn format_entry(&self, path: &Path, plugin_field: Option<&str>) -> String {
-        let colored_name = colorize_file_name(path).to_string();
+    /// Collects paths in the order the tree would draw them, honoring depth.
+    fn collect_tree_order(
+        path: &Path,
+        tree: &HashMap<PathBuf, Vec<PathBuf>>,
+        current_depth: usize,
+        max_depth: Option<usize>,
+        ordered: &mut Vec<String>,
+    ) {
+        if max_depth.is_some_and(|max| current_depth > max) {
+            return;
+        }
+        ordered.push(path.to_string_lossy().into_owned());
+        for child in tree.get(path).into_iter().flatten() {
+            Self::collect_tree_order(child, tree, current_depth + 1, max_depth, ordered);
+        }
+    }
+
+    fn format_entry(
+        &self,
+        path: &Path,
+        metadata: Option<&EntryMetadata>,
+        indicator: &str,
+        fields: &str,
+    ) -> String {
+        let colored_name = colorize_file_name(path, metadata).to_string();
         let name = if self.show_icons {
-            format_with_icon(path, colored_name, true)
+            format_with_icon(path, metadata, colored_name, true)
         } else {
             colored_name
         };
         let name = hyperlink::link_path(path, name);
-        match plugin_field.filter(|field| !field.is_empty()) {
-            Some(field) => format!("{name} {field}"),
-            None => name,
+        if fields.is_empty() {
+            format!("{name}{indicator}")
+        } else {
+            format!("{name}{indicator} {fields}")
         }
     }
 
+    /// Roots and children keep the order of `entries`, which are sorted
+    /// within each directory before they reach the formatter.
     fn build_tree(
         &self,
         entries: &[DecoratedEntry],
     ) -> (Vec<PathBuf>, HashMap<PathBuf, Vec<PathBuf>>) {
         let mut tree: HashMap<PathBuf, Vec<PathBuf>> = HashMap::with_capacity(entries.len());
-        let mut path_set: HashSet<PathBuf> = HashSet::with_capacity(entries.len());
-        let mut child_paths = HashSet::new();
+        let path_set: HashSet<PathBuf> = entries
+            .iter()
+            .map(|entry| PathBuf::from(&entry.path))
+            .collect();
+        let mut seen = HashSet::with_capacity(entries.len());
+        let mut root_paths = Vec::new();
 
         for entry in entries {
-            path_set.insert(PathBuf::from(&entry.path));
-        }
-
-        for path in path_set.iter() {
-            if let Some(parent) = path.parent() {
-                if path_set.contains(parent) {
-                    tree.entry(parent.to_path_buf())
-                        .or_default()
-                        .push(path.clone());
-                    child_paths.insert(path.clone());
-                }
+            let path = PathBuf::from(&entry.path);
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            match path.parent().filter(|parent| path_set.contains(*parent)) {
+                Some(parent) => tree.entry(parent.to_path_buf()).or_default().push(path),
+                None => root_paths.push(path),
             }
         }
-        for children in tree.values_mut() {
-            children.sort_unstable();
-        }
-        let mut root_paths: Vec<_> = path_set
-            .into_iter()
-            .filter(|path| !child_paths.contains(path))
-            .collect();
-        root_paths.sort_unstable();
 
         (root_paths, tree)
     }
@@ -76,7 +113,7 @@ impl TreeFormatter {
         prefix: &str,
         is_last: bool,
         tree: &HashMap<PathBuf, Vec<PathBuf>>,
-        plugin_fields: &HashMap<PathBuf, String>,
+        labels: &HashMap<PathBuf, String>,
         writer: &mut impl Write,
         current_depth: usize,
         max_depth: Option<usize>,
@@ -90,7 +127,10 @@ impl TreeFormatter {
         let node_prefix = if is_last { "└── " } else { "├── " };
         let child_prefix = if is_last { "    " } else { "│   " };
 
-        let formatted_name = self.format_entry(path, plugin_fields.get(path).map(String::as_str));
+        let formatted_name = labels
+            .get(path)
+            .cloned()
+            .unwrap_or_else(|| self.format_entry(path, None, "", ""));
         writeln!(
             writer,
             "{}{}{}",
@@ -109,7 +149,7 @@ impl TreeFormatter {
                     &new_prefix,
                     is_last_child,
                     tree,
-                    plugin_fields,
+                    labels,
                     writer,
                     current_depth + 1,
                     max_depth,
@@ -135,12 +175,47 @@ impl FileFormatter for TreeFormatter {
             return Ok(String::new());
         }
 
+        if let Some(plain) = self.plain {
+            // Script-friendly output drops the connectors and writes full paths.
+            let (root_paths, tree) = self.build_tree(files);
+            let mut ordered = Vec::with_capacity(files.len());
+            for path in &root_paths {
+                Self::collect_tree_order(path, &tree, 0, depth, &mut ordered);
+            }
+            return Ok(plain.records(ordered.iter().map(String::as_str)));
+        }
+
         plugin_manager.prepare_format_fields(files, "tree");
-        let plugin_fields = files
+        let labels = files
             .iter()
-            .filter_map(|entry| {
-                let fields = plugin_manager.format_fields(entry, "tree").join(" ");
-                (!fields.is_empty()).then(|| (PathBuf::from(&entry.path), fields))
+            .map(|entry| {
+                let mut fields = plugin_manager.format_fields(entry, "tree");
+                if let (Some(basis), Some(metadata)) = (self.sizes, entry.metadata.as_ref()) {
+                    fields.insert(
+                        0,
+                        format_size(basis.of(metadata)).bright_black().to_string(),
+                    );
+                }
+                // Mark where the tree enters another file system, which is also
+                // where `--one-file-system` stops descending.
+                if entry
+                    .metadata
+                    .as_ref()
+                    .is_some_and(|metadata| metadata.is_dir)
+                {
+                    if let Some(filesystem) = mounted_filesystem(Path::new(&entry.path)) {
+                        fields.insert(0, format!("[mount: {}]", filesystem).cyan().to_string());
+                    }
+                }
+                let path = PathBuf::from(&entry.path);
+                let indicator = if self.classify {
+                    classify_indicator(&path, entry.metadata.as_ref())
+                } else {
+                    ""
+                };
+                let label =
+                    self.format_entry(&path, entry.metadata.as_ref(), indicator, &fields.join(" "));
+                (path, label)
             })
             .collect::<HashMap<_, _>>();
 
@@ -150,16 +225,7 @@ impl FileFormatter for TreeFormatter {
         let last_idx = root_paths.len().saturating_sub(1);
         for (i, path) in root_paths.iter().enumerate() {
             let is_last = i == last_idx;
-            self.write_tree_recursive(
-                path,
-                "",
-                is_last,
-                &tree,
-                &plugin_fields,
-                &mut buffer,
-                0,
-                depth,
-            )?;
+            self.write_tree_recursive(path, "", is_last, &tree, &labels, &mut buffer, 0, depth)?;
         }
 
         Ok(String::from_utf8_lossy(&buffer).into_owned())
@@ -1,5 +1,6 @@
 use crate::error::{LlaError, Result};
-use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata};
+use crate::utils::fs_metadata::kind_from_mode;
+use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata, FileKind};
 use std::collections::{BTreeSet, HashMap};
 use std::fs::File;
 use std::io::Read;
@@ -50,6 +51,7 @@ fn synthesize_directory_entries(entries: &mut Vec<DecoratedEntry>, root_prefix:
                 permissions: 0o755,
                 uid: 0,
                 gid: 0,
+                kind: FileKind::Directory as i32,
                 ..EntryMetadata::default()
             }),
             custom_fields: HashMap::new(),
@@ -98,6 +100,7 @@ pub fn read_zip(path: &Path) -> Result<Vec<DecoratedEntry>> {
             permissions: 0o755,
             uid: 0,
             gid: 0,
+            kind: FileKind::Directory as i32,
             ..EntryMetadata::default()
         }),
         custom_fields: root_fields,
@@ -136,13 +139,13 @@ pub fn read_zip(path: &Path) -> Result<Vec<DecoratedEntry>> {
             abs_src.to_string_lossy().into_owned(),
         );
 
-        // Heuristic for symlink from POSIX mode bits (if present).
-        const FILE_TYPE_MASK: u32 = 0o170000;
-        const SYMLINK_TYPE: u32 = 0o120000;
-        let is_symlink = match file.unix_mode() {
-            Some(m) => (m & FILE_TYPE_MASK) == SYMLINK_TYPE,
-            None => false,
+        // Zip archives written on Unix keep the file type in the mode bits.
+        let kind = match file.unix_mode().map(kind_from_mode) {
+            Some(kind) if kind != FileKind::Unknown => kind,
+            _ if is_dir => FileKind::Directory,
+            _ => FileKind::Regular,
         };
+        let is_symlink = kind == FileKind::Symlink;
 
         entries.push(DecoratedEntry {
             path: format!("{}/{}", root_name, name),
@@ -157,6 +160,7 @@ pub fn read_zip(path: &Path) -> Result<Vec<DecoratedEntry>> {
                 permissions: mode,
                 uid: 0,
                 gid: 0,
+                kind: kind as i32,
                 ..EntryMetadata::default()
             }),
             custom_fields,
@@ -198,6 +202,7 @@ pub fn read_tar<R: Read>(mut reader: R, source_path: &Path) -> Result<Vec<Decora
             permissions: 0o755,
             uid: 0,
             gid: 0,
+            kind: FileKind::Directory as i32,
             ..EntryMetadata::default()
         }),
         custom_fields: root_fields,
@@ -236,10 +241,23 @@ pub fn read_tar<R: Read>(mut reader: R, source_path: &Path) -> Result<Vec<Decora
         }
 
         let header = entry.header();
-        let kind = header.entry_type();
-        let is_dir = kind.is_dir();
-        let is_symlink = kind.is_symlink();
+        let entry_type = header.entry_type();
+        let is_dir = entry_type.is_dir();
+        let is_symlink = entry_type.is_symlink();
         let is_file = !(is_dir || is_symlink);
+        let kind = if is_dir {
+            FileKind::Directory
+        } else if is_symlink {
+            FileKind::Symlink
+        } else if entry_type.is_fifo() {
+            FileKind::Fifo
+        } else if entry_type.is_block_special() {
+            FileKind::BlockDevice
+        } else if entry_type.is_character_special() {
+            FileKind::CharDevice
+        } else {
+            FileKind::Regular
+        };
 
         let size = if is_dir {
             0
@@ -278,6 +296,7 @@ pub fn read_tar<R: Read>(mut reader: R, source_path: &Path) -> Result<Vec<Decora
                 permissions: mode,
                 uid,
                 gid,
+                kind: kind as i32,
                 ..EntryMetadata::default()
             }),
             custom_fields,
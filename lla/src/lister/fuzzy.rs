@@ -2,8 +2,10 @@
 
 use super::FileLister;
 use crate::utils::color::*;
+use crate::utils::exclude::ExcludeSet;
 use crate::utils::fs_metadata;
 use crate::utils::icons::format_with_icon;
+use crate::utils::llaignore::LlaIgnoreMode;
 use crate::{error::Result, theme::color_value_to_color};
 use colored::*;
 use crossbeam_channel::{bounded, Sender};
@@ -52,6 +54,8 @@ fn path_contains_git_dir(path: &Path) -> bool {
 
 fn stream_gitignore_filtered_entries(
     directory: &str,
+    excludes: ExcludeSet,
+    one_file_system: bool,
     sender: Sender<Vec<FileEntry>>,
     total_indexed: &Arc<AtomicUsize>,
 ) {
@@ -65,8 +69,14 @@ fn stream_gitignore_filtered_entries(
         .parents(true)
         .ignore(true)
         .require_git(false)
-        .same_file_system(false)
-        .threads(1);
+        .same_file_system(one_file_system)
+        .threads(1)
+        .filter_entry(move |entry| {
+            !excludes.contains_entry(
+                entry.path(),
+                entry.file_type().is_some_and(|ft| ft.is_dir()),
+            )
+        });
 
     let mut batch = Vec::with_capacity(CHUNK_SIZE);
 
@@ -479,14 +489,23 @@ pub struct FuzzyLister {
     index: SearchIndex,
     config: crate::config::Config,
     respect_gitignore: bool,
+    one_file_system: bool,
+    llaignore: LlaIgnoreMode,
 }
 
 impl FuzzyLister {
-    pub fn new(config: crate::config::Config, respect_gitignore: bool) -> Self {
+    pub fn new(
+        config: crate::config::Config,
+        respect_gitignore: bool,
+        one_file_system: bool,
+        llaignore: LlaIgnoreMode,
+    ) -> Self {
         Self {
             index: SearchIndex::new(config.clone(), respect_gitignore),
             config,
             respect_gitignore,
+            one_file_system,
+            llaignore,
         }
     }
 
@@ -519,9 +538,17 @@ impl FuzzyLister {
         let directory = directory.to_string();
 
         let respect_gitignore = self.respect_gitignore;
+        let one_file_system = self.one_file_system;
+        let excludes = ExcludeSet::new(&self.config, Path::new(&directory), self.llaignore);
         thread::spawn(move || {
             if respect_gitignore {
-                stream_gitignore_filtered_entries(&directory, sender.clone(), &total_indexed_clone);
+                stream_gitignore_filtered_entries(
+                    &directory,
+                    excludes,
+                    one_file_system,
+                    sender.clone(),
+                    &total_indexed_clone,
+                );
                 indexing_complete_clone.store(true, AtomicOrdering::SeqCst);
                 return;
             }
@@ -534,8 +561,14 @@ impl FuzzyLister {
                 .parents(false)
                 .ignore(false)
                 .follow_links(false)
-                .same_file_system(false)
-                .threads(num_cpus::get());
+                .same_file_system(one_file_system)
+                .threads(num_cpus::get())
+                .filter_entry(move |entry| {
+                    !excludes.contains_entry(
+                        entry.path(),
+                        entry.file_type().is_some_and(|ft| ft.is_dir()),
+                    )
+                });
             let walker = builder.build_parallel();
 
             let (tx, rx) = std::sync::mpsc::channel();
@@ -1393,6 +1426,7 @@ impl ResultList {
                 let name_display = if is_selected {
                     format_with_icon(
                         path,
+                        None,
                         file_name
                             .color(color_value_to_color(&theme.colors.directory))
                             .bold()
@@ -1401,7 +1435,7 @@ impl ResultList {
                         true,
                     )
                 } else {
-                    format_with_icon(path, colorize_file_name(path).to_string(), true)
+                    format_with_icon(path, None, colorize_file_name(path, None).to_string(), true)
                 };
 
                 let prefix = if is_marked {
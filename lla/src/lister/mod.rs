@@ -8,6 +8,19 @@ pub trait FileLister {
         recursive: bool,
         depth: Option<usize>,
     ) -> Result<Vec<PathBuf>>;
+
+    /// Hands the listing to `sink` one directory at a time so callers can start
+    /// rendering before the walk has finished. Listers that cannot walk
+    /// incrementally deliver everything as a single batch.
+    fn list_files_batched(
+        &self,
+        directory: &str,
+        recursive: bool,
+        depth: Option<usize>,
+        sink: &mut dyn FnMut(Vec<PathBuf>) -> Result<()>,
+    ) -> Result<()> {
+        sink(self.list_files(directory, recursive, depth)?)
+    }
 }
 
 pub mod archive;
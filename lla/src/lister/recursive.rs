@@ -2,22 +2,28 @@ use super::FileLister;
 use crate::config::Config;
 use crate::error::Result;
 use crate::lister::BasicLister;
-use rayon::prelude::*;
-use std::path::PathBuf;
+use crate::utils::exclude::ExcludeSet;
+use crate::utils::fs_metadata::device_id;
+use crate::utils::llaignore::LlaIgnoreMode;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use walkdir::{DirEntry, WalkDir};
 
-const PARALLEL_THRESHOLD: usize = 1000;
-const BUFFER_CAPACITY: usize = 1024;
-
 pub struct RecursiveLister {
     config: Config,
+    one_file_system: bool,
+    llaignore: LlaIgnoreMode,
 }
 
 impl RecursiveLister {
-    pub fn new(config: Config) -> Self {
-        Self { config }
+    pub fn new(config: Config, one_file_system: bool, llaignore: LlaIgnoreMode) -> Self {
+        Self {
+            config,
+            one_file_system,
+            llaignore,
+        }
     }
 
     fn is_hidden(entry: &DirEntry) -> bool {
@@ -33,9 +39,6 @@ impl RecursiveLister {
         counter: &Arc<AtomicUsize>,
         max_entries: usize,
     ) -> bool {
-        // Skip excluded directories early
-        // If the entry path starts with any excluded prefix, skip it entirely
-        // Note: We cannot access self.config here; exclusion is handled in filter and at walker creation
         if counter.load(Ordering::Relaxed) >= max_entries {
             return false;
         }
@@ -73,50 +76,117 @@ impl FileLister for RecursiveLister {
             .unwrap_or(usize::MAX);
 
         let counter = Arc::new(AtomicUsize::new(0));
-        let mut entries = Vec::with_capacity(BUFFER_CAPACITY);
+        let excludes = ExcludeSet::new(&self.config, Path::new(directory), self.llaignore);
 
-        // Pre-compute excluded prefixes as absolute PathBufs (canonicalize best-effort)
-        let exclude_prefixes: Vec<PathBuf> = self
-            .config
-            .exclude_paths
-            .iter()
-            .map(|p| p.canonicalize().unwrap_or_else(|_| p.clone()))
-            .collect();
-
-        let walker = WalkDir::new(directory)
+        // Excluded directories are pruned in filter_entry, so WalkDir never
+        // opens them.
+        let entries = WalkDir::new(directory)
             .min_depth(0)
             .max_depth(max_depth)
             .follow_links(false)
-            .same_file_system(true)
+            .same_file_system(self.one_file_system)
             .into_iter()
-            .filter_entry(|e| {
-                if exclude_prefixes.is_empty() {
-                    return true;
-                }
-                // Compare against canonicalized path best-effort to avoid mismatches
-                let p = match e.path().canonicalize() {
-                    Ok(abs) => abs,
-                    Err(_) => e.path().to_path_buf(),
-                };
-                // If this entry is exactly or under an excluded prefix, skip descending
-                !exclude_prefixes.iter().any(|ex| p.starts_with(ex))
-            })
+            .filter_entry(|e| !excludes.contains_entry(e.path(), e.file_type().is_dir()))
             .filter_map(|e| e.ok())
             .filter(|e| Self::should_process_entry(e, &counter, max_entries))
+            .map(|e| e.into_path())
             .collect::<Vec<_>>();
 
-        if walker.len() > PARALLEL_THRESHOLD {
-            entries.par_extend(walker.into_par_iter().map(|e| e.into_path()).filter(|p| {
-                let p_abs = p.canonicalize().unwrap_or_else(|_| p.clone());
-                !exclude_prefixes.iter().any(|ex| p_abs.starts_with(ex))
-            }));
+        Ok(entries)
+    }
+
+    fn list_files_batched(
+        &self,
+        directory: &str,
+        recursive: bool,
+        depth: Option<usize>,
+        sink: &mut dyn FnMut(Vec<PathBuf>) -> Result<()>,
+    ) -> Result<()> {
+        if !recursive {
+            return sink(BasicLister.list_files(directory, false, None)?);
+        }
+
+        let max_depth = depth.unwrap_or(usize::MAX);
+        let max_entries = self
+            .config
+            .listers
+            .recursive
+            .max_entries
+            .unwrap_or(usize::MAX);
+        let excludes = ExcludeSet::new(&self.config, Path::new(directory), self.llaignore);
+        let root_device = if self.one_file_system {
+            device_id(Path::new(directory))
         } else {
-            entries.extend(walker.into_iter().map(|e| e.into_path()).filter(|p| {
-                let p_abs = p.canonicalize().unwrap_or_else(|_| p.clone());
-                !exclude_prefixes.iter().any(|ex| p_abs.starts_with(ex))
-            }));
+            None
+        };
+
+        // The root leads the first batch, as `list_files` yields it first.
+        let mut root = Some(PathBuf::from(directory));
+
+        // Walk one directory at a time instead of handing everything to WalkDir so
+        // each batch holds exactly one directory's children. Only the stack of
+        // directories still to visit is kept between batches.
+        let mut files_seen = 0usize;
+        let mut pending = vec![(PathBuf::from(directory), 0usize)];
+        while let Some((current, level)) = pending.pop() {
+            if level >= max_depth || files_seen >= max_entries {
+                continue;
+            }
+            let Ok(read_dir) = fs::read_dir(&current) else {
+                continue;
+            };
+
+            let mut batch = Vec::new();
+            let mut subdirectories = Vec::new();
+            for entry in read_dir.flatten() {
+                if files_seen >= max_entries {
+                    break;
+                }
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                let path = entry.path();
+                if excludes.contains_entry(&path, file_type.is_dir()) {
+                    continue;
+                }
+                if file_type.is_file() {
+                    let hidden = entry
+                        .file_name()
+                        .to_str()
+                        .map(|s| s.starts_with('.'))
+                        .unwrap_or(false);
+                    if hidden {
+                        continue;
+                    }
+                    files_seen += 1;
+                } else if file_type.is_dir()
+                    && (root_device.is_none() || device_id(&path) == root_device)
+                {
+                    subdirectories.push(path.clone());
+                }
+                batch.push(path);
+            }
+
+            // Visit subdirectories in name order so the output is deterministic.
+            subdirectories.sort();
+            pending.extend(
+                subdirectories
+                    .into_iter()
+                    .rev()
+                    .map(|subdirectory| (subdirectory, level + 1)),
+            );
+
+            if let Some(root) = root.take() {
+                batch.insert(0, root);
+            }
+            if !batch.is_empty() {
+                sink(batch)?;
+            }
         }
 
-        Ok(entries)
+        match root {
+            Some(root) => sink(vec![root]),
+            None => Ok(()),
+        }
     }
 }
@@ -5,8 +5,8 @@ use dashmap::DashMap;
 use libloading::Library;
 use lla_plugin_interface::{
     manifest::{
-        ActionArgument, ActionArgumentType, ActionDescriptor, ActionOutputSchema, FieldType,
-        ManifestValue, PluginManifest, PluginRuntime, MANIFEST_FILE_NAME,
+        ActionArgument, ActionArgumentType, ActionDescriptor, ActionOutputSchema, FieldDescriptor,
+        FieldType, ManifestValue, PluginManifest, PluginRuntime, MANIFEST_FILE_NAME,
     },
     proto::{self, plugin_message::Message, PluginMessage},
     ActionInfo, PluginApiV3, MAX_BATCH_ENTRIES, MAX_RESPONSE_BYTES, PLUGIN_API_VERSION,
@@ -174,6 +174,7 @@ fn normalize_plugin_format(format: &str) -> Option<&'static str> {
         "default" => Some("default"),
         "long" => Some("long"),
         "table" => Some("long"),
+        "template" => Some("long"),
         "tree" => Some("tree"),
         "grid" => Some("grid"),
         "recursive" => Some("recursive"),
@@ -1641,6 +1642,17 @@ impl PluginManager {
     }
 
     pub fn decorate_entries(&mut self, entries: &mut [proto::DecoratedEntry], format: &str) {
+        self.decorate_entries_skipping(entries, format, &HashSet::new());
+    }
+
+    /// Like `decorate_entries`, but leaves out plugins that already decorated
+    /// the entries for a field filter.
+    pub fn decorate_entries_skipping(
+        &mut self,
+        entries: &mut [proto::DecoratedEntry],
+        format: &str,
+        skip: &HashSet<String>,
+    ) {
         let Some(plugin_format) = normalize_plugin_format(format) else {
             return;
         };
@@ -1649,43 +1661,19 @@ impl PluginManager {
         }
 
         let cache_scope = self.decoration_cache_scope();
-        let enabled_names: Vec<_> = self.enabled_plugins.iter().cloned().collect();
+        let enabled_names: Vec<_> = self
+            .enabled_plugins
+            .iter()
+            .filter(|name| !skip.contains(*name))
+            .cloned()
+            .collect();
         let supported_names: Vec<_> = enabled_names
             .into_iter()
             .filter(|name| self.supports_format(name, plugin_format))
             .collect();
 
         for name in supported_names {
-            for chunk in entries.chunks_mut(MAX_BATCH_ENTRIES) {
-                let request = PluginMessage {
-                    message: Some(Message::DecorateBatch(proto::BatchDecorateRequest {
-                        entries: chunk.to_vec(),
-                        format: plugin_format.to_string(),
-                    })),
-                };
-                let batch_entries = self.send_request(&name, request).ok().and_then(|response| {
-                    match response.message {
-                        Some(Message::DecorateBatchResponse(batch))
-                            if batch.entries.len() == chunk.len()
-                                && chunk
-                                    .iter()
-                                    .zip(&batch.entries)
-                                    .all(|(entry, decorated)| entry.path == decorated.path) =>
-                        {
-                            Some(batch.entries)
-                        }
-                        _ => None,
-                    }
-                });
-                if let Some(batch_entries) = batch_entries {
-                    for (entry, decorated) in chunk.iter_mut().zip(batch_entries) {
-                        entry.custom_fields.extend(decorated.custom_fields);
-                        entry.typed_fields.extend(decorated.typed_fields);
-                    }
-                } else {
-                    self.decorate_entries_individually(&name, chunk);
-                }
-            }
+            self.decorate_batches(&name, plugin_format, entries);
         }
 
         for entry in entries {
@@ -1702,6 +1690,117 @@ impl PluginManager {
         }
     }
 
+    /// Decorates entries with only `plugins`, so a field filter can run before
+    /// the remaining plugins see the entries. A plugin that does not render
+    /// `format` is asked for the first format it declares. Nothing is cached
+    /// because the decoration is incomplete.
+    pub fn decorate_entries_for_filter(
+        &mut self,
+        entries: &mut [proto::DecoratedEntry],
+        format: &str,
+        plugins: &HashSet<String>,
+    ) {
+        if entries.is_empty() {
+            return;
+        }
+        let mut names: Vec<_> = plugins
+            .iter()
+            .filter(|name| self.enabled_plugins.contains(*name))
+            .cloned()
+            .collect();
+        names.sort();
+        for name in names {
+            let plugin_format = normalize_plugin_format(format)
+                .filter(|plugin_format| self.supports_format(&name, plugin_format))
+                .or_else(|| {
+                    self.manifests
+                        .get(&name)
+                        .and_then(|manifest| manifest.capabilities.formats.first())
+                        .and_then(|format| normalize_plugin_format(format))
+                });
+            if let Some(plugin_format) = plugin_format {
+                self.decorate_batches(&name, plugin_format, entries);
+            }
+        }
+        for entry in entries {
+            self.apply_typed_fields(entry);
+        }
+    }
+
+    /// The enabled plugin that declares `field`, with the field's descriptor.
+    pub fn field_descriptor(&self, field: &str) -> Option<(String, FieldDescriptor)> {
+        let mut names: Vec<_> = self.enabled_plugins.iter().collect();
+        names.sort();
+        names.into_iter().find_map(|name| {
+            let manifest = self.manifests.get(name)?;
+            let descriptor = manifest.fields.iter().find(|f| f.name == field)?;
+            Some((name.clone(), descriptor.clone()))
+        })
+    }
+
+    /// Names of the filterable fields declared by enabled plugins.
+    pub fn filterable_fields(&self) -> Vec<String> {
+        self.declared_fields(|field| field.filterable)
+    }
+
+    /// Names of the sortable fields declared by enabled plugins.
+    pub fn sortable_fields(&self) -> Vec<String> {
+        self.declared_fields(|field| field.sortable)
+    }
+
+    fn declared_fields(&self, include: impl Fn(&FieldDescriptor) -> bool) -> Vec<String> {
+        let mut fields: Vec<String> = self
+            .enabled_plugins
+            .iter()
+            .filter_map(|name| self.manifests.get(name))
+            .flat_map(|manifest| manifest.fields.iter())
+            .filter(|field| include(field))
+            .map(|field| field.name.clone())
+            .collect();
+        fields.sort();
+        fields.dedup();
+        fields
+    }
+
+    fn decorate_batches(
+        &self,
+        name: &str,
+        plugin_format: &str,
+        entries: &mut [proto::DecoratedEntry],
+    ) {
+        for chunk in entries.chunks_mut(MAX_BATCH_ENTRIES) {
+            let request = PluginMessage {
+                message: Some(Message::DecorateBatch(proto::BatchDecorateRequest {
+                    entries: chunk.to_vec(),
+                    format: plugin_format.to_string(),
+                })),
+            };
+            let batch_entries =
+                self.send_request(name, request)
+                    .ok()
+                    .and_then(|response| match response.message {
+                        Some(Message::DecorateBatchResponse(batch))
+                            if batch.entries.len() == chunk.len()
+                                && chunk
+                                    .iter()
+                                    .zip(&batch.entries)
+                                    .all(|(entry, decorated)| entry.path == decorated.path) =>
+                        {
+                            Some(batch.entries)
+                        }
+                        _ => None,
+                    });
+            if let Some(batch_entries) = batch_entries {
+                for (entry, decorated) in chunk.iter_mut().zip(batch_entries) {
+                    entry.custom_fields.extend(decorated.custom_fields);
+                    entry.typed_fields.extend(decorated.typed_fields);
+                }
+            } else {
+                self.decorate_entries_individually(name, chunk);
+            }
+        }
+    }
+
     fn decorate_entries_individually(
         &self,
         plugin_name: &str,
@@ -2032,6 +2131,7 @@ mod tests {
             assert_eq!(normalize_plugin_format(format), Some(format));
         }
         assert_eq!(normalize_plugin_format("table"), Some("long"));
+        assert_eq!(normalize_plugin_format("template"), Some("long"));
         assert_eq!(normalize_plugin_format("unknown"), None);
     }
 
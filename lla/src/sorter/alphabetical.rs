@@ -1,59 +1,81 @@
-use super::{natural_cmp, FileSorter, SortOptions};
-use crate::error::Result;
-use lla_plugin_interface::proto::DecoratedEntry;
-use rayon::prelude::*;
-use std::path::PathBuf;
+use super::collation::{compare_names, NameKey};
+use super::{Collation, FileSorter, SortEntry, SortOptions};
+use std::cmp::Ordering;
 
 pub struct AlphabeticalSorter;
 
+impl AlphabeticalSorter {
+    fn name_key<'a>(sorted: &SortEntry<'a>, options: SortOptions) -> &'a NameKey {
+        sorted.keys.name.get_or_init(|| {
+            let name = sorted
+                .path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            NameKey::new(&name, options.case_sensitive)
+        })
+    }
+}
+
 impl FileSorter for AlphabeticalSorter {
-    fn sort_files_with_metadata(
-        &self,
-        entries: &mut [(PathBuf, &DecoratedEntry)],
-        options: SortOptions,
-    ) -> Result<()> {
-        entries.par_sort_unstable_by(|(path_a, entry_a), (path_b, entry_b)| {
-            if options.dirs_first {
-                let a_is_dir = entry_a.metadata.as_ref().is_some_and(|m| m.is_dir);
-                let b_is_dir = entry_b.metadata.as_ref().is_some_and(|m| m.is_dir);
-
-                match (a_is_dir, b_is_dir) {
-                    (true, false) => {
-                        return if options.reverse {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            std::cmp::Ordering::Less
-                        }
-                    }
-                    (false, true) => {
-                        return if options.reverse {
-                            std::cmp::Ordering::Less
-                        } else {
-                            std::cmp::Ordering::Greater
-                        }
-                    }
-                    _ => {}
-                }
+    fn compare(&self, a: &SortEntry, b: &SortEntry, options: SortOptions) -> Ordering {
+        let name_order = match options.collation {
+            Collation::Unicode => {
+                Self::name_key(a, options).compare(Self::name_key(b, options), options.natural)
             }
-
-            let a_name = path_a.file_name().unwrap_or_default().to_string_lossy();
-            let b_name = path_b.file_name().unwrap_or_default().to_string_lossy();
-
-            let name_order = if options.natural {
-                natural_cmp(&a_name, &b_name)
-            } else if options.case_sensitive {
-                a_name.cmp(&b_name)
-            } else {
-                a_name.to_lowercase().cmp(&b_name.to_lowercase())
-            };
-
-            if options.reverse {
-                name_order.reverse()
-            } else {
-                name_order
+            Collation::Bytes => {
+                let a_name = a.path.file_name().unwrap_or_default().to_string_lossy();
+                let b_name = b.path.file_name().unwrap_or_default().to_string_lossy();
+                compare_names(&a_name, &b_name, options)
             }
-        });
+        };
+
+        if options.reverse {
+            name_order.reverse()
+        } else {
+            name_order
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorter::SortKeys;
+    use lla_plugin_interface::proto::DecoratedEntry;
+
+    fn named(name: &str) -> DecoratedEntry {
+        DecoratedEntry {
+            path: format!("/tmp/{}", name),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn normalizes_each_name_once_under_unicode_collation() {
+        let entries = [named("zebra"), named("Éclair"), named("apple")];
+        let keys: Vec<SortKeys> = entries.iter().map(|_| SortKeys::default()).collect();
+        let mut sorted: Vec<SortEntry> = entries
+            .iter()
+            .zip(&keys)
+            .map(|(entry, keys)| SortEntry::new(entry, keys))
+            .collect();
+        let options = SortOptions {
+            collation: Collation::Unicode,
+            ..Default::default()
+        };
+
+        AlphabeticalSorter
+            .sort_files_with_metadata(&mut sorted, options)
+            .unwrap();
+        let names: Vec<&str> = sorted.iter().map(|e| e.entry.path.as_str()).collect();
+        assert_eq!(names, ["/tmp/apple", "/tmp/Éclair", "/tmp/zebra"]);
+        assert!(keys.iter().all(|keys| keys.name.get().is_some()));
 
-        Ok(())
+        let keys = [SortKeys::default(), SortKeys::default()];
+        let a = SortEntry::new(&entries[0], &keys[0]);
+        let b = SortEntry::new(&entries[2], &keys[1]);
+        AlphabeticalSorter.compare(&a, &b, SortOptions::default());
+        assert!(keys.iter().all(|keys| keys.name.get().is_none()));
     }
 }
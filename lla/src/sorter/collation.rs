@@ -0,0 +1,138 @@
+use super::{natural_cmp, SortOptions};
+use std::cmp::Ordering;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// How names are compared when sorting by name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Collation {
+    /// Compare characters by code point, so `Éclair` sorts after `zebra`.
+    #[default]
+    Bytes,
+    /// Compare normalized names with accents and case set aside, then use
+    /// accents and case only to break ties.
+    Unicode,
+}
+
+impl Collation {
+    pub const NAMES: [&'static str; 2] = ["unicode", "bytes"];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "unicode" => Some(Self::Unicode),
+            "bytes" => Some(Self::Bytes),
+            _ => None,
+        }
+    }
+}
+
+/// Compares two names under `options.collation`, honoring `natural` and
+/// `case_sensitive`.
+pub(crate) fn compare_names(a: &str, b: &str, options: SortOptions) -> Ordering {
+    match options.collation {
+        Collation::Bytes => {
+            if options.natural {
+                natural_cmp(a, b)
+            } else if options.case_sensitive {
+                a.cmp(b)
+            } else {
+                a.to_lowercase().cmp(&b.to_lowercase())
+            }
+        }
+        Collation::Unicode => NameKey::new(a, options.case_sensitive)
+            .compare(&NameKey::new(b, options.case_sensitive), options.natural),
+    }
+}
+
+/// A name prepared for Unicode collation, so a sort normalizes each name once
+/// rather than on every comparison.
+pub(super) struct NameKey {
+    base: String,
+    accents: Vec<Vec<char>>,
+    composed: String,
+}
+
+impl NameKey {
+    pub(super) fn new(name: &str, case_sensitive: bool) -> Self {
+        NameKey {
+            base: base_letters(name, case_sensitive),
+            accents: accents(name),
+            composed: name.nfc().collect(),
+        }
+    }
+
+    pub(super) fn compare(&self, other: &NameKey, natural: bool) -> Ordering {
+        let primary = if natural {
+            natural_cmp(&self.base, &other.base)
+        } else {
+            self.base.cmp(&other.base)
+        };
+        primary
+            .then_with(|| self.accents.cmp(&other.accents))
+            .then_with(|| self.composed.cmp(&other.composed))
+    }
+}
+
+/// The name decomposed with its combining marks dropped, case-folded unless
+/// `case_sensitive`: `Éclair` becomes `eclair`.
+fn base_letters(name: &str, case_sensitive: bool) -> String {
+    let letters = name.nfd().filter(|c| !is_combining_mark(*c));
+    if case_sensitive {
+        letters.collect()
+    } else {
+        letters.flat_map(char::to_lowercase).collect()
+    }
+}
+
+/// The combining marks on each base character, so an unaccented letter sorts
+/// before the same letter with an accent, wherever it is in the name.
+fn accents(name: &str) -> Vec<Vec<char>> {
+    let mut accents: Vec<Vec<char>> = Vec::new();
+    for c in name.nfd() {
+        match accents.last_mut() {
+            Some(marks) if is_combining_mark(c) => marks.push(c),
+            _ if is_combining_mark(c) => accents.push(vec![c]),
+            _ => accents.push(Vec::new()),
+        }
+    }
+    accents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted<'a>(names: &[&'a str], options: SortOptions) -> Vec<&'a str> {
+        let mut names = names.to_vec();
+        names.sort_by(|a, b| compare_names(a, b, options));
+        names
+    }
+
+    #[test]
+    fn unicode_collation_sets_accents_and_case_aside() {
+        let unicode = SortOptions {
+            natural: true,
+            collation: Collation::Unicode,
+            ..Default::default()
+        };
+        assert_eq!(
+            sorted(&["zebra", "Éclair", "eclair", "apple", "Eclair"], unicode),
+            ["apple", "Eclair", "eclair", "Éclair", "zebra"]
+        );
+        assert_eq!(
+            sorted(&["zebra", "Éclair", "apple"], SortOptions::default()),
+            ["apple", "zebra", "Éclair"]
+        );
+
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+        assert_eq!(
+            compare_names(composed, decomposed, unicode),
+            Ordering::Equal
+        );
+        assert_eq!(
+            sorted(&[composed, "cafes", decomposed, "cafe"], unicode),
+            ["cafe", composed, decomposed, "cafes"]
+        );
+    }
+}
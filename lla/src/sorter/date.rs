@@ -1,52 +1,39 @@
-use super::{FileSorter, SortOptions};
-use crate::error::Result;
-use lla_plugin_interface::proto::DecoratedEntry;
-use rayon::prelude::*;
-use std::path::PathBuf;
+use super::{FileSorter, SortEntry, SortOptions};
+use lla_plugin_interface::proto::EntryMetadata;
+use std::cmp::Ordering;
 
-pub struct DateSorter;
+/// Orders entries by one of their timestamps, oldest first.
+pub enum DateSorter {
+    Modified,
+    Created,
+    Accessed,
+}
+
+impl DateSorter {
+    fn time(&self, metadata: &EntryMetadata) -> u64 {
+        match self {
+            DateSorter::Modified => metadata.modified,
+            DateSorter::Created => metadata.created,
+            DateSorter::Accessed => metadata.accessed,
+        }
+    }
+}
 
 impl FileSorter for DateSorter {
-    fn sort_files_with_metadata(
+    fn compare(
         &self,
-        entries: &mut [(PathBuf, &DecoratedEntry)],
+        SortEntry { entry: entry_a, .. }: &SortEntry,
+        SortEntry { entry: entry_b, .. }: &SortEntry,
         options: SortOptions,
-    ) -> Result<()> {
-        entries.par_sort_unstable_by(|(_path_a, entry_a), (_path_b, entry_b)| {
-            if options.dirs_first {
-                let a_is_dir = entry_a.metadata.as_ref().is_some_and(|m| m.is_dir);
-                let b_is_dir = entry_b.metadata.as_ref().is_some_and(|m| m.is_dir);
-
-                match (a_is_dir, b_is_dir) {
-                    (true, false) => {
-                        return if options.reverse {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            std::cmp::Ordering::Less
-                        }
-                    }
-                    (false, true) => {
-                        return if options.reverse {
-                            std::cmp::Ordering::Less
-                        } else {
-                            std::cmp::Ordering::Greater
-                        }
-                    }
-                    _ => {}
-                }
-            }
-
-            let time_a = entry_a.metadata.as_ref().map_or(0, |m| m.modified);
-            let time_b = entry_b.metadata.as_ref().map_or(0, |m| m.modified);
-            let date_order = time_a.cmp(&time_b);
-
-            if options.reverse {
-                date_order.reverse()
-            } else {
-                date_order
-            }
-        });
+    ) -> Ordering {
+        let time_a = entry_a.metadata.as_ref().map_or(0, |m| self.time(m));
+        let time_b = entry_b.metadata.as_ref().map_or(0, |m| self.time(m));
+        let date_order = time_a.cmp(&time_b);
 
-        Ok(())
+        if options.reverse {
+            date_order.reverse()
+        } else {
+            date_order
+        }
     }
 }
@@ -0,0 +1,37 @@
+use super::{natural_cmp, FileSorter, SortEntry, SortOptions};
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// Orders entries by extension, those without one first, like `ls -X`.
+pub struct ExtensionSorter;
+
+fn extension(path: &Path, case_sensitive: bool) -> String {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if case_sensitive {
+        extension
+    } else {
+        extension.to_lowercase()
+    }
+}
+
+impl FileSorter for ExtensionSorter {
+    fn compare(
+        &self,
+        SortEntry { path: path_a, .. }: &SortEntry,
+        SortEntry { path: path_b, .. }: &SortEntry,
+        options: SortOptions,
+    ) -> Ordering {
+        let order = natural_cmp(
+            &extension(path_a, options.case_sensitive),
+            &extension(path_b, options.case_sensitive),
+        );
+        if options.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::{natural_cmp, FileSorter, SortEntry, SortOptions};
+use lla_plugin_interface::manifest::FieldType;
+use lla_plugin_interface::proto::{typed_value::Value, DecoratedEntry};
+use std::cmp::Ordering;
+
+/// Orders entries by a plugin field, compared according to its declared
+/// type. Entries without the field go last in either direction.
+pub struct FieldSorter {
+    field: String,
+    field_type: FieldType,
+}
+
+enum SortValue<'a> {
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+    Boolean(bool),
+    Text(&'a str),
+}
+
+impl FieldSorter {
+    pub fn new(field: String, field_type: FieldType) -> Self {
+        FieldSorter { field, field_type }
+    }
+
+    fn sort_value<'a>(&self, entry: &'a DecoratedEntry) -> Option<SortValue<'a>> {
+        let typed = entry
+            .typed_fields
+            .get(&self.field)
+            .and_then(|value| value.value.as_ref());
+        let text = entry.custom_fields.get(&self.field).map(|text| text.trim());
+        match self.field_type {
+            FieldType::Integer => match typed {
+                Some(Value::IntegerValue(value)) => Some(*value),
+                _ => text?.parse().ok(),
+            }
+            .map(SortValue::Signed),
+            FieldType::Bytes | FieldType::Timestamp => match typed {
+                Some(Value::BytesValue(value) | Value::TimestampValue(value)) => Some(*value),
+                _ => text?.parse().ok(),
+            }
+            .map(SortValue::Unsigned),
+            FieldType::Float => match typed {
+                Some(Value::FloatValue(value)) => Some(*value),
+                Some(Value::IntegerValue(value)) => Some(*value as f64),
+                _ => text?.parse().ok(),
+            }
+            .map(SortValue::Float),
+            FieldType::Boolean => match typed {
+                Some(Value::BooleanValue(value)) => Some(*value),
+                _ => text?.parse().ok(),
+            }
+            .map(SortValue::Boolean),
+            FieldType::String | FieldType::Path => match typed {
+                Some(Value::StringValue(value) | Value::PathValue(value)) => Some(value.as_str()),
+                _ => text,
+            }
+            .map(SortValue::Text),
+        }
+    }
+}
+
+fn compare(a: &SortValue, b: &SortValue, case_sensitive: bool) -> Ordering {
+    match (a, b) {
+        (SortValue::Signed(a), SortValue::Signed(b)) => a.cmp(b),
+        (SortValue::Unsigned(a), SortValue::Unsigned(b)) => a.cmp(b),
+        (SortValue::Float(a), SortValue::Float(b)) => a.total_cmp(b),
+        (SortValue::Boolean(a), SortValue::Boolean(b)) => a.cmp(b),
+        (SortValue::Text(a), SortValue::Text(b)) if case_sensitive => natural_cmp(a, b),
+        (SortValue::Text(a), SortValue::Text(b)) => {
+            natural_cmp(&a.to_lowercase(), &b.to_lowercase())
+        }
+        _ => Ordering::Equal,
+    }
+}
+
+impl FileSorter for FieldSorter {
+    fn compare(
+        &self,
+        SortEntry { entry: entry_a, .. }: &SortEntry,
+        SortEntry { entry: entry_b, .. }: &SortEntry,
+        options: SortOptions,
+    ) -> Ordering {
+        match (self.sort_value(entry_a), self.sort_value(entry_b)) {
+            (Some(a), Some(b)) => {
+                let order = compare(&a, &b, options.case_sensitive);
+                if options.reverse {
+                    order.reverse()
+                } else {
+                    order
+                }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorter::SortKeys;
+
+    fn entry(path: &str, complexity: Option<&str>) -> DecoratedEntry {
+        let mut entry = DecoratedEntry {
+            path: path.to_string(),
+            ..Default::default()
+        };
+        if let Some(value) = complexity {
+            entry
+                .custom_fields
+                .insert("complexity".to_string(), value.to_string());
+        }
+        entry
+    }
+
+    fn sorted(field_type: FieldType, reverse: bool, entries: &[DecoratedEntry]) -> Vec<String> {
+        let keys: Vec<SortKeys> = entries.iter().map(|_| SortKeys::default()).collect();
+        let mut pairs: Vec<_> = entries
+            .iter()
+            .zip(&keys)
+            .map(|(e, k)| SortEntry::new(e, k))
+            .collect();
+        FieldSorter::new("complexity".to_string(), field_type)
+            .sort_files_with_metadata(
+                &mut pairs,
+                SortOptions {
+                    reverse,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        pairs
+            .iter()
+            .map(|sorted| sorted.entry.path.clone())
+            .collect()
+    }
+
+    #[test]
+    fn sorts_by_declared_type_with_missing_values_last() {
+        let entries = [
+            entry("a", Some("10")),
+            entry("b", None),
+            entry("c", Some("9")),
+            entry("d", Some("x")),
+        ];
+        assert_eq!(
+            sorted(FieldType::Integer, false, &entries),
+            ["c", "a", "b", "d"]
+        );
+        assert_eq!(
+            sorted(FieldType::Integer, true, &entries),
+            ["a", "c", "b", "d"]
+        );
+        // As strings, 9 and 10 still sort naturally.
+        assert_eq!(
+            sorted(FieldType::String, false, &entries),
+            ["c", "a", "d", "b"]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use super::{FileSorter, SortEntry, SortOptions};
+use crate::utils::fs_metadata::entry_kind;
+use lla_plugin_interface::proto::{DecoratedEntry, FileKind};
+use std::cmp::Ordering;
+
+/// Orders entries by file type: directories, symlinks, regular files, then
+/// special files.
+pub struct KindSorter;
+
+fn rank(entry: &DecoratedEntry) -> u8 {
+    let Some(metadata) = entry.metadata.as_ref() else {
+        return u8::MAX;
+    };
+    match entry_kind(metadata) {
+        FileKind::Directory => 0,
+        FileKind::Symlink => 1,
+        FileKind::Regular => 2,
+        FileKind::Fifo => 3,
+        FileKind::Socket => 4,
+        FileKind::BlockDevice => 5,
+        FileKind::CharDevice => 6,
+        FileKind::Unknown => 7,
+    }
+}
+
+impl FileSorter for KindSorter {
+    fn compare(
+        &self,
+        SortEntry { entry: entry_a, .. }: &SortEntry,
+        SortEntry { entry: entry_b, .. }: &SortEntry,
+        options: SortOptions,
+    ) -> Ordering {
+        let order = rank(entry_a).cmp(&rank(entry_b));
+        if options.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
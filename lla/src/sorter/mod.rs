@@ -1,5 +1,9 @@
 use crate::error::Result;
-use std::path::PathBuf;
+use lla_plugin_interface::proto::DecoratedEntry;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::path::Path;
+use std::sync::OnceLock;
 
 #[derive(Clone, Copy, Default)]
 pub struct SortOptions {
@@ -7,24 +11,169 @@ pub struct SortOptions {
     pub dirs_first: bool,
     pub case_sensitive: bool,
     pub natural: bool,
+    pub collation: Collation,
+}
+
+/// An entry being sorted. It only borrows the entry, so building one for a
+/// comparison allocates nothing.
+#[derive(Clone, Copy)]
+pub struct SortEntry<'a> {
+    pub path: &'a Path,
+    pub entry: &'a DecoratedEntry,
+    keys: &'a SortKeys,
+}
+
+impl<'a> SortEntry<'a> {
+    pub fn new(entry: &'a DecoratedEntry, keys: &'a SortKeys) -> Self {
+        SortEntry {
+            path: Path::new(&entry.path),
+            entry,
+            keys,
+        }
+    }
+}
+
+/// Sort keys that are costly to derive from an entry, such as an owner's
+/// name or a normalized file name. Each is computed the first time a comparison needs it and reused by
+/// every later comparison, so keep one `SortKeys` per entry for a whole sort.
+#[derive(Default)]
+pub struct SortKeys {
+    owner: OnceLock<ownership::OwnerKey>,
+    group: OnceLock<ownership::OwnerKey>,
+    name: OnceLock<collation::NameKey>,
 }
 
 pub trait FileSorter: Send + Sync {
+    /// Compares two entries on this key alone, in its usual direction, or
+    /// the opposite one when `options.reverse` is set.
+    fn compare(&self, a: &SortEntry, b: &SortEntry, options: SortOptions) -> Ordering;
+
+    /// Sorts `entries` by `compare`, after directories when `dirs_first` is
+    /// set. The sort is stable.
     fn sort_files_with_metadata(
         &self,
-        entries: &mut [(PathBuf, &DecoratedEntry)],
+        entries: &mut [SortEntry],
         options: SortOptions,
-    ) -> Result<()>;
+    ) -> Result<()> {
+        entries.par_sort_by(|a, b| {
+            dirs_first_order(a.entry, b.entry, options).then_with(|| self.compare(a, b, options))
+        });
+        Ok(())
+    }
 }
 
 mod alphabetical;
+mod collation;
 mod date;
+mod extension;
+mod field;
+mod kind;
+mod multi;
+mod numeric;
+mod ownership;
+mod score;
 mod size;
+mod top_k;
+mod version;
 
 pub use alphabetical::AlphabeticalSorter;
+pub use collation::Collation;
 pub use date::DateSorter;
-use lla_plugin_interface::proto::DecoratedEntry;
+pub use extension::ExtensionSorter;
+pub use field::FieldSorter;
+pub use kind::KindSorter;
+pub use multi::MultiKeySorter;
+pub use numeric::NumericSorter;
+pub use ownership::OwnershipSorter;
+pub use score::ScoreSorter;
 pub use size::SizeSorter;
+pub use top_k::{EntryLimit, TopK};
+pub use version::VersionSorter;
+
+/// The built-in sort keys, besides `score` and `field:<name>`.
+pub const SORT_KEYS: &[&str] = &[
+    "name",
+    "size",
+    "date",
+    "modified",
+    "created",
+    "accessed",
+    "extension",
+    "kind",
+    "owner",
+    "group",
+    "inode",
+    "links",
+    "version",
+];
+
+/// One key of a `--sort` list such as `size,-modified,name`. A leading `-`
+/// flips the key's usual direction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortKey {
+    pub name: String,
+    pub reverse: bool,
+}
+
+impl SortKey {
+    /// Splits a comma-separated sort list. `modified` is another name for
+    /// `date`.
+    pub fn parse_list(spec: &str) -> Vec<SortKey> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                let (name, reverse) = match key.strip_prefix('-') {
+                    Some(name) => (name.trim(), true),
+                    None => (key, false),
+                };
+                SortKey {
+                    name: match name {
+                        "modified" => "date",
+                        name => name,
+                    }
+                    .to_string(),
+                    reverse,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether `key` names a sort order: one of `SORT_KEYS`, `score` for fuzzy
+/// queries, or `field:<name>` for a plugin field.
+pub fn is_valid_sort_key(key: &str) -> bool {
+    match key.strip_prefix("field:") {
+        Some(field) => !field.is_empty(),
+        None => key == "score" || SORT_KEYS.contains(&key),
+    }
+}
+
+/// Whether `spec` is a comma-separated list of sort keys, each optionally
+/// prefixed with `-`.
+pub fn is_valid_sort_spec(spec: &str) -> bool {
+    let keys: Vec<&str> = spec.split(',').map(str::trim).collect();
+    keys.iter().all(|key| {
+        let key = key.strip_prefix('-').map_or(*key, str::trim);
+        is_valid_sort_key(key)
+    })
+}
+
+/// Directories before files when `dirs_first` is set, after them when the
+/// sort is also reversed.
+fn dirs_first_order(a: &DecoratedEntry, b: &DecoratedEntry, options: SortOptions) -> Ordering {
+    if !options.dirs_first {
+        return Ordering::Equal;
+    }
+    let a_is_dir = a.metadata.as_ref().is_some_and(|m| m.is_dir);
+    let b_is_dir = b.metadata.as_ref().is_some_and(|m| m.is_dir);
+    let order = b_is_dir.cmp(&a_is_dir);
+    if options.reverse {
+        order.reverse()
+    } else {
+        order
+    }
+}
 
 pub(crate) fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
     let mut a_chars = a.chars().peekable();
@@ -0,0 +1,151 @@
+use super::{FileSorter, SortEntry, SortOptions};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// Orders entries by a list of keys, each breaking the ties of the one
+/// before it. A key's flag flips its direction on top of `options.reverse`.
+pub struct MultiKeySorter {
+    keys: Vec<(Arc<dyn FileSorter + Send + Sync>, bool)>,
+    tie_break: Option<Arc<dyn FileSorter + Send + Sync>>,
+}
+
+impl MultiKeySorter {
+    pub fn new(keys: Vec<(Arc<dyn FileSorter + Send + Sync>, bool)>) -> Self {
+        MultiKeySorter {
+            keys,
+            tie_break: None,
+        }
+    }
+
+    /// Orders entries equal on every key by `sorter`, always in its usual
+    /// direction, so reversing the listing leaves ties in a stable order.
+    pub fn with_tie_break(mut self, sorter: Arc<dyn FileSorter + Send + Sync>) -> Self {
+        self.tie_break = Some(sorter);
+        self
+    }
+}
+
+impl FileSorter for MultiKeySorter {
+    fn compare(&self, a: &SortEntry, b: &SortEntry, options: SortOptions) -> Ordering {
+        self.keys
+            .iter()
+            .map(|(sorter, reverse)| {
+                let options = SortOptions {
+                    reverse: options.reverse != *reverse,
+                    ..options
+                };
+                sorter.compare(a, b, options)
+            })
+            .find(|order| order.is_ne())
+            .unwrap_or_else(|| match &self.tie_break {
+                Some(sorter) => sorter.compare(
+                    a,
+                    b,
+                    SortOptions {
+                        reverse: false,
+                        ..options
+                    },
+                ),
+                None => Ordering::Equal,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorter::{AlphabeticalSorter, DateSorter, SizeSorter, SortKeys};
+    use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata};
+
+    fn entry(path: &str, size: u64, modified: u64, is_dir: bool) -> DecoratedEntry {
+        DecoratedEntry {
+            path: path.to_string(),
+            metadata: Some(EntryMetadata {
+                size,
+                modified,
+                is_dir,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn sorted(entries: &[DecoratedEntry], options: SortOptions) -> Vec<String> {
+        let sorter = MultiKeySorter::new(vec![
+            (Arc::new(SizeSorter), false),
+            (Arc::new(DateSorter::Modified), true),
+            (Arc::new(AlphabeticalSorter), false),
+        ]);
+        let keys: Vec<SortKeys> = entries.iter().map(|_| SortKeys::default()).collect();
+        let mut pairs: Vec<_> = entries
+            .iter()
+            .zip(&keys)
+            .map(|(e, k)| SortEntry::new(e, k))
+            .collect();
+        sorter
+            .sort_files_with_metadata(&mut pairs, options)
+            .unwrap();
+        pairs
+            .iter()
+            .map(|sorted| sorted.entry.path.clone())
+            .collect()
+    }
+
+    #[test]
+    fn later_keys_break_ties_in_their_own_direction() {
+        let entries = [
+            entry("b", 10, 1, false),
+            entry("a", 10, 1, false),
+            entry("old", 10, 0, false),
+            entry("big", 20, 0, false),
+            entry("dir", 0, 5, true),
+        ];
+        let options = SortOptions {
+            natural: true,
+            ..Default::default()
+        };
+        // Size descending, then newest first, then by name.
+        assert_eq!(sorted(&entries, options), ["big", "a", "b", "old", "dir"]);
+
+        let options = SortOptions {
+            dirs_first: true,
+            ..options
+        };
+        assert_eq!(sorted(&entries, options), ["dir", "big", "a", "b", "old"]);
+
+        let options = SortOptions {
+            reverse: true,
+            ..options
+        };
+        assert_eq!(sorted(&entries, options), ["old", "b", "a", "big", "dir"]);
+    }
+
+    #[test]
+    fn reversing_keeps_the_tie_break_ascending() {
+        let entries = [
+            entry("b", 10, 0, false),
+            entry("a", 10, 0, false),
+            entry("big", 20, 0, false),
+        ];
+        let sorter = MultiKeySorter::new(vec![(Arc::new(SizeSorter), false)])
+            .with_tie_break(Arc::new(AlphabeticalSorter));
+        let keys: Vec<SortKeys> = entries.iter().map(|_| SortKeys::default()).collect();
+        let mut pairs: Vec<_> = entries
+            .iter()
+            .zip(&keys)
+            .map(|(e, k)| SortEntry::new(e, k))
+            .collect();
+        let options = SortOptions {
+            reverse: true,
+            ..Default::default()
+        };
+        sorter
+            .sort_files_with_metadata(&mut pairs, options)
+            .unwrap();
+        let order: Vec<_> = pairs
+            .iter()
+            .map(|sorted| sorted.entry.path.as_str())
+            .collect();
+        assert_eq!(order, ["a", "b", "big"]);
+    }
+}
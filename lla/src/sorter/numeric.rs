@@ -0,0 +1,36 @@
+use super::{FileSorter, SortEntry, SortOptions};
+use lla_plugin_interface::proto::EntryMetadata;
+use std::cmp::Ordering;
+
+/// Orders entries by a numeric metadata value, smallest first.
+pub enum NumericSorter {
+    Inode,
+    Links,
+}
+
+impl NumericSorter {
+    fn value(&self, metadata: &EntryMetadata) -> u64 {
+        match self {
+            NumericSorter::Inode => metadata.inode,
+            NumericSorter::Links => metadata.hard_links,
+        }
+    }
+}
+
+impl FileSorter for NumericSorter {
+    fn compare(
+        &self,
+        SortEntry { entry: entry_a, .. }: &SortEntry,
+        SortEntry { entry: entry_b, .. }: &SortEntry,
+        options: SortOptions,
+    ) -> Ordering {
+        let value_a = entry_a.metadata.as_ref().map_or(0, |m| self.value(m));
+        let value_b = entry_b.metadata.as_ref().map_or(0, |m| self.value(m));
+        let order = value_a.cmp(&value_b);
+        if options.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
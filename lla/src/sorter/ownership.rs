@@ -0,0 +1,89 @@
+use super::{natural_cmp, FileSorter, SortEntry, SortOptions};
+use crate::formatter::serializable::{gid_to_name, uid_to_name};
+use std::cmp::Ordering;
+
+/// Orders entries by owning user or group name. Ids without a name compare
+/// by number, after the named ones.
+pub enum OwnershipSorter {
+    Owner,
+    Group,
+}
+
+/// An entry's owner as sorted: the name, lowercased unless the sort is case
+/// sensitive, and the id.
+pub(super) type OwnerKey = (Option<String>, u32);
+
+impl OwnershipSorter {
+    fn key<'a>(&self, sorted: &SortEntry<'a>, options: SortOptions) -> &'a OwnerKey {
+        let slot = match self {
+            OwnershipSorter::Owner => &sorted.keys.owner,
+            OwnershipSorter::Group => &sorted.keys.group,
+        };
+        slot.get_or_init(|| {
+            let Some(metadata) = sorted.entry.metadata.as_ref() else {
+                return (None, u32::MAX);
+            };
+            let (name, id) = match self {
+                OwnershipSorter::Owner => (uid_to_name(metadata.uid), metadata.uid),
+                OwnershipSorter::Group => (gid_to_name(metadata.gid), metadata.gid),
+            };
+            let name = if options.case_sensitive {
+                name
+            } else {
+                name.map(|name| name.to_lowercase())
+            };
+            (name, id)
+        })
+    }
+}
+
+impl FileSorter for OwnershipSorter {
+    fn compare(&self, a: &SortEntry, b: &SortEntry, options: SortOptions) -> Ordering {
+        let order = match (self.key(a, options), self.key(b, options)) {
+            ((Some(a), _), (Some(b), _)) => natural_cmp(a, b),
+            ((Some(_), _), (None, _)) => Ordering::Less,
+            ((None, _), (Some(_), _)) => Ordering::Greater,
+            ((None, a), (None, b)) => a.cmp(b),
+        };
+        if options.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorter::SortKeys;
+    use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata};
+
+    fn owned_by(uid: u32) -> DecoratedEntry {
+        DecoratedEntry {
+            path: format!("{}", uid),
+            metadata: Some(EntryMetadata {
+                uid,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolves_each_owner_once_and_puts_unnamed_ids_last() {
+        let entries = [owned_by(4_000_000_000), owned_by(0)];
+        let keys = [SortKeys::default(), SortKeys::default()];
+        let unnamed = SortEntry::new(&entries[0], &keys[0]);
+        let root = SortEntry::new(&entries[1], &keys[1]);
+        let options = SortOptions::default();
+
+        assert_eq!(
+            OwnershipSorter::Owner.compare(&root, &unnamed, options),
+            Ordering::Less
+        );
+        assert_eq!(keys[0].owner.get(), Some(&(None, 4_000_000_000)));
+        assert_eq!(keys[1].owner.get(), Some(&(Some("root".to_string()), 0)));
+        assert!(keys[0].group.get().is_none());
+    }
+}
@@ -0,0 +1,35 @@
+use super::{FileSorter, SortEntry, SortOptions};
+use crate::formatter::serializable::FUZZY_SCORE_FIELD;
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::cmp::Ordering;
+
+/// Orders entries by fuzzy match score, best first. Entries without a score
+/// go last in either direction.
+pub struct ScoreSorter;
+
+fn score(entry: &DecoratedEntry) -> Option<i64> {
+    entry.custom_fields.get(FUZZY_SCORE_FIELD)?.parse().ok()
+}
+
+impl FileSorter for ScoreSorter {
+    fn compare(
+        &self,
+        SortEntry { entry: entry_a, .. }: &SortEntry,
+        SortEntry { entry: entry_b, .. }: &SortEntry,
+        options: SortOptions,
+    ) -> Ordering {
+        match (score(entry_a), score(entry_b)) {
+            (Some(a), Some(b)) => {
+                let order = b.cmp(&a);
+                if options.reverse {
+                    order.reverse()
+                } else {
+                    order
+                }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
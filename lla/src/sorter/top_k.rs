@@ -0,0 +1,172 @@
+use super::{dirs_first_order, FileSorter, SortEntry, SortKeys, SortOptions};
+use lla_plugin_interface::proto::DecoratedEntry;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// Which end of a sorted listing to keep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryLimit {
+    /// The first `n` entries, from `--limit` or `--head`.
+    Head(usize),
+    /// The last `n` entries, from `--tail`.
+    Tail(usize),
+}
+
+impl EntryLimit {
+    fn count(self) -> usize {
+        match self {
+            EntryLimit::Head(count) | EntryLimit::Tail(count) => count,
+        }
+    }
+}
+
+struct Ranked {
+    entry: DecoratedEntry,
+    keys: SortKeys,
+    seen: usize,
+}
+
+/// Picks the entries an `EntryLimit` keeps from a stream of entries without
+/// sorting them all.
+///
+/// The kept entries live in a binary heap whose root is the one that would
+/// be dropped next, so memory stays proportional to the limit and each
+/// offered entry costs one comparison, or `O(log n)` when it is kept. Ties
+/// keep the entries offered first, the way a stable sort would.
+pub struct TopK {
+    sorter: Arc<dyn FileSorter + Send + Sync>,
+    options: SortOptions,
+    limit: EntryLimit,
+    heap: Vec<Ranked>,
+    seen: usize,
+}
+
+impl TopK {
+    pub fn new(
+        sorter: Arc<dyn FileSorter + Send + Sync>,
+        options: SortOptions,
+        limit: EntryLimit,
+    ) -> Self {
+        TopK {
+            sorter,
+            options,
+            limit,
+            heap: Vec::with_capacity(limit.count().min(4096)),
+            seen: 0,
+        }
+    }
+
+    /// Orders entries by how much the limit wants them: `Less` for the one
+    /// to keep.
+    fn order(&self, a: &Ranked, b: &Ranked) -> Ordering {
+        let order = dirs_first_order(&a.entry, &b.entry, self.options)
+            .then_with(|| {
+                self.sorter.compare(
+                    &SortEntry::new(&a.entry, &a.keys),
+                    &SortEntry::new(&b.entry, &b.keys),
+                    self.options,
+                )
+            })
+            .then_with(|| a.seen.cmp(&b.seen));
+        match self.limit {
+            EntryLimit::Head(_) => order,
+            EntryLimit::Tail(_) => order.reverse(),
+        }
+    }
+
+    pub fn push(&mut self, entry: DecoratedEntry) {
+        let ranked = Ranked {
+            entry,
+            keys: SortKeys::default(),
+            seen: self.seen,
+        };
+        self.seen += 1;
+
+        if self.heap.len() < self.limit.count() {
+            self.heap.push(ranked);
+            self.sift_up(self.heap.len() - 1);
+        } else if !self.heap.is_empty() && self.order(&ranked, &self.heap[0]).is_lt() {
+            self.heap[0] = ranked;
+            self.sift_down(0);
+        }
+    }
+
+    pub fn extend(&mut self, entries: impl IntoIterator<Item = DecoratedEntry>) {
+        for entry in entries {
+            self.push(entry);
+        }
+    }
+
+    /// The kept entries, in no particular order.
+    pub fn into_entries(self) -> Vec<DecoratedEntry> {
+        self.heap.into_iter().map(|ranked| ranked.entry).collect()
+    }
+
+    fn sift_up(&mut self, mut child: usize) {
+        while child > 0 {
+            let parent = (child - 1) / 2;
+            if self.order(&self.heap[child], &self.heap[parent]).is_le() {
+                break;
+            }
+            self.heap.swap(child, parent);
+            child = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut parent: usize) {
+        loop {
+            let mut largest = parent;
+            for child in [2 * parent + 1, 2 * parent + 2] {
+                if child < self.heap.len()
+                    && self.order(&self.heap[child], &self.heap[largest]).is_gt()
+                {
+                    largest = child;
+                }
+            }
+            if largest == parent {
+                break;
+            }
+            self.heap.swap(parent, largest);
+            parent = largest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorter::SizeSorter;
+    use lla_plugin_interface::proto::EntryMetadata;
+
+    fn entry(path: &str, size: u64) -> DecoratedEntry {
+        DecoratedEntry {
+            path: path.to_string(),
+            metadata: Some(EntryMetadata {
+                size,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn kept(limit: EntryLimit) -> Vec<String> {
+        let mut top = TopK::new(Arc::new(SizeSorter), SortOptions::default(), limit);
+        top.extend((0..100).map(|i| entry(&format!("f{}", i), i % 10)));
+        let mut paths: Vec<String> = top
+            .into_entries()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn keeps_either_end_with_stable_ties() {
+        // Largest first: the three size-9 files seen first.
+        assert_eq!(kept(EntryLimit::Head(3)), ["f19", "f29", "f9"]);
+        // Smallest last: the three size-0 files seen last.
+        assert_eq!(kept(EntryLimit::Tail(3)), ["f70", "f80", "f90"]);
+        assert_eq!(kept(EntryLimit::Head(1000)).len(), 100);
+    }
+}
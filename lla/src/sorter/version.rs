@@ -0,0 +1,211 @@
+use super::{natural_cmp, FileSorter, SortEntry, SortOptions};
+use std::cmp::Ordering;
+
+/// Orders names by the version they carry, following semver precedence:
+/// `v1.9.2` before `v1.10.0`, and `1.0.0-rc.1` before `1.0.0`.
+pub struct VersionSorter;
+
+/// A name split around its first version: `lla-1.2.0-rc.1.tar.gz` is the
+/// prefix `lla-`, core `[1, 2, 0]`, pre-release `[rc, 1]` and suffix
+/// `.tar.gz`.
+struct Versioned<'a> {
+    prefix: &'a str,
+    core: Vec<u64>,
+    pre: Vec<&'a str>,
+    suffix: &'a str,
+}
+
+impl<'a> Versioned<'a> {
+    fn parse(name: &'a str) -> Option<Self> {
+        let start = name.find(|c: char| c.is_ascii_digit())?;
+        let (prefix, mut rest) = name.split_at(start);
+
+        let mut core = Vec::new();
+        loop {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            core.push(rest[..digits].parse().unwrap_or(u64::MAX));
+            rest = &rest[digits..];
+            match rest.strip_prefix('.') {
+                Some(next) if next.starts_with(|c: char| c.is_ascii_digit()) => rest = next,
+                _ => break,
+            }
+        }
+
+        // Pre-release identifiers run until a word follows a number, which
+        // is where an extension such as `.tar.gz` starts. Any other word after
+        // the dash, such as a platform in `tool-1.2-linux`, stays in the suffix.
+        let mut pre = Vec::new();
+        if let Some(mut identifiers) = rest
+            .strip_prefix('-')
+            .filter(|identifiers| starts_pre_release(identifiers))
+        {
+            let mut previous_numeric = false;
+            loop {
+                let end = identifiers
+                    .find(|c: char| !c.is_ascii_alphanumeric())
+                    .unwrap_or(identifiers.len());
+                let identifier = &identifiers[..end];
+                let numeric = identifier.bytes().all(|b| b.is_ascii_digit());
+                if identifier.is_empty() || (previous_numeric && !numeric) {
+                    break;
+                }
+                pre.push(identifier);
+                previous_numeric = numeric;
+                identifiers = &identifiers[end..];
+                rest = identifiers;
+                match identifiers.strip_prefix('.') {
+                    Some(next) => identifiers = next,
+                    None => break,
+                }
+            }
+        }
+
+        Some(Versioned {
+            prefix,
+            core,
+            pre,
+            suffix: rest,
+        })
+    }
+}
+
+const PRE_RELEASE_TAGS: [&str; 5] = ["alpha", "beta", "rc", "pre", "dev"];
+
+/// Whether the text after a version's dash opens a pre-release: a number, or
+/// a tag such as `alpha`, `beta2` or `rc` with optional trailing digits.
+fn starts_pre_release(identifiers: &str) -> bool {
+    let end = identifiers
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(identifiers.len());
+    let tag = identifiers[..end].trim_end_matches(|c: char| c.is_ascii_digit());
+    (tag.is_empty() && end > 0)
+        || PRE_RELEASE_TAGS
+            .iter()
+            .any(|known| tag.eq_ignore_ascii_case(known))
+}
+
+fn compare_core(a: &[u64], b: &[u64]) -> Ordering {
+    let parts = a.len().max(b.len());
+    (0..parts)
+        .map(|i| {
+            let a = a.get(i).copied().unwrap_or(0);
+            let b = b.get(i).copied().unwrap_or(0);
+            a.cmp(&b)
+        })
+        .find(|order| order.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Semver pre-release precedence: a release sorts after its pre-releases,
+/// numeric identifiers compare as numbers and before words, and a longer
+/// list wins when the shared identifiers are equal.
+fn compare_pre(a: &[&str], b: &[&str]) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        _ => {}
+    }
+    for (a, b) in a.iter().zip(b) {
+        let order = match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => a.cmp(b),
+        };
+        if order.is_ne() {
+            return order;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+pub(crate) fn version_cmp(a: &str, b: &str) -> Ordering {
+    match (Versioned::parse(a), Versioned::parse(b)) {
+        (Some(a), Some(b)) => natural_cmp(&a.prefix.to_lowercase(), &b.prefix.to_lowercase())
+            .then_with(|| compare_core(&a.core, &b.core))
+            .then_with(|| compare_pre(&a.pre, &b.pre))
+            .then_with(|| natural_cmp(a.suffix, b.suffix)),
+        _ => natural_cmp(&a.to_lowercase(), &b.to_lowercase()),
+    }
+}
+
+impl FileSorter for VersionSorter {
+    fn compare(
+        &self,
+        SortEntry { path: path_a, .. }: &SortEntry,
+        SortEntry { path: path_b, .. }: &SortEntry,
+        options: SortOptions,
+    ) -> Ordering {
+        let a_name = path_a.file_name().unwrap_or_default().to_string_lossy();
+        let b_name = path_b.file_name().unwrap_or_default().to_string_lossy();
+        let order = version_cmp(&a_name, &b_name);
+        if options.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_by_semver_precedence() {
+        let mut names = vec![
+            "v1.10.0",
+            "v1.9.2",
+            "v1.0.0",
+            "v1.0.0-rc.10",
+            "v1.0.0-rc.2",
+            "v1.0.0-alpha",
+            "v1.0.0-alpha.1",
+            "v1.0.0-beta",
+            "v2.0.0-rc.1",
+        ];
+        names.sort_by(|a, b| version_cmp(a, b));
+        assert_eq!(
+            names,
+            [
+                "v1.0.0-alpha",
+                "v1.0.0-alpha.1",
+                "v1.0.0-beta",
+                "v1.0.0-rc.2",
+                "v1.0.0-rc.10",
+                "v1.0.0",
+                "v1.9.2",
+                "v1.10.0",
+                "v2.0.0-rc.1",
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_extensions_out_of_the_pre_release() {
+        assert_eq!(
+            version_cmp("lla-1.2.0-rc.1.tar.gz", "lla-1.2.0.tar.gz"),
+            Ordering::Less
+        );
+        assert_eq!(
+            version_cmp("lla-1.2.0.zip", "lla-1.2.0.tar.gz"),
+            Ordering::Greater
+        );
+        assert_eq!(version_cmp("notes.txt", "Changes"), Ordering::Greater);
+    }
+
+    #[test]
+    fn treats_only_pre_release_tags_as_pre_releases() {
+        assert_eq!(version_cmp("tool-1.2-linux", "tool-1.2"), Ordering::Greater);
+        assert_eq!(
+            version_cmp("tool-1.2-linux", "tool-1.2-darwin"),
+            Ordering::Greater
+        );
+        assert_eq!(version_cmp("tool-1.2-beta2", "tool-1.2"), Ordering::Less);
+        assert_eq!(version_cmp("tool-1.2-RC1", "tool-1.2"), Ordering::Less);
+        assert_eq!(version_cmp("tool-1.2-1", "tool-1.2"), Ordering::Less);
+    }
+}
@@ -92,6 +92,14 @@ pub struct ThemeColors {
     pub symlink: ColorValue,
     #[serde(default = "default_executable_color")]
     pub executable: ColorValue,
+    #[serde(default = "default_fifo_color")]
+    pub fifo: ColorValue,
+    #[serde(default = "default_socket_color")]
+    pub socket: ColorValue,
+    #[serde(default = "default_device_color")]
+    pub block_device: ColorValue,
+    #[serde(default = "default_device_color")]
+    pub char_device: ColorValue,
     #[serde(default = "default_size_color")]
     pub size: ColorValue,
     #[serde(default = "default_date_color")]
@@ -132,6 +140,10 @@ impl Default for ThemeColors {
             directory: default_directory_color(),
             symlink: default_symlink_color(),
             executable: default_executable_color(),
+            fifo: default_fifo_color(),
+            socket: default_socket_color(),
+            block_device: default_device_color(),
+            char_device: default_device_color(),
             size: default_size_color(),
             date: default_date_color(),
             user: default_user_color(),
@@ -157,6 +169,15 @@ fn default_symlink_color() -> ColorValue {
 fn default_executable_color() -> ColorValue {
     ColorValue::Named("bright_green".to_string())
 }
+fn default_fifo_color() -> ColorValue {
+    ColorValue::Named("yellow".to_string())
+}
+fn default_socket_color() -> ColorValue {
+    ColorValue::Named("bright_magenta".to_string())
+}
+fn default_device_color() -> ColorValue {
+    ColorValue::Named("bright_yellow".to_string())
+}
 fn default_size_color() -> ColorValue {
     ColorValue::Named("green".to_string())
 }
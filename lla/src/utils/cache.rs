@@ -1,53 +1,137 @@
 use crate::error::{LlaError, Result};
+use crate::utils::fs_metadata;
 use chrono::Utc;
 use lla_plugin_interface::proto::{DecoratedEntry, EntryMetadata};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub struct ListingCache {
     base_dir: PathBuf,
 }
 
+/// Summary of the listings stored on disk, for `lla cache stats`.
+pub struct CacheStats {
+    pub directory: PathBuf,
+    pub listings: usize,
+    pub total_bytes: u64,
+    pub oldest: Option<SystemTime>,
+    pub newest: Option<SystemTime>,
+}
+
 impl ListingCache {
     pub fn new() -> Result<Self> {
         let mut root = dirs::cache_dir().unwrap_or_else(|| PathBuf::from("."));
         root = root.join("lla").join("listings");
-        fs::create_dir_all(&root)?;
-        Ok(Self { base_dir: root })
+        Self::in_dir(root)
     }
 
-    pub fn load(&self, key: &str) -> Result<Option<Vec<DecoratedEntry>>> {
-        let path = self.cache_path(key);
-        if !path.exists() {
+    fn in_dir(base_dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&base_dir)?;
+        Ok(Self { base_dir })
+    }
+
+    /// Loads a cached listing of `root` and brings it up to date. Directories
+    /// whose modification time or inode changed since the listing was saved,
+    /// and the parents of entries whose metadata no longer matches the disk,
+    /// are handed to `rewalk`, which must return the entries of that subtree
+    /// including the directory itself (except for `root`). Their cached
+    /// subtrees are replaced; everything else is reused as is.
+    ///
+    /// Returns `None` when there is no usable listing, including listings
+    /// written before fingerprints were recorded.
+    pub fn load_fresh<F>(
+        &self,
+        key: &str,
+        root: &Path,
+        mut rewalk: F,
+    ) -> Result<Option<Vec<DecoratedEntry>>>
+    where
+        F: FnMut(&Path) -> Result<Vec<DecoratedEntry>>,
+    {
+        let Some(listing) = self.read_listing(key)? else {
+            return Ok(None);
+        };
+        if listing.directories.is_empty() || Path::new(&listing.root) != root {
             return Ok(None);
         }
 
-        let bytes = fs::read(&path)?;
-        let listing: CachedListing = serde_json::from_slice(&bytes).map_err(|err| {
-            LlaError::Other(format!(
-                "Failed to read cached listing {}: {}",
-                path.display(),
-                err
-            ))
-        })?;
+        let entries: Vec<DecoratedEntry> = listing
+            .entries
+            .into_iter()
+            .map(DecoratedEntry::from)
+            .collect();
+        let stale = stale_subtrees(&listing.directories, &entries);
+        let mut entries: Vec<DecoratedEntry> = entries
+            .into_iter()
+            .filter(|entry| {
+                let path = Path::new(&entry.path);
+                !stale.iter().any(|dir| path.starts_with(dir))
+            })
+            .collect();
+        if stale.is_empty() {
+            return Ok(Some(entries));
+        }
 
-        Ok(Some(
-            listing
-                .entries
-                .into_iter()
-                .map(DecoratedEntry::from)
-                .collect(),
-        ))
+        for dir in &stale {
+            entries.extend(rewalk(dir)?);
+        }
+        self.save(
+            key,
+            &listing.context_summary,
+            root,
+            listing.recursive,
+            &entries,
+        )?;
+        Ok(Some(entries))
     }
 
-    pub fn save(&self, key: &str, summary: &str, entries: &[DecoratedEntry]) -> Result<()> {
+    /// Saves a listing of `root` together with fingerprints of the directories
+    /// it was built from: only `root` for flat listings, and every directory
+    /// that was descended into for recursive ones.
+    pub fn save(
+        &self,
+        key: &str,
+        summary: &str,
+        root: &Path,
+        recursive: bool,
+        entries: &[DecoratedEntry],
+    ) -> Result<()> {
+        let mut walked = BTreeSet::from([root.to_path_buf()]);
+        if recursive {
+            for entry in entries {
+                let path = Path::new(&entry.path);
+                if let Some(parent) = path.parent().filter(|p| p.starts_with(root)) {
+                    walked.insert(parent.to_path_buf());
+                }
+                let is_dir = entry
+                    .metadata
+                    .as_ref()
+                    .is_some_and(|meta| meta.is_dir && !meta.is_symlink);
+                if is_dir {
+                    walked.insert(path.to_path_buf());
+                }
+            }
+        }
+        let directories = walked
+            .into_iter()
+            .filter_map(|dir| {
+                let fingerprint = DirFingerprint::of(&dir)?;
+                Some((dir.to_string_lossy().into_owned(), fingerprint))
+            })
+            .collect();
+
         let path = self.cache_path(key);
         let listing = CachedListing {
             context_summary: summary.to_string(),
             generated_at: Utc::now().to_rfc3339(),
             entry_count: entries.len(),
+            root: root.to_string_lossy().into_owned(),
+            recursive,
+            directories,
             entries: entries.iter().map(SerializableEntry::from).collect(),
         };
 
@@ -58,16 +142,191 @@ impl ListingCache {
         Ok(())
     }
 
+    pub fn stats(&self) -> Result<CacheStats> {
+        let files = self.listing_files()?;
+        Ok(CacheStats {
+            directory: self.base_dir.clone(),
+            listings: files.len(),
+            total_bytes: files.iter().map(|file| file.size).sum(),
+            oldest: files.iter().map(|file| file.modified).min(),
+            newest: files.iter().map(|file| file.modified).max(),
+        })
+    }
+
+    /// Removes every cached listing and returns how many were removed.
+    pub fn clear(&self) -> Result<usize> {
+        let files = self.listing_files()?;
+        for file in &files {
+            fs::remove_file(&file.path)?;
+        }
+        Ok(files.len())
+    }
+
+    /// Removes listings older than `max_age`, then the oldest remaining ones
+    /// until the cache fits in `max_bytes`. Returns the number of listings and
+    /// bytes removed.
+    pub fn prune(&self, max_age: Duration, max_bytes: u64) -> Result<(usize, u64)> {
+        let mut files = self.listing_files()?;
+        files.sort_by_key(|file| file.modified);
+
+        let cutoff = SystemTime::now().checked_sub(max_age);
+        let mut total: u64 = files.iter().map(|file| file.size).sum();
+        let mut removed = (0usize, 0u64);
+        for file in &files {
+            let expired = cutoff.is_some_and(|cutoff| file.modified < cutoff);
+            let oversized = total > max_bytes;
+            if !expired && !oversized {
+                continue;
+            }
+            fs::remove_file(&file.path)?;
+            total -= file.size;
+            removed.0 += 1;
+            removed.1 += file.size;
+        }
+        Ok(removed)
+    }
+
+    fn read_listing(&self, key: &str) -> Result<Option<CachedListing>> {
+        let path = self.cache_path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&path)?;
+        let listing = serde_json::from_slice(&bytes).map_err(|err| {
+            LlaError::Other(format!(
+                "Failed to read cached listing {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        Ok(Some(listing))
+    }
+
+    fn listing_files(&self) -> Result<Vec<ListingFile>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.base_dir)?.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            files.push(ListingFile {
+                path,
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(UNIX_EPOCH),
+            });
+        }
+        Ok(files)
+    }
+
     fn cache_path(&self, key: &str) -> PathBuf {
         self.base_dir.join(format!("{}.json", key))
     }
 }
 
+struct ListingFile {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// The outermost directories whose fingerprint no longer matches or that hold
+/// an entry changed in place. Nested stale directories are covered by
+/// re-walking their ancestor.
+fn stale_subtrees(
+    directories: &BTreeMap<String, DirFingerprint>,
+    entries: &[DecoratedEntry],
+) -> Vec<PathBuf> {
+    let mut stale: Vec<PathBuf> = Vec::new();
+    // BTreeMap keys are sorted, but path order (component-wise) is what keeps
+    // a subtree contiguous, so sort again as paths.
+    let mut changed: Vec<PathBuf> = directories
+        .iter()
+        .filter(|(dir, fingerprint)| DirFingerprint::of(Path::new(dir)) != Some(**fingerprint))
+        .map(|(dir, _)| PathBuf::from(dir))
+        .collect();
+    // Editing a file in place leaves its directory's mtime alone, so every
+    // entry that is not itself fingerprinted is compared with the disk.
+    changed.extend(
+        entries
+            .par_iter()
+            .filter(|entry| !directories.contains_key(&entry.path) && changed_on_disk(entry))
+            .filter_map(|entry| Path::new(&entry.path).parent().map(Path::to_path_buf))
+            .collect::<Vec<_>>(),
+    );
+    changed.sort();
+    changed.dedup();
+    for dir in changed {
+        if stale.last().is_some_and(|last| dir.starts_with(last)) {
+            continue;
+        }
+        stale.push(dir);
+    }
+    stale
+}
+
+/// Whether the metadata a listing shows for `entry` no longer matches the
+/// disk. A symlink's entry may describe the link or its target, so either one
+/// matching counts. Access times are left out, since reading a file moves them.
+fn changed_on_disk(entry: &DecoratedEntry) -> bool {
+    let Some(cached) = entry.metadata.as_ref() else {
+        return false;
+    };
+    let path = Path::new(&entry.path);
+    let matches = |metadata: fs::Metadata| {
+        let current = fs_metadata::from_metadata(&metadata);
+        current.size == cached.size
+            && current.modified == cached.modified
+            && current.permissions == cached.permissions
+            && current.uid == cached.uid
+            && current.gid == cached.gid
+            && current.inode == cached.inode
+            && current.hard_links == cached.hard_links
+    };
+    let link_matches = fs::symlink_metadata(path).is_ok_and(matches);
+    let target_matches = || cached.is_symlink && fs::metadata(path).is_ok_and(matches);
+    !link_matches && !target_matches()
+}
+
+/// What a directory looked like when a listing was cached. Its mtime changes
+/// whenever an entry is created, removed or renamed inside it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct DirFingerprint {
+    modified_ns: u64,
+    inode: u64,
+}
+
+impl DirFingerprint {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified_ns = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos() as u64;
+        #[cfg(unix)]
+        let inode = std::os::unix::fs::MetadataExt::ino(&metadata);
+        #[cfg(not(unix))]
+        let inode = 0;
+        Some(Self { modified_ns, inode })
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 struct CachedListing {
     context_summary: String,
     generated_at: String,
     entry_count: usize,
+    #[serde(default)]
+    root: String,
+    #[serde(default)]
+    recursive: bool,
+    #[serde(default)]
+    directories: BTreeMap<String, DirFingerprint>,
     entries: Vec<SerializableEntry>,
 }
 
@@ -108,6 +367,8 @@ struct SerializableMetadata {
     mount_source: String,
     #[serde(default)]
     filesystem: String,
+    #[serde(default)]
+    kind: i32,
 }
 
 impl From<&DecoratedEntry> for SerializableEntry {
@@ -153,6 +414,7 @@ impl From<&EntryMetadata> for SerializableMetadata {
             mount_point: meta.mount_point.clone(),
             mount_source: meta.mount_source.clone(),
             filesystem: meta.filesystem.clone(),
+            kind: meta.kind,
         }
     }
 }
@@ -179,6 +441,122 @@ impl From<SerializableMetadata> for EntryMetadata {
             mount_point: meta.mount_point,
             mount_source: meta.mount_source,
             filesystem: meta.filesystem,
+            kind: meta.kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &Path) -> DecoratedEntry {
+        let metadata = fs::symlink_metadata(path).unwrap();
+        DecoratedEntry {
+            path: path.to_string_lossy().into_owned(),
+            metadata: Some(fs_metadata::from_metadata(&metadata)),
+            ..Default::default()
+        }
+    }
+
+    fn paths(entries: &[DecoratedEntry]) -> BTreeSet<String> {
+        entries.iter().map(|entry| entry.path.clone()).collect()
+    }
+
+    #[test]
+    fn rewalks_only_changed_subtrees() {
+        let tree = tempfile::tempdir().unwrap();
+        let root = tree.path();
+        for dir in ["stable", "changing"] {
+            fs::create_dir(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join("file"), b"").unwrap();
+        }
+        let entries: Vec<_> = ["stable", "stable/file", "changing", "changing/file"]
+            .iter()
+            .map(|path| entry(&root.join(path)))
+            .collect();
+
+        let cache = ListingCache::in_dir(tree.path().join(".cache")).unwrap();
+        cache.save("key", "{}", root, true, &entries).unwrap();
+
+        let unchanged = cache
+            .load_fresh("key", root, |dir| panic!("unexpected rewalk of {:?}", dir))
+            .unwrap()
+            .unwrap();
+        assert_eq!(paths(&unchanged), paths(&entries));
+
+        // Give filesystems with coarse timestamps a chance to tick.
+        std::thread::sleep(Duration::from_millis(20));
+        fs::write(root.join("changing/added"), b"").unwrap();
+        let mut rewalked = Vec::new();
+        let refreshed = cache
+            .load_fresh("key", root, |dir| {
+                rewalked.push(dir.to_path_buf());
+                Ok(["changing", "changing/file", "changing/added"]
+                    .iter()
+                    .map(|path| entry(&root.join(path)))
+                    .collect())
+            })
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(rewalked, vec![root.join("changing")]);
+        assert!(
+            paths(&refreshed).contains(&root.join("changing/added").to_string_lossy().into_owned())
+        );
+        assert!(
+            paths(&refreshed).contains(&root.join("stable/file").to_string_lossy().into_owned())
+        );
+    }
+
+    #[test]
+    fn rewalks_the_parent_of_a_file_edited_in_place() {
+        let tree = tempfile::tempdir().unwrap();
+        let root = tree.path();
+        fs::create_dir(root.join("sub")).unwrap();
+        fs::write(root.join("sub/file"), b"old").unwrap();
+        let listed = ["sub", "sub/file"];
+        let entries: Vec<_> = listed.iter().map(|path| entry(&root.join(path))).collect();
+
+        let cache = ListingCache::in_dir(tree.path().join(".cache")).unwrap();
+        cache.save("key", "{}", root, true, &entries).unwrap();
+
+        // Rewriting the file changes its size but not the directory's mtime.
+        let fingerprint = DirFingerprint::of(&root.join("sub"));
+        fs::write(root.join("sub/file"), b"longer").unwrap();
+        assert_eq!(DirFingerprint::of(&root.join("sub")), fingerprint);
+
+        let mut rewalked = Vec::new();
+        let refreshed = cache
+            .load_fresh("key", root, |dir| {
+                rewalked.push(dir.to_path_buf());
+                Ok(listed.iter().map(|path| entry(&root.join(path))).collect())
+            })
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(rewalked, vec![root.join("sub")]);
+        let file = refreshed
+            .iter()
+            .find(|entry| entry.path.ends_with("file"))
+            .unwrap();
+        assert_eq!(file.metadata.as_ref().unwrap().size, 6);
+    }
+
+    #[test]
+    fn prune_bounds_total_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ListingCache::in_dir(dir.path().to_path_buf()).unwrap();
+        for key in ["a", "b", "c"] {
+            fs::write(cache.cache_path(key), vec![b' '; 100]).unwrap();
         }
+
+        assert_eq!(
+            cache.prune(Duration::from_secs(3600), 250).unwrap(),
+            (1, 100)
+        );
+        assert_eq!(cache.stats().unwrap().listings, 2);
+        assert_eq!(cache.clear().unwrap(), 2);
+        assert_eq!(cache.stats().unwrap().total_bytes, 0);
     }
 }
@@ -1,6 +1,8 @@
 use crate::commands::args::Args;
 use crate::theme::{color_value_to_color, get_file_color, is_no_color, ColorValue, Theme};
+use crate::utils::fs_metadata::{entry_kind, file_kind, kind_from_mode};
 use colored::*;
+use lla_plugin_interface::proto::{EntryMetadata, FileKind};
 use std::path::Path;
 use std::sync::OnceLock;
 
@@ -18,7 +20,9 @@ fn get_color(color_value: &ColorValue) -> Color {
     color_value_to_color(color_value)
 }
 
-pub fn colorize_file_name(path: &Path) -> ColoredString {
+/// Colors a name by its file type and the theme. Listed entries pass their
+/// metadata so special files are recognized without another `lstat`.
+pub fn colorize_file_name(path: &Path, metadata: Option<&EntryMetadata>) -> ColoredString {
     let name = path
         .file_name()
         .and_then(|n| n.to_str())
@@ -46,6 +50,8 @@ pub fn colorize_file_name(path: &Path) -> ColoredString {
         name.color(get_color(&theme.colors.symlink))
             .italic()
             .underline()
+    } else if let Some(color) = special_file_color(path, metadata, theme) {
+        name.color(color)
     } else if is_executable(path) {
         name.color(get_color(&theme.colors.executable)).bold()
     } else if let Some(color) = get_extension_color(path) {
@@ -55,7 +61,11 @@ pub fn colorize_file_name(path: &Path) -> ColoredString {
     }
 }
 
-pub fn colorize_file_name_with_icon(path: &Path, content: String) -> ColoredString {
+pub fn colorize_file_name_with_icon(
+    path: &Path,
+    metadata: Option<&EntryMetadata>,
+    content: String,
+) -> ColoredString {
     let parts: Vec<&str> = content.split(' ').collect();
     if parts.len() != 2 {
         return if is_no_color() {
@@ -65,36 +75,78 @@ pub fn colorize_file_name_with_icon(path: &Path, content: String) -> ColoredStri
         };
     }
 
-    let icon = parts[0];
-    let name = parts[1];
+    colorize_as_file(path, metadata, content)
+}
 
+/// Colors any text, such as an icon or a full path, the way the theme colors
+/// the name of the file at `path`.
+pub fn colorize_as_file(
+    path: &Path,
+    metadata: Option<&EntryMetadata>,
+    text: String,
+) -> ColoredString {
     if is_no_color() {
-        return format!("{} {}", icon, name).normal();
+        return text.normal();
     }
 
     let theme = get_theme();
 
     if path.is_dir() {
         if let Some(color) = get_file_color(path) {
-            format!("{} {}", icon, name).color(color).bold()
+            text.color(color).bold()
         } else {
-            format!("{} {}", icon, name)
-                .color(get_color(&theme.colors.directory))
-                .bold()
+            text.color(get_color(&theme.colors.directory)).bold()
         }
     } else if path.is_symlink() {
-        format!("{} {}", icon, name)
-            .color(get_color(&theme.colors.symlink))
+        text.color(get_color(&theme.colors.symlink))
             .italic()
             .underline()
+    } else if let Some(color) = special_file_color(path, metadata, theme) {
+        text.color(color)
     } else if is_executable(path) {
-        format!("{} {}", icon, name)
-            .color(get_color(&theme.colors.executable))
-            .bold()
+        text.color(get_color(&theme.colors.executable)).bold()
     } else if let Some(color) = get_extension_color(path) {
-        format!("{} {}", icon, name).color(color)
+        text.color(color)
     } else {
-        format!("{} {}", icon, name).color(get_color(&theme.colors.file))
+        text.color(get_color(&theme.colors.file))
+    }
+}
+
+/// Theme color for FIFOs, sockets and devices.
+fn special_file_color(
+    path: &Path,
+    metadata: Option<&EntryMetadata>,
+    theme: &Theme,
+) -> Option<Color> {
+    let kind = match metadata {
+        Some(metadata) => entry_kind(metadata),
+        None => file_kind(&path.symlink_metadata().ok()?.file_type()),
+    };
+    let color = match kind {
+        FileKind::Fifo => &theme.colors.fifo,
+        FileKind::Socket => &theme.colors.socket,
+        FileKind::BlockDevice => &theme.colors.block_device,
+        FileKind::CharDevice => &theme.colors.char_device,
+        _ => return None,
+    };
+    Some(get_color(color))
+}
+
+/// `ls -F` indicator appended to a name: `/` for directories, `*` for
+/// executables, `@` for symlinks, `|` for FIFOs and `=` for sockets.
+pub fn classify_indicator(path: &Path, metadata: Option<&EntryMetadata>) -> &'static str {
+    let Some(metadata) = metadata else {
+        return "";
+    };
+    match entry_kind(metadata) {
+        // colorize_file_name already writes the slash in these cases.
+        FileKind::Directory if is_no_color() || get_file_color(path).is_some() => "",
+        FileKind::Directory => "/",
+        FileKind::Symlink => "@",
+        FileKind::Fifo => "|",
+        FileKind::Socket => "=",
+        FileKind::Regular if metadata.permissions & 0o111 != 0 => "*",
+        _ => "",
     }
 }
 
@@ -109,6 +161,10 @@ pub fn colorize_size(size: u64) -> ColoredString {
         format!("{:.1}G", size as f64 / (1024.0 * 1024.0 * 1024.0))
     };
 
+    colorize_size_text(formatted)
+}
+
+pub fn colorize_size_text(formatted: String) -> ColoredString {
     if is_no_color() {
         formatted.normal()
     } else {
@@ -152,12 +208,9 @@ pub fn colorize_permissions(mode: u32, format: Option<&str>) -> String {
 }
 
 fn format_symbolic_permissions(mode: u32, theme: &Theme) -> String {
-    let file_type = if mode & 0o170000 == 0o120000 {
-        "l".color(get_color(&theme.colors.permission_dir))
-    } else if mode & 0o170000 == 0o040000 {
-        "d".color(get_color(&theme.colors.permission_dir))
-    } else {
-        "-".color(get_color(&theme.colors.permission_none))
+    let file_type = match symbolic_type_char(mode) {
+        "-" => "-".color(get_color(&theme.colors.permission_none)),
+        kind => kind.color(get_color(&theme.colors.permission_dir)),
     };
     let user = triplet(mode, 6);
     let group = triplet(mode, 3);
@@ -410,13 +463,7 @@ fn format_permissions_no_color(mode: u32, format: Option<&str>) -> String {
             )
         }
         _ => {
-            let file_type = if mode & 0o170000 == 0o120000 {
-                "l"
-            } else if mode & 0o170000 == 0o040000 {
-                "d"
-            } else {
-                "-"
-            };
+            let file_type = symbolic_type_char(mode);
             let user = triplet_no_color(mode, 6);
             let group = triplet_no_color(mode, 3);
             let other = triplet_no_color(mode, 0);
@@ -425,6 +472,19 @@ fn format_permissions_no_color(mode: u32, format: Option<&str>) -> String {
     }
 }
 
+/// First character of `ls -l` permissions for the file type in `mode`.
+fn symbolic_type_char(mode: u32) -> &'static str {
+    match kind_from_mode(mode) {
+        FileKind::Directory => "d",
+        FileKind::Symlink => "l",
+        FileKind::Fifo => "p",
+        FileKind::Socket => "s",
+        FileKind::BlockDevice => "b",
+        FileKind::CharDevice => "c",
+        _ => "-",
+    }
+}
+
 fn triplet(mode: u32, shift: u32) -> String {
     let theme = get_theme();
     let r = if mode >> (shift + 2) & 1u32 != 0 {
@@ -580,6 +640,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn no_color_symbolic_permissions_show_special_file_types() {
+        assert_eq!(
+            format_permissions_no_color(0o010644, Some("symbolic")),
+            "prw-r--r--"
+        );
+        assert_eq!(
+            format_permissions_no_color(0o140755, Some("symbolic")),
+            "srwxr-xr-x"
+        );
+        assert_eq!(
+            format_permissions_no_color(0o020620, Some("symbolic")),
+            "crw--w----"
+        );
+    }
+
+    #[test]
+    fn classify_indicators_follow_ls() {
+        let metadata = |kind: FileKind, permissions: u32| EntryMetadata {
+            kind: kind as i32,
+            permissions,
+            ..Default::default()
+        };
+        let path = Path::new("entry");
+        let indicator =
+            |kind, permissions| classify_indicator(path, Some(&metadata(kind, permissions)));
+        assert_eq!(indicator(FileKind::Regular, 0o100755), "*");
+        assert_eq!(indicator(FileKind::Regular, 0o100644), "");
+        assert_eq!(indicator(FileKind::Symlink, 0o120777), "@");
+        assert_eq!(indicator(FileKind::Fifo, 0o010644), "|");
+        assert_eq!(indicator(FileKind::Socket, 0o140755), "=");
+        assert_eq!(indicator(FileKind::CharDevice, 0o020620), "");
+    }
+
     #[test]
     fn no_color_non_symbolic_permission_formats_are_unchanged() {
         assert_eq!(format_permissions_no_color(0o100644, Some("octal")), "-644");
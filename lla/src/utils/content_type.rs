@@ -0,0 +1,337 @@
+use crate::utils::fs_metadata::entry_kind;
+use lla_plugin_interface::proto::{EntryMetadata, FileKind};
+use object::elf::{self, FileHeader32, FileHeader64};
+use object::read::elf::{FileHeader, ProgramHeader};
+use object::Endianness;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read from the start of a file to classify it. Text detection looks
+/// at all of them, the same window Git uses to spot binary files.
+const HEADER_LEN: usize = 8192;
+
+/// A `--type` category.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentType {
+    Image,
+    Text,
+    Binary,
+    Executable,
+    Archive,
+}
+
+impl ContentType {
+    pub const NAMES: [&'static str; 5] = ["image", "text", "binary", "executable", "archive"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "image" => Some(ContentType::Image),
+            "text" => Some(ContentType::Text),
+            "binary" => Some(ContentType::Binary),
+            "executable" => Some(ContentType::Executable),
+            "archive" => Some(ContentType::Archive),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ContentType::Image => "image",
+            ContentType::Text => "text",
+            ContentType::Binary => "binary",
+            ContentType::Executable => "executable",
+            ContentType::Archive => "archive",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Class {
+    Image,
+    Archive,
+    Executable,
+    Script,
+    Document,
+    Text,
+    Binary,
+    /// Empty files, directories and other non-regular entries.
+    Other,
+}
+
+/// What a file's header says it contains.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sniffed {
+    pub mime: &'static str,
+    class: Class,
+}
+
+impl Sniffed {
+    fn new(mime: &'static str, class: Class) -> Self {
+        Sniffed { mime, class }
+    }
+
+    /// Scripts count as both text and executable; images, archives, PDFs and
+    /// native executables all count as binary.
+    pub fn is(&self, content_type: ContentType) -> bool {
+        match content_type {
+            ContentType::Image => self.class == Class::Image,
+            ContentType::Archive => self.class == Class::Archive,
+            ContentType::Executable => matches!(self.class, Class::Executable | Class::Script),
+            ContentType::Text => matches!(self.class, Class::Text | Class::Script),
+            ContentType::Binary => matches!(
+                self.class,
+                Class::Image | Class::Archive | Class::Executable | Class::Document | Class::Binary
+            ),
+        }
+    }
+}
+
+/// Classifies an entry by reading the start of its contents. Only regular
+/// files are opened, so FIFOs and devices never block the listing. Returns
+/// `None` when the file cannot be read.
+pub fn sniff(path: &Path, metadata: &EntryMetadata) -> Option<Sniffed> {
+    let mime = match entry_kind(metadata) {
+        FileKind::Regular => return sniff_file(path),
+        FileKind::Directory => "inode/directory",
+        FileKind::Symlink => "inode/symlink",
+        FileKind::Fifo => "inode/fifo",
+        FileKind::Socket => "inode/socket",
+        FileKind::BlockDevice => "inode/blockdevice",
+        FileKind::CharDevice => "inode/chardevice",
+        FileKind::Unknown => return None,
+    };
+    Some(Sniffed::new(mime, Class::Other))
+}
+
+/// The MIME type shown in the `mime` column and JSON output.
+pub fn mime(path: &Path, metadata: &EntryMetadata) -> Option<&'static str> {
+    sniff(path, metadata).map(|sniffed| sniffed.mime)
+}
+
+fn sniff_file(path: &Path) -> Option<Sniffed> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    File::open(path)
+        .ok()?
+        .take(HEADER_LEN as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+    Some(sniff_bytes(&header))
+}
+
+/// Classifies the first bytes of a file.
+pub fn sniff_bytes(header: &[u8]) -> Sniffed {
+    if header.is_empty() {
+        return Sniffed::new("inode/x-empty", Class::Other);
+    }
+    if let Some(sniffed) = signature(header).or_else(|| object_file(header)) {
+        return sniffed;
+    }
+    if let Some(interpreter) = header.strip_prefix(b"#!") {
+        return Sniffed::new(script_mime(interpreter), Class::Script);
+    }
+    if is_text(header) {
+        Sniffed::new("text/plain", Class::Text)
+    } else {
+        Sniffed::new("application/octet-stream", Class::Binary)
+    }
+}
+
+fn signature(header: &[u8]) -> Option<Sniffed> {
+    const SIGNATURES: &[(&[u8], &str, Class)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png", Class::Image),
+        (b"\xff\xd8\xff", "image/jpeg", Class::Image),
+        (b"GIF87a", "image/gif", Class::Image),
+        (b"GIF89a", "image/gif", Class::Image),
+        (b"II*\0", "image/tiff", Class::Image),
+        (b"MM\0*", "image/tiff", Class::Image),
+        (b"\0\0\x01\0", "image/x-icon", Class::Image),
+        (b"%PDF-", "application/pdf", Class::Document),
+        (b"PK\x03\x04", "application/zip", Class::Archive),
+        (b"PK\x05\x06", "application/zip", Class::Archive),
+        (b"\x1f\x8b", "application/gzip", Class::Archive),
+        (b"BZh", "application/x-bzip2", Class::Archive),
+        (b"\xfd7zXZ\0", "application/x-xz", Class::Archive),
+        (b"\x28\xb5\x2f\xfd", "application/zstd", Class::Archive),
+        (
+            b"7z\xbc\xaf\x27\x1c",
+            "application/x-7z-compressed",
+            Class::Archive,
+        ),
+        (b"Rar!\x1a\x07", "application/vnd.rar", Class::Archive),
+    ];
+
+    if let Some((_, mime, class)) = SIGNATURES
+        .iter()
+        .find(|(magic, _, _)| header.starts_with(magic))
+    {
+        return Some(Sniffed::new(mime, *class));
+    }
+    if header.starts_with(b"RIFF") && header.get(8..12) == Some(b"WEBP") {
+        return Some(Sniffed::new("image/webp", Class::Image));
+    }
+    if header.get(257..262) == Some(b"ustar") {
+        return Some(Sniffed::new("application/x-tar", Class::Archive));
+    }
+    None
+}
+
+fn object_file(header: &[u8]) -> Option<Sniffed> {
+    use object::FileKind as Kind;
+
+    let sniffed = match Kind::parse(header).ok()? {
+        Kind::Elf32 => Sniffed::new(
+            elf_mime::<FileHeader32<Endianness>>(header),
+            Class::Executable,
+        ),
+        Kind::Elf64 => Sniffed::new(
+            elf_mime::<FileHeader64<Endianness>>(header),
+            Class::Executable,
+        ),
+        Kind::MachO32 | Kind::MachO64 | Kind::MachOFat32 | Kind::MachOFat64 => {
+            Sniffed::new("application/x-mach-binary", Class::Executable)
+        }
+        Kind::Pe32 | Kind::Pe64 => Sniffed::new(
+            "application/vnd.microsoft.portable-executable",
+            Class::Executable,
+        ),
+        Kind::Archive => Sniffed::new("application/x-archive", Class::Archive),
+        // COFF magic is only two bytes, too weak to trust on its own.
+        _ => return None,
+    };
+    Some(sniffed)
+}
+
+/// Distinguishes ELF executables, shared objects, relocatable objects and
+/// core dumps by `e_type`. Position-independent executables are shared
+/// objects that request a program interpreter.
+fn elf_mime<Elf: FileHeader<Endian = Endianness>>(header: &[u8]) -> &'static str {
+    let Some((elf, endian)) = Elf::parse(header)
+        .ok()
+        .and_then(|elf| Some((elf, elf.endian().ok()?)))
+    else {
+        return "application/x-executable";
+    };
+    match elf.e_type(endian) {
+        elf::ET_REL => "application/x-object",
+        elf::ET_CORE => "application/x-coredump",
+        elf::ET_DYN => {
+            let interpreted = elf.program_headers(endian, header).is_ok_and(|headers| {
+                headers
+                    .iter()
+                    .any(|header| header.p_type(endian) == elf::PT_INTERP)
+            });
+            if interpreted {
+                "application/x-pie-executable"
+            } else {
+                "application/x-sharedlib"
+            }
+        }
+        _ => "application/x-executable",
+    }
+}
+
+/// Names the script type after the interpreter on the shebang line, looking
+/// past `/usr/bin/env`.
+fn script_mime(shebang: &[u8]) -> &'static str {
+    let line = shebang.split(|&b| b == b'\n').next().unwrap_or_default();
+    let line = String::from_utf8_lossy(line);
+    let mut words = line
+        .split_whitespace()
+        .map(|word| word.rsplit('/').next().unwrap_or(word));
+    let mut interpreter = words.next().unwrap_or_default();
+    if interpreter == "env" {
+        interpreter = words
+            .find(|word| !word.starts_with('-'))
+            .unwrap_or_default();
+    }
+    let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    match name {
+        "sh" | "bash" | "dash" | "zsh" | "ksh" | "fish" => "text/x-shellscript",
+        "python" => "text/x-python",
+        "perl" => "text/x-perl",
+        "ruby" => "text/x-ruby",
+        "node" | "deno" | "bun" => "text/javascript",
+        "php" => "text/x-php",
+        "lua" => "text/x-lua",
+        _ => "text/x-script",
+    }
+}
+
+/// UTF-8 or UTF-16 text, or single-byte text without control characters
+/// other than whitespace and escape. A NUL byte always means binary unless a
+/// UTF-16 byte order mark says otherwise.
+fn is_text(header: &[u8]) -> bool {
+    if header.starts_with(b"\xff\xfe") || header.starts_with(b"\xfe\xff") {
+        return true;
+    }
+    if header.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(header) {
+        Ok(_) => true,
+        // The header may end partway through a multi-byte character.
+        Err(error) if error.error_len().is_none() => true,
+        Err(_) => !header
+            .iter()
+            .any(|&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_common_signatures() {
+        let png = sniff_bytes(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+        assert_eq!(png.mime, "image/png");
+        assert!(png.is(ContentType::Image) && png.is(ContentType::Binary));
+        assert!(!png.is(ContentType::Text));
+
+        let gzip = sniff_bytes(b"\x1f\x8b\x08\0\0\0\0\0");
+        assert!(gzip.is(ContentType::Archive));
+
+        let mut tar = vec![b'a'; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(sniff_bytes(&tar).mime, "application/x-tar");
+
+        assert_eq!(sniff_bytes(b"%PDF-1.7\n").mime, "application/pdf");
+        assert!(!sniff_bytes(b"%PDF-1.7\n").is(ContentType::Archive));
+    }
+
+    #[test]
+    fn detects_native_executables_and_scripts() {
+        let mut elf = vec![0u8; 64];
+        elf[..7].copy_from_slice(b"\x7fELF\x02\x01\x01");
+        elf[16] = 2;
+        let sniffed = sniff_bytes(&elf);
+        assert_eq!(sniffed.mime, "application/x-executable");
+        assert!(sniffed.is(ContentType::Executable) && !sniffed.is(ContentType::Text));
+        elf[16] = 1;
+        assert_eq!(sniff_bytes(&elf).mime, "application/x-object");
+
+        let script = sniff_bytes(b"#!/usr/bin/env python3\nprint('hi')\n");
+        assert_eq!(script.mime, "text/x-python");
+        assert!(script.is(ContentType::Executable) && script.is(ContentType::Text));
+        assert_eq!(
+            sniff_bytes(b"#!/bin/bash\necho hi\n").mime,
+            "text/x-shellscript"
+        );
+    }
+
+    #[test]
+    fn separates_text_from_binary() {
+        assert!(sniff_bytes("fn main() {}\n// héllo".as_bytes()).is(ContentType::Text));
+        // Truncated in the middle of a multi-byte character.
+        assert!(sniff_bytes(&"é".as_bytes()[..1]).is(ContentType::Text));
+        assert!(sniff_bytes(b"\xff\xfeh\0i\0").is(ContentType::Text));
+        assert!(sniff_bytes(b"abc\0def").is(ContentType::Binary));
+
+        let empty = sniff_bytes(b"");
+        assert_eq!(empty.mime, "inode/x-empty");
+        assert!(ContentType::NAMES
+            .iter()
+            .all(|name| !empty.is(ContentType::parse(name).unwrap())));
+    }
+}
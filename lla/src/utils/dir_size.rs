@@ -0,0 +1,410 @@
+use crate::utils::exclude::ExcludeSet;
+use crate::utils::fs_metadata::{device_id, metadata_allocated_size};
+use crate::utils::llaignore::LlaIgnoreMode;
+use lla_plugin_interface::proto::EntryMetadata;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+
+/// Recursive size of a directory's contents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DirTotals {
+    /// Sum of file lengths (`st_size`).
+    pub apparent: u64,
+    /// Sum of bytes allocated on disk (`st_blocks * 512`).
+    pub allocated: u64,
+}
+
+impl DirTotals {
+    fn add(&mut self, other: DirTotals) {
+        self.apparent = self.apparent.saturating_add(other.apparent);
+        self.allocated = self.allocated.saturating_add(other.allocated);
+    }
+}
+
+/// Which size single-number views such as the tree and size map report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeBasis {
+    Apparent,
+    Allocated,
+}
+
+impl SizeBasis {
+    pub fn of(self, metadata: &EntryMetadata) -> u64 {
+        match self {
+            Self::Apparent => metadata.size,
+            Self::Allocated => metadata.allocated_size,
+        }
+    }
+}
+
+/// Totals for every directory below a set of roots, computed bottom-up from a
+/// single walk instead of one walk per listed directory.
+///
+/// Symlinks are not followed. A file with several hard links is counted once,
+/// in the directory of the link whose path sorts first. Links are claimed in
+/// that order after the parallel walk, so totals do not depend on which
+/// thread reaches a link first. With `one_file_system`, directories on
+/// another device than their root are left out, like `du -x`. Paths hidden
+/// by `.llaignore` are left out too.
+#[derive(Debug, Default)]
+pub struct DirSizeIndex {
+    totals: HashMap<PathBuf, DirTotals>,
+    seen: HashSet<(u64, u64)>,
+}
+
+impl DirSizeIndex {
+    pub fn compute(roots: &[PathBuf], one_file_system: bool, llaignore: LlaIgnoreMode) -> Self {
+        // The listing walk already explains what it hides.
+        let llaignore = match llaignore {
+            LlaIgnoreMode::Explain => LlaIgnoreMode::On,
+            mode => mode,
+        };
+        let mut roots: Vec<&PathBuf> = roots.iter().filter(|root| root.is_dir()).collect();
+        roots.sort();
+        // Roots nested inside another root are covered by its walk.
+        roots.dedup_by(|nested, outer| nested.starts_with(outer));
+
+        let mut index = Self::default();
+        for root in roots {
+            let device = if one_file_system {
+                device_id(root)
+            } else {
+                None
+            };
+            let excludes = ExcludeSet::llaignore(root, llaignore);
+            index.add_walk(walk_root(root, device, &excludes));
+        }
+        index
+    }
+
+    pub fn get(&self, path: &Path) -> Option<DirTotals> {
+        self.totals.get(path).copied()
+    }
+
+    /// Replaces the size fields of a directory entry with its totals.
+    pub fn apply(&self, path: &Path, metadata: &mut EntryMetadata) {
+        if let Some(totals) = self.get(path) {
+            metadata.size = totals.apparent;
+            metadata.allocated_size = totals.allocated;
+        }
+    }
+
+    /// Claims the walk's multiply-linked files in path order, then rolls
+    /// every directory's totals up into its parents.
+    fn add_walk(&mut self, mut walk: Walk) {
+        walk.linked.sort_by(|a, b| a.path.cmp(&b.path));
+        for file in walk.linked {
+            if self.seen.insert(file.identity) {
+                walk.directories[file.directory].totals.add(file.totals);
+            }
+        }
+
+        for index in (1..walk.directories.len()).rev() {
+            let directory = &walk.directories[index];
+            if let Some(parent) = directory.parent {
+                let totals = directory.totals;
+                walk.directories[parent].totals.add(totals);
+            }
+        }
+        self.totals.extend(
+            walk.directories
+                .into_iter()
+                .map(|directory| (directory.path, directory.totals)),
+        );
+    }
+}
+
+/// Directory totals for a streamed listing, filled in as batches arrive.
+///
+/// The subtree of a directory is walked when the directory is first listed.
+/// That walk also totals every directory below it, so later batches find
+/// their totals already there and nothing is walked twice. Hard links are
+/// claimed in batch order, then in path order within a batch.
+#[derive(Debug)]
+pub struct LazyDirSizes {
+    index: DirSizeIndex,
+    device: Option<u64>,
+    excludes: ExcludeSet,
+}
+
+impl LazyDirSizes {
+    pub fn new(root: &Path, one_file_system: bool, llaignore: LlaIgnoreMode) -> Self {
+        let llaignore = match llaignore {
+            LlaIgnoreMode::Explain => LlaIgnoreMode::On,
+            mode => mode,
+        };
+        Self {
+            index: DirSizeIndex::default(),
+            device: if one_file_system {
+                device_id(root)
+            } else {
+                None
+            },
+            excludes: ExcludeSet::llaignore(root, llaignore),
+        }
+    }
+
+    /// Totals the directories among `paths` that earlier batches did not
+    /// cover.
+    pub fn cover(&mut self, paths: &[PathBuf]) {
+        let directories: Vec<&PathBuf> = paths
+            .iter()
+            .filter(|path| {
+                self.index.get(path).is_none()
+                    && path
+                        .symlink_metadata()
+                        .is_ok_and(|metadata| metadata.is_dir())
+                    && (self.device.is_none() || device_id(path) == self.device)
+            })
+            .collect();
+        if directories.is_empty() {
+            return;
+        }
+
+        let walks: Vec<Walk> = directories
+            .into_par_iter()
+            .map(|directory| walk_root(directory, self.device, &self.excludes))
+            .collect();
+        let mut combined = Walk::default();
+        for walk in walks {
+            combined.append(walk, None);
+        }
+        self.index.add_walk(combined);
+    }
+
+    pub fn index(&self) -> &DirSizeIndex {
+        &self.index
+    }
+}
+
+/// A directory found by a walk, with the totals of the files directly in it
+/// until the walk is rolled up.
+struct WalkedDirectory {
+    path: PathBuf,
+    parent: Option<usize>,
+    totals: DirTotals,
+}
+
+/// A file with several hard links, counted only once its link is claimed.
+struct LinkedFile {
+    path: PathBuf,
+    directory: usize,
+    identity: (u64, u64),
+    totals: DirTotals,
+}
+
+/// Directories in discovery order, so every parent precedes its children and
+/// a single reverse pass rolls child totals up into their parents.
+#[derive(Default)]
+struct Walk {
+    directories: Vec<WalkedDirectory>,
+    linked: Vec<LinkedFile>,
+}
+
+impl Walk {
+    fn push(&mut self, path: PathBuf, parent: Option<usize>) {
+        self.directories.push(WalkedDirectory {
+            path,
+            parent,
+            totals: DirTotals::default(),
+        });
+    }
+
+    /// Appends another walk whose first directory is a child of `parent`, or
+    /// another root.
+    fn append(&mut self, other: Walk, parent: Option<usize>) {
+        let offset = self.directories.len();
+        self.directories
+            .extend(other.directories.into_iter().map(|mut directory| {
+                directory.parent = directory.parent.map(|p| p + offset).or(parent);
+                directory
+            }));
+        self.linked.extend(other.linked.into_iter().map(|mut file| {
+            file.directory += offset;
+            file
+        }));
+    }
+}
+
+/// Walks the root's subdirectories in parallel and combines their walks.
+fn walk_root(root: &Path, device: Option<u64>, excludes: &ExcludeSet) -> Walk {
+    let mut walk = Walk::default();
+    walk.push(root.to_path_buf(), None);
+    let mut subdirectories = Vec::new();
+    read_directory(&mut walk, 0, device, excludes, &mut subdirectories);
+
+    let subtrees: Vec<Walk> = subdirectories
+        .into_par_iter()
+        .map(|directory| walk_subtree(directory, device, excludes))
+        .collect();
+    for subtree in subtrees {
+        walk.append(subtree, Some(0));
+    }
+    walk
+}
+
+/// Walks one subtree sequentially.
+fn walk_subtree(root: PathBuf, device: Option<u64>, excludes: &ExcludeSet) -> Walk {
+    let mut walk = Walk::default();
+    walk.push(root, None);
+    let mut subdirectories = Vec::new();
+    let mut index = 0;
+    while index < walk.directories.len() {
+        read_directory(&mut walk, index, device, excludes, &mut subdirectories);
+        for path in subdirectories.drain(..) {
+            walk.push(path, Some(index));
+        }
+        index += 1;
+    }
+    walk
+}
+
+/// Adds the files directly inside directory `index` of the walk to its
+/// totals, or to the walk's linked files, and collects its subdirectories.
+/// Unreadable and excluded entries, and subdirectories on another device when
+/// `device` is set, are skipped.
+fn read_directory(
+    walk: &mut Walk,
+    index: usize,
+    device: Option<u64>,
+    excludes: &ExcludeSet,
+    subdirectories: &mut Vec<PathBuf>,
+) {
+    let Ok(entries) = fs::read_dir(&walk.directories[index].path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            continue;
+        }
+        if !excludes.is_empty() && excludes.contains_entry(&entry.path(), file_type.is_dir()) {
+            continue;
+        }
+        if file_type.is_dir() {
+            let path = entry.path();
+            if device.is_none() || device_id(&path) == device {
+                subdirectories.push(path);
+            }
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let totals = DirTotals {
+            apparent: metadata.len(),
+            allocated: metadata_allocated_size(&metadata),
+        };
+        match hard_link_identity(&metadata) {
+            Some(identity) => walk.linked.push(LinkedFile {
+                path: entry.path(),
+                directory: index,
+                identity,
+                totals,
+            }),
+            None => walk.directories[index].totals.add(totals),
+        }
+    }
+}
+
+/// (device, inode) for files with more than one link, which must only be
+/// counted once.
+#[cfg(unix)]
+fn hard_link_identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(windows)]
+fn hard_link_identity(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iterative_directory_size_counts_files_and_skips_symlinks() {
+        let root = tempfile::tempdir().unwrap();
+        let nested = root.path().join("nested");
+        fs::create_dir(&nested).unwrap();
+        fs::write(root.path().join("one"), [0u8; 7]).unwrap();
+        fs::write(nested.join("two"), [0u8; 11]).unwrap();
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root.path().join("one"), nested.join("link")).unwrap();
+
+        let index = DirSizeIndex::compute(&[root.path().to_path_buf()], true, LlaIgnoreMode::Off);
+        assert_eq!(index.get(root.path()).unwrap().apparent, 18);
+        assert_eq!(index.get(&nested).unwrap().apparent, 11);
+    }
+
+    #[test]
+    fn totals_every_level_from_one_walk() {
+        let root = tempfile::tempdir().unwrap();
+        let deep = root.path().join("a/b/c");
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(root.path().join("a/one"), [0u8; 1]).unwrap();
+        fs::write(root.path().join("a/b/two"), [0u8; 2]).unwrap();
+        fs::write(deep.join("four"), [0u8; 4]).unwrap();
+
+        // Nested roots are folded into the outer walk.
+        let index = DirSizeIndex::compute(
+            &[root.path().join("a"), root.path().join("a/b")],
+            true,
+            LlaIgnoreMode::Off,
+        );
+        assert_eq!(index.get(&root.path().join("a")).unwrap().apparent, 7);
+        assert_eq!(index.get(&root.path().join("a/b")).unwrap().apparent, 6);
+        assert_eq!(index.get(&deep).unwrap().apparent, 4);
+        assert!(index.get(root.path()).is_none());
+    }
+
+    #[test]
+    fn lazy_totals_match_a_single_walk() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("a/b")).unwrap();
+        fs::write(root.path().join("a/one"), [0u8; 1]).unwrap();
+        fs::write(root.path().join("a/b/two"), [0u8; 2]).unwrap();
+
+        let mut lazy = LazyDirSizes::new(root.path(), false, LlaIgnoreMode::Off);
+        lazy.cover(&[root.path().join("a"), root.path().join("a/one")]);
+        assert_eq!(
+            lazy.index().get(&root.path().join("a")).unwrap().apparent,
+            3
+        );
+        // Covered by the walk of `a`, so the next batch walks nothing.
+        assert_eq!(
+            lazy.index().get(&root.path().join("a/b")).unwrap().apparent,
+            2
+        );
+        assert!(lazy.index().get(root.path()).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn counts_hard_links_once() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir(root.path().join("left")).unwrap();
+        fs::create_dir(root.path().join("right")).unwrap();
+        fs::write(root.path().join("left/data"), [0u8; 100]).unwrap();
+        fs::hard_link(
+            root.path().join("left/data"),
+            root.path().join("right/data"),
+        )
+        .unwrap();
+
+        let index = DirSizeIndex::compute(&[root.path().to_path_buf()], true, LlaIgnoreMode::Off);
+        let total = index.get(root.path()).unwrap();
+        assert_eq!(total.apparent, 100);
+        assert!(total.allocated >= total.apparent || total.allocated == 0);
+        // The link whose path sorts first is the one counted.
+        assert_eq!(index.get(&root.path().join("left")).unwrap().apparent, 100);
+        assert_eq!(index.get(&root.path().join("right")).unwrap().apparent, 0);
+    }
+}
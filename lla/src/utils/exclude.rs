@@ -0,0 +1,173 @@
+use crate::config::Config;
+use crate::utils::llaignore::{LlaIgnore, LlaIgnoreMode};
+use std::path::{Component, Path, PathBuf};
+
+/// `exclude_paths` and `.llaignore` rules resolved once per walk so traversals
+/// can prune excluded subtrees before opening them.
+///
+/// Walkers hand out paths relative to the root they were started from. Rather
+/// than canonicalizing every entry, the root is canonicalized once and entries
+/// below it are mapped onto that absolute prefix lexically.
+#[derive(Clone, Debug, Default)]
+pub struct ExcludeSet {
+    prefixes: Vec<PathBuf>,
+    root: PathBuf,
+    canonical_root: PathBuf,
+    llaignore: LlaIgnore,
+}
+
+impl ExcludeSet {
+    pub fn new(config: &Config, root: &Path, llaignore: LlaIgnoreMode) -> Self {
+        if config.exclude_paths.is_empty() && llaignore == LlaIgnoreMode::Off {
+            return Self::default();
+        }
+
+        let prefixes = config
+            .exclude_paths
+            .iter()
+            .map(|p| p.canonicalize().unwrap_or_else(|_| p.clone()))
+            .collect();
+        Self {
+            prefixes,
+            root: root.to_path_buf(),
+            canonical_root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+            llaignore: LlaIgnore::new(llaignore),
+        }
+    }
+
+    /// Only `.llaignore` rules, for walks that do not apply `exclude_paths`.
+    pub fn llaignore(root: &Path, llaignore: LlaIgnoreMode) -> Self {
+        Self::new(&Config::default(), root, llaignore)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty() && !self.llaignore.is_enabled()
+    }
+
+    /// Whether `path` is, or lives under, an excluded prefix or a directory
+    /// hidden by `.llaignore`.
+    pub fn contains(&self, path: &Path) -> bool {
+        self.excludes(path, &|| {
+            std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir())
+        })
+    }
+
+    /// Like `contains`, for walkers that already know the entry's type.
+    pub fn contains_entry(&self, path: &Path, is_dir: bool) -> bool {
+        self.excludes(path, &|| is_dir)
+    }
+
+    fn excludes(&self, path: &Path, is_dir: &dyn Fn() -> bool) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let absolute = self.absolute(path);
+        if self.prefixes.iter().any(|ex| absolute.starts_with(ex)) {
+            return true;
+        }
+        if !self.llaignore.is_enabled() || absolute == self.canonical_root {
+            return false;
+        }
+
+        // Directories between the root and the path are checked too, so the
+        // contents of a hidden directory stay hidden when they are reached
+        // without walking it, as with path lists and git trees. Walkers hit
+        // the directory cache here.
+        let parents: Vec<&Path> = absolute
+            .ancestors()
+            .skip(1)
+            .take_while(|parent| {
+                parent.starts_with(&self.canonical_root) && *parent != self.canonical_root
+            })
+            .collect();
+        for parent in parents.into_iter().rev() {
+            if self
+                .llaignore
+                .is_ignored(parent, &|| true, &|| self.shown(parent))
+            {
+                return true;
+            }
+        }
+        self.llaignore
+            .is_ignored(&absolute, is_dir, &|| self.shown(&absolute))
+    }
+
+    /// The path as the walk named it, for explain output.
+    fn shown(&self, absolute: &Path) -> PathBuf {
+        match absolute.strip_prefix(&self.canonical_root) {
+            Ok(relative) => self.root.join(relative),
+            Err(_) => absolute.to_path_buf(),
+        }
+    }
+
+    fn absolute(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(&self.root) {
+            Ok(relative) => {
+                let mut absolute = self.canonical_root.clone();
+                for component in relative.components() {
+                    match component {
+                        Component::CurDir => {}
+                        Component::ParentDir => {
+                            absolute.pop();
+                        }
+                        other => absolute.push(other),
+                    }
+                }
+                absolute
+            }
+            Err(_) => path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_entries_below_relative_roots() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("keep")).unwrap();
+        std::fs::create_dir_all(root.path().join("skip/nested")).unwrap();
+
+        let config = Config {
+            exclude_paths: vec![root.path().join("skip")],
+            ..Config::default()
+        };
+        let excludes = ExcludeSet::new(&config, root.path(), LlaIgnoreMode::Off);
+
+        assert!(excludes.contains(&root.path().join("skip")));
+        assert!(excludes.contains(&root.path().join("skip/nested/file")));
+        assert!(!excludes.contains(&root.path().join("keep")));
+        assert!(!excludes.contains(&root.path().join("skipped")));
+    }
+
+    #[test]
+    fn empty_config_excludes_nothing() {
+        let excludes = ExcludeSet::new(&Config::default(), Path::new("."), LlaIgnoreMode::Off);
+        assert!(excludes.is_empty());
+        assert!(!excludes.contains(Path::new("./anything")));
+    }
+
+    #[test]
+    fn nested_llaignore_files_hide_and_reinclude() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("build/keep")).unwrap();
+        std::fs::create_dir_all(root.path().join("src/gen")).unwrap();
+        std::fs::write(root.path().join(".llaignore"), "build/\n*.log\n").unwrap();
+        std::fs::write(root.path().join("src/.llaignore"), "gen\n!debug.log\n").unwrap();
+
+        let excludes = ExcludeSet::llaignore(root.path(), LlaIgnoreMode::On);
+        assert!(excludes.contains_entry(&root.path().join("build"), true));
+        assert!(excludes.contains(&root.path().join("build/keep/main.rs")));
+        assert!(excludes.contains_entry(&root.path().join("src/gen"), true));
+        assert!(excludes.contains_entry(&root.path().join("trace.log"), false));
+        assert!(excludes.contains_entry(&root.path().join("src/trace.log"), false));
+        assert!(!excludes.contains_entry(&root.path().join("src/debug.log"), false));
+        assert!(!excludes.contains_entry(&root.path().join("src/main.rs"), false));
+
+        let disabled = ExcludeSet::llaignore(root.path(), LlaIgnoreMode::Off);
+        assert!(disabled.is_empty());
+        assert!(!disabled.contains(&root.path().join("build")));
+    }
+}
@@ -1,6 +1,6 @@
-use lla_plugin_interface::proto::EntryMetadata;
+use lla_plugin_interface::proto::{EntryMetadata, FileKind};
 use once_cell::sync::Lazy;
-use std::fs::Metadata;
+use std::fs::{FileType, Metadata};
 #[cfg(target_os = "macos")]
 use std::os::unix::ffi::OsStrExt;
 #[cfg(unix)]
@@ -34,10 +34,58 @@ pub fn from_metadata(metadata: &Metadata) -> EntryMetadata {
         inode: metadata_inode(metadata),
         hard_links: metadata_hard_links(metadata),
         allocated_size: metadata_allocated_size(metadata),
+        kind: file_kind(&metadata.file_type()) as i32,
         ..EntryMetadata::default()
     }
 }
 
+#[cfg(unix)]
+pub fn file_kind(file_type: &FileType) -> FileKind {
+    use std::os::unix::fs::FileTypeExt;
+
+    if file_type.is_symlink() {
+        FileKind::Symlink
+    } else if file_type.is_dir() {
+        FileKind::Directory
+    } else if file_type.is_file() {
+        FileKind::Regular
+    } else if file_type.is_fifo() {
+        FileKind::Fifo
+    } else if file_type.is_socket() {
+        FileKind::Socket
+    } else if file_type.is_block_device() {
+        FileKind::BlockDevice
+    } else if file_type.is_char_device() {
+        FileKind::CharDevice
+    } else {
+        FileKind::Unknown
+    }
+}
+
+#[cfg(windows)]
+pub fn file_kind(file_type: &FileType) -> FileKind {
+    if file_type.is_symlink() {
+        FileKind::Symlink
+    } else if file_type.is_dir() {
+        FileKind::Directory
+    } else if file_type.is_file() {
+        FileKind::Regular
+    } else {
+        FileKind::Unknown
+    }
+}
+
+/// Kind encoded in the `S_IFMT` bits of a POSIX mode, as stored by archives.
+pub fn kind_from_mode(mode: u32) -> FileKind {
+    FileKind::from_mode(mode)
+}
+
+/// The entry's kind, falling back to the boolean flags for metadata that was
+/// produced without one, such as entries decorated by older plugins.
+pub fn entry_kind(metadata: &EntryMetadata) -> FileKind {
+    metadata.file_kind()
+}
+
 #[cfg(unix)]
 pub fn permission_mode(metadata: &Metadata) -> u32 {
     metadata.mode()
@@ -104,15 +152,26 @@ fn metadata_hard_links(_metadata: &Metadata) -> u64 {
 }
 
 #[cfg(unix)]
-fn metadata_allocated_size(metadata: &Metadata) -> u64 {
+pub fn metadata_allocated_size(metadata: &Metadata) -> u64 {
     metadata.blocks().saturating_mul(512)
 }
 
 #[cfg(windows)]
-fn metadata_allocated_size(_metadata: &Metadata) -> u64 {
+pub fn metadata_allocated_size(_metadata: &Metadata) -> u64 {
     0
 }
 
+/// Device the path lives on, used to keep walks on a single filesystem.
+#[cfg(unix)]
+pub fn device_id(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+#[cfg(windows)]
+pub fn device_id(_path: &Path) -> Option<u64> {
+    None
+}
+
 fn timestamp(value: std::io::Result<std::time::SystemTime>) -> u64 {
     value
         .ok()
@@ -313,6 +372,18 @@ fn mount_for(path: &Path) -> Option<&'static MountInfo> {
         .max_by_key(|mount| mount.point.as_os_str().len())
 }
 
+/// File system type mounted exactly at `path`, if the path is a mount point.
+pub fn mounted_filesystem(path: &Path) -> Option<&'static str> {
+    let absolute = path.canonicalize().ok()?;
+
+    // When several file systems are stacked on one point, the last is visible.
+    MOUNTS
+        .iter()
+        .rev()
+        .find(|mount| mount.point == absolute)
+        .map(|mount| mount.filesystem.as_str())
+}
+
 fn load_mounts() -> Vec<MountInfo> {
     #[cfg(target_os = "linux")]
     if let Ok(contents) = std::fs::read_to_string("/proc/self/mountinfo") {
@@ -404,6 +475,37 @@ mod tests {
         assert_eq!(mounts[0].filesystem, "apfs");
     }
 
+    #[test]
+    fn kind_from_mode_reads_file_type_bits() {
+        assert_eq!(kind_from_mode(0o100644), FileKind::Regular);
+        assert_eq!(kind_from_mode(0o040755), FileKind::Directory);
+        assert_eq!(kind_from_mode(0o120777), FileKind::Symlink);
+        assert_eq!(kind_from_mode(0o010644), FileKind::Fifo);
+        assert_eq!(kind_from_mode(0o644), FileKind::Unknown);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn classifies_sockets() {
+        let directory = tempfile::tempdir().unwrap();
+        let socket = directory.path().join("socket");
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        let metadata = from_metadata(&fs::symlink_metadata(&socket).unwrap());
+        assert_eq!(metadata.kind(), FileKind::Socket);
+        assert!(!metadata.is_file);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn recognizes_mount_points() {
+        assert!(mounted_filesystem(Path::new("/")).is_some());
+        let directory = tempfile::tempdir().unwrap();
+        let nested = directory.path().join("nested");
+        fs::create_dir(&nested).unwrap();
+        assert!(mounted_filesystem(&nested).is_none());
+    }
+
     #[test]
     #[cfg(unix)]
     fn captures_inode_links_allocated_size_and_xattrs() {
@@ -1,5 +1,6 @@
 use crate::utils::color::colorize_file_name_with_icon;
 use colored::Colorize;
+use lla_plugin_interface::proto::EntryMetadata;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::path::Path;
@@ -460,11 +461,16 @@ fn get_special_file_icon(filename: &str) -> Option<&'static str> {
     }
 }
 
-pub fn format_with_icon(path: &Path, name: String, show_icons: bool) -> String {
+pub fn format_with_icon(
+    path: &Path,
+    metadata: Option<&EntryMetadata>,
+    name: String,
+    show_icons: bool,
+) -> String {
     if show_icons {
         let icon_str = get_icon(path).bold();
         let with_icon = format!("{} {}", icon_str, name);
-        colorize_file_name_with_icon(path, with_icon).to_string()
+        colorize_file_name_with_icon(path, metadata, with_icon).to_string()
     } else {
         name
     }
@@ -0,0 +1,130 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+pub const FILE_NAME: &str = ".llaignore";
+
+/// Whether `.llaignore` files are honored, and whether hidden paths are
+/// reported on stderr.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LlaIgnoreMode {
+    Off,
+    #[default]
+    On,
+    Explain,
+}
+
+/// `.llaignore` files for one walk, with gitignore syntax.
+///
+/// Every directory may hold a `.llaignore`, and its rules apply to the entries
+/// below it, including directories above the walk root. Files are read lazily
+/// the first time a directory is looked at and cached, so a walk reads each
+/// one at most once. When several files have a rule for a path, the deepest
+/// one decides, so `!pattern` in a nested file re-includes what a parent
+/// hid.
+#[derive(Clone, Debug, Default)]
+pub struct LlaIgnore {
+    rules: Option<Arc<Rules>>,
+}
+
+#[derive(Debug, Default)]
+struct Rules {
+    explain: bool,
+    matchers: RwLock<HashMap<PathBuf, Option<Arc<Gitignore>>>>,
+    directories: RwLock<HashMap<PathBuf, bool>>,
+}
+
+impl LlaIgnore {
+    pub fn new(mode: LlaIgnoreMode) -> Self {
+        let rules = (mode != LlaIgnoreMode::Off).then(|| {
+            Arc::new(Rules {
+                explain: mode == LlaIgnoreMode::Explain,
+                ..Rules::default()
+            })
+        });
+        Self { rules }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.rules.is_some()
+    }
+
+    /// Whether the absolute `path` is hidden by a rule. `shown` gives the path
+    /// reported in explain mode. Directories are cached, since their
+    /// descendants ask about them again.
+    pub fn is_ignored(
+        &self,
+        path: &Path,
+        is_dir: &dyn Fn() -> bool,
+        shown: &dyn Fn() -> PathBuf,
+    ) -> bool {
+        let Some(rules) = &self.rules else {
+            return false;
+        };
+        if let Some(&ignored) = rules.directories.read().unwrap().get(path) {
+            return ignored;
+        }
+
+        let matchers: Vec<Arc<Gitignore>> = path
+            .ancestors()
+            .skip(1)
+            .filter_map(|directory| rules.matcher(directory))
+            .collect();
+        if matchers.is_empty() {
+            return false;
+        }
+
+        let is_dir = is_dir();
+        let ignored = match matchers
+            .iter()
+            .map(|matcher| matcher.matched(path, is_dir))
+            .find(|matched| !matched.is_none())
+        {
+            Some(Match::Ignore(glob)) => {
+                if rules.explain {
+                    let file = glob.from().unwrap_or(Path::new(FILE_NAME));
+                    eprintln!(
+                        "{}: hidden by '{}' in {}",
+                        shown().display(),
+                        glob.original(),
+                        file.display()
+                    );
+                }
+                true
+            }
+            _ => false,
+        };
+        if is_dir {
+            rules
+                .directories
+                .write()
+                .unwrap()
+                .insert(path.to_path_buf(), ignored);
+        }
+        ignored
+    }
+}
+
+impl Rules {
+    fn matcher(&self, directory: &Path) -> Option<Arc<Gitignore>> {
+        if let Some(cached) = self.matchers.read().unwrap().get(directory) {
+            return cached.clone();
+        }
+
+        let file = directory.join(FILE_NAME);
+        let matcher = file.is_file().then(|| {
+            let mut builder = GitignoreBuilder::new(directory);
+            if let Some(error) = builder.add(&file) {
+                eprintln!("Warning: {}: {}", file.display(), error);
+            }
+            Arc::new(builder.build().unwrap_or_else(|_| Gitignore::empty()))
+        });
+        self.matchers
+            .write()
+            .unwrap()
+            .insert(directory.to_path_buf(), matcher.clone());
+        matcher
+    }
+}
@@ -1,5 +1,9 @@
 pub mod cache;
 pub mod color;
+pub mod content_type;
+pub mod dir_size;
+pub mod exclude;
 pub mod fs_metadata;
 pub mod hyperlink;
 pub mod icons;
+pub mod llaignore;
@@ -5,6 +5,7 @@ use prost::Message as _;
 use std::collections::HashMap;
 
 pub use lla_plugin_interface as interface;
+pub use lla_plugin_interface::proto::FileKind;
 #[cfg(feature = "component")]
 pub use lla_plugin_sdk_macros::export_component;
 pub use lla_plugin_sdk_macros::export_plugin;
@@ -31,6 +32,11 @@ pub trait DecoratedEntryExt {
     fn promote_integer_field(&mut self, name: &str);
     fn promote_boolean_field(&mut self, name: &str);
     fn promote_path_field(&mut self, name: &str);
+
+    /// The entry's file kind. Hosts that predate `EntryMetadata.kind` only send
+    /// the `is_dir`/`is_file`/`is_symlink` flags, so the kind is derived from
+    /// them in that case.
+    fn file_kind(&self) -> FileKind;
 }
 
 impl DecoratedEntryExt for proto::DecoratedEntry {
@@ -80,6 +86,12 @@ impl DecoratedEntryExt for proto::DecoratedEntry {
                 .insert(name.to_string(), value::path(display));
         }
     }
+
+    fn file_kind(&self) -> FileKind {
+        self.metadata
+            .as_ref()
+            .map_or(FileKind::Unknown, proto::EntryMetadata::file_kind)
+    }
 }
 
 /// Ergonomic, type-checked access to arguments already validated by the host.
@@ -662,6 +674,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn file_kind_falls_back_to_legacy_flags() {
+        let mut entry = proto::DecoratedEntry {
+            metadata: Some(proto::EntryMetadata {
+                is_dir: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(entry.file_kind(), FileKind::Directory);
+
+        entry.metadata.as_mut().unwrap().kind = FileKind::Socket as i32;
+        assert_eq!(entry.file_kind(), FileKind::Socket);
+        assert_eq!(
+            proto::DecoratedEntry::default().file_kind(),
+            FileKind::Unknown
+        );
+    }
+
     #[test]
     fn native_batch_override_executes_once() {
         let entries = (0..8)